@@ -92,6 +92,20 @@ impl ModelManager {
         }
     }
 
+    /// Generate embeddings for `(message_id, text)` pairs in batches of
+    /// `batch_size`. See [`embed_in_batches`].
+    pub fn embed_messages(
+        &self,
+        items: &[(i64, String)],
+        batch_size: usize,
+    ) -> AnalysisResult<Vec<(i64, Vec<f32>)>> {
+        let model = self
+            .embedding_model
+            .as_deref()
+            .ok_or_else(|| AnalysisError::ModelLoading("Embedding model not loaded".to_string()))?;
+        Ok(embed_in_batches(model, items, batch_size))
+    }
+
     /// Predict sentiment for texts.
     pub fn predict_sentiment(&self, texts: &[String]) -> AnalysisResult<Vec<SentimentPrediction>> {
         if let Some(model) = &self.sentiment_model {
@@ -148,6 +162,173 @@ pub trait EmbeddingModel: Send + Sync {
     fn name(&self) -> &str;
 }
 
+/// Default number of texts sent to an embedding model per call.
+pub const DEFAULT_EMBED_BATCH_SIZE: usize = 32;
+
+/// Embeds `(message_id, text)` pairs with one model call per batch and maps
+/// the vectors back to their message ids.
+///
+/// When a batch call fails, or returns the wrong number of vectors, its items
+/// are retried one by one; items that still fail are left out of the result.
+pub fn embed_in_batches(
+    model: &dyn EmbeddingModel,
+    items: &[(i64, String)],
+    batch_size: usize,
+) -> Vec<(i64, Vec<f32>)> {
+    let mut out = Vec::with_capacity(items.len());
+    for chunk in items.chunks(batch_size.max(1)) {
+        let texts: Vec<String> = chunk.iter().map(|(_, text)| text.clone()).collect();
+        match model.embed(&texts) {
+            Ok(vectors) if vectors.len() == chunk.len() => {
+                out.extend(chunk.iter().map(|(id, _)| *id).zip(vectors));
+            }
+            _ => {
+                for (id, text) in chunk {
+                    if let Ok(mut vectors) = model.embed(std::slice::from_ref(text)) {
+                        if let Some(vector) = vectors.pop() {
+                            out.push((*id, vector));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Environment variable naming the model served by the embedding endpoint.
+/// Setting it enables [`HttpEmbeddingConfig::from_env`].
+pub const EMBEDDING_MODEL_ENV: &str = "XENOBOT_EMBEDDING_MODEL";
+/// Environment variable with the endpoint's base URL.
+pub const EMBEDDING_BASE_URL_ENV: &str = "XENOBOT_EMBEDDING_BASE_URL";
+/// Environment variable with the endpoint's API key.
+pub const EMBEDDING_API_KEY_ENV: &str = "XENOBOT_EMBEDDING_API_KEY";
+/// Base URL used when [`EMBEDDING_BASE_URL_ENV`] is unset.
+pub const DEFAULT_EMBEDDING_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Connection settings for an OpenAI-compatible `/embeddings` endpoint
+/// (OpenAI, Ollama, LM Studio, vLLM, ...).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpEmbeddingConfig {
+    /// Base URL the `/embeddings` path is appended to.
+    pub base_url: String,
+    /// Model name sent with each request.
+    pub model: String,
+    /// Bearer token; local servers usually need none.
+    pub api_key: Option<String>,
+}
+
+impl HttpEmbeddingConfig {
+    /// Read settings from the process environment. `None` unless
+    /// [`EMBEDDING_MODEL_ENV`] is set.
+    pub fn from_env() -> Option<Self> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Read settings through `lookup`, which returns a variable's value.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let value = |name: &str| {
+            lookup(name)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        Some(Self {
+            model: value(EMBEDDING_MODEL_ENV)?,
+            base_url: value(EMBEDDING_BASE_URL_ENV)
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| DEFAULT_EMBEDDING_BASE_URL.to_string()),
+            api_key: value(EMBEDDING_API_KEY_ENV),
+        })
+    }
+}
+
+/// Embedding model behind an OpenAI-compatible HTTP endpoint.
+///
+/// Calls block on a private runtime, so [`EmbeddingModel::embed`] must not be
+/// called from inside another tokio runtime.
+pub struct HttpEmbeddingModel {
+    client: async_openai::Client<async_openai::config::OpenAIConfig>,
+    model: String,
+    dim: usize,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl HttpEmbeddingModel {
+    /// Connect to the endpoint and learn the vector dimension from one probe
+    /// request, so a wrong URL, key or model fails here rather than mid-search.
+    pub fn connect(config: HttpEmbeddingConfig) -> AnalysisResult<Self> {
+        let http = xenobot_core::http_client::outbound_client_builder()
+            .and_then(|builder| {
+                builder
+                    .timeout(std::time::Duration::from_secs(60))
+                    .build()
+                    .map_err(|e| xenobot_core::Error::Config(e.to_string()))
+            })
+            .map_err(|e| AnalysisError::Config(e.to_string()))?;
+        let mut openai_config =
+            async_openai::config::OpenAIConfig::new().with_api_base(config.base_url);
+        if let Some(api_key) = config.api_key {
+            openai_config = openai_config.with_api_key(api_key);
+        }
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let mut model = Self {
+            client: async_openai::Client::with_config(openai_config).with_http_client(http),
+            model: config.model,
+            dim: 0,
+            runtime,
+        };
+        let probe = model.embed(&["dimension probe".to_string()])?;
+        model.dim = probe.first().map(Vec::len).unwrap_or_default();
+        if model.dim == 0 {
+            return Err(AnalysisError::Embedding(format!(
+                "model '{}' returned an empty embedding",
+                model.model
+            )));
+        }
+        Ok(model)
+    }
+}
+
+impl EmbeddingModel for HttpEmbeddingModel {
+    fn embed(&self, texts: &[String]) -> AnalysisResult<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let request = async_openai::types::CreateEmbeddingRequestArgs::default()
+            .model(self.model.as_str())
+            .input(texts.to_vec())
+            .build()
+            .map_err(|e| AnalysisError::InvalidInput(e.to_string()))?;
+        let response = self
+            .runtime
+            .block_on(self.client.embeddings().create(request))
+            .map_err(|e| AnalysisError::ExternalApi(e.to_string()))?;
+        if response.data.len() != texts.len() {
+            return Err(AnalysisError::Embedding(format!(
+                "expected {} embeddings, endpoint returned {}",
+                texts.len(),
+                response.data.len()
+            )));
+        }
+        let mut data = response.data;
+        data.sort_by_key(|embedding| embedding.index);
+        Ok(data
+            .into_iter()
+            .map(|embedding| embedding.embedding)
+            .collect())
+    }
+
+    fn dimension(&self) -> usize {
+        self.dim
+    }
+
+    fn name(&self) -> &str {
+        &self.model
+    }
+}
+
 /// Trait for classification models.
 pub trait ClassificationModel: Send + Sync {
     /// Predict classes for texts.
@@ -612,6 +793,89 @@ mod tests {
         assert_eq!(embeddings[0].len(), 64);
     }
 
+    struct CountingEmbedder {
+        calls: std::sync::atomic::AtomicUsize,
+        fail_batches: bool,
+    }
+
+    impl EmbeddingModel for CountingEmbedder {
+        fn embed(&self, texts: &[String]) -> AnalysisResult<Vec<Vec<f32>>> {
+            self.calls
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            if self.fail_batches && texts.len() > 1 {
+                return Err(AnalysisError::Ai("batch rejected".to_string()));
+            }
+            if texts.iter().any(|text| text == "poison") {
+                return Err(AnalysisError::Ai("bad input".to_string()));
+            }
+            Ok(texts.iter().map(|text| vec![text.len() as f32]).collect())
+        }
+
+        fn dimension(&self) -> usize {
+            1
+        }
+
+        fn name(&self) -> &str {
+            "counting"
+        }
+    }
+
+    #[test]
+    fn http_embedding_config_needs_a_model() {
+        let lookup = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert_eq!(
+            HttpEmbeddingConfig::from_lookup(lookup(&[(EMBEDDING_BASE_URL_ENV, "http://x")])),
+            None
+        );
+        let config = HttpEmbeddingConfig::from_lookup(lookup(&[
+            (EMBEDDING_MODEL_ENV, " nomic-embed-text "),
+            (EMBEDDING_BASE_URL_ENV, "http://localhost:11434/v1/"),
+            (EMBEDDING_API_KEY_ENV, ""),
+        ]))
+        .expect("configured");
+        assert_eq!(config.model, "nomic-embed-text");
+        assert_eq!(config.base_url, "http://localhost:11434/v1");
+        assert_eq!(config.api_key, None);
+    }
+
+    #[test]
+    fn embed_in_batches_uses_one_call_per_batch() {
+        let model = CountingEmbedder {
+            calls: Default::default(),
+            fail_batches: false,
+        };
+        let items: Vec<(i64, String)> = (1..=5).map(|id| (id, "x".repeat(id as usize))).collect();
+
+        let out = embed_in_batches(&model, &items, 5);
+        assert_eq!(model.calls.load(std::sync::atomic::Ordering::Relaxed), 1);
+        assert_eq!(out.len(), 5);
+        assert_eq!(out[3], (4, vec![4.0]));
+    }
+
+    #[test]
+    fn embed_in_batches_falls_back_to_single_items_on_batch_error() {
+        let model = CountingEmbedder {
+            calls: Default::default(),
+            fail_batches: true,
+        };
+        let items = vec![
+            (10, "ok".to_string()),
+            (11, "poison".to_string()),
+            (12, "fine".to_string()),
+        ];
+
+        let out = embed_in_batches(&model, &items, 3);
+        assert_eq!(model.calls.load(std::sync::atomic::Ordering::Relaxed), 4);
+        let ids: Vec<i64> = out.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![10, 12]);
+    }
+
     #[test]
     fn sentiment_model_detects_positive_and_negative_terms() {
         let model = LexiconClassificationModel;
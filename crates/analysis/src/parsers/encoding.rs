@@ -0,0 +1,82 @@
+//! Text decoding for line-based exports.
+//!
+//! Older desktop clients save chat logs in the system code page (GBK,
+//! Shift_JIS, Windows-1252, ...) rather than UTF-8. Text parsers read through
+//! here so the encoding is detected once and reported with the parsed chat.
+
+use super::ParseError;
+use encoding_rs::Encoding;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Encoding a text export was decoded with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceEncoding {
+    /// WHATWG encoding name, e.g. `UTF-8` or `GBK`.
+    pub name: String,
+    /// False when detection had no clear winner; content may be mis-decoded.
+    pub confident: bool,
+    /// Chosen by the caller instead of detected.
+    pub forced: bool,
+}
+
+/// Look up an encoding by label (`utf-8`, `gbk`, `shift_jis`, `latin1`, ...).
+pub fn encoding_for_label(label: &str) -> Result<&'static Encoding, ParseError> {
+    Encoding::for_label(label.trim().as_bytes())
+        .ok_or_else(|| ParseError::InvalidFormat(format!("unknown encoding '{}'", label.trim())))
+}
+
+/// Read `path` as text, decoding with `forced` or else a detected encoding.
+pub(crate) fn read_text_file(
+    path: &Path,
+    forced: Option<&'static Encoding>,
+) -> Result<(String, SourceEncoding), ParseError> {
+    let bytes = std::fs::read(path)?;
+    Ok(decode_text(&bytes, forced))
+}
+
+/// A BOM or valid UTF-8 is trusted as-is; anything else is guessed from byte
+/// statistics, which is unreliable on short or mostly-ASCII input.
+fn decode_text(bytes: &[u8], forced: Option<&'static Encoding>) -> (String, SourceEncoding) {
+    let (encoding, confident) = match forced {
+        Some(encoding) => (encoding, true),
+        None => match Encoding::for_bom(bytes) {
+            Some((encoding, _)) => (encoding, true),
+            None if std::str::from_utf8(bytes).is_ok() => (encoding_rs::UTF_8, true),
+            None => {
+                let mut detector = chardetng::EncodingDetector::new();
+                detector.feed(bytes, true);
+                detector.guess_assess(None, false)
+            }
+        },
+    };
+    let (text, _) = encoding.decode_with_bom_removal(bytes);
+    (
+        text.into_owned(),
+        SourceEncoding {
+            name: encoding.name().to_string(),
+            confident,
+            forced: forced.is_some(),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_legacy_encodings_and_strips_boms() {
+        let (encoded, _, _) =
+            encoding_rs::GBK.encode("[2024-03-01 10:00:00] 张三: 大家好，今天下午开会");
+        let (text, detected) = decode_text(&encoded, None);
+        assert_eq!(detected.name, "GBK");
+        assert!(!detected.forced);
+        assert!(text.contains("张三"));
+
+        let (text, detected) = decode_text("\u{feff}hello".as_bytes(), None);
+        assert_eq!(text, "hello");
+        assert_eq!(detected.name, "UTF-8");
+        assert!(detected.confident);
+    }
+}
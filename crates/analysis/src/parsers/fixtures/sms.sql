@@ -0,0 +1,3 @@
+CREATE TABLE sms (_id INTEGER PRIMARY KEY, address TEXT, date INTEGER, body TEXT, type INTEGER);
+INSERT INTO sms VALUES (1, '+15550001', 1735813230000, 'hello sms', 1);
+INSERT INTO sms VALUES (2, '+15550001', 1735813290000, 'hi back', 2);
@@ -1,8 +1,19 @@
+use encoding::read_text_file;
+use encoding_rs::Encoding;
+use salvage::parse_json_export;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use thiserror::Error;
 use tracing::{info, warn};
 
+mod encoding;
+mod salvage;
+mod selftest;
+
+pub use encoding::{encoding_for_label, SourceEncoding};
+pub use salvage::JsonSalvage;
+pub use selftest::{FixtureStatus, ParserFixtureReport};
+
 /// Errors that can occur during chat parsing.
 #[derive(Error, Debug)]
 pub enum ParseError {
@@ -30,16 +41,27 @@ pub struct ParsedMessage {
     pub sender: String,
     /// Optional display name of the sender.
     pub sender_name: Option<String>,
+    /// Per-group name (room nickname, server nickname) when the export
+    /// records one that differs from `sender_name`.
+    #[serde(default)]
+    pub sender_group_nickname: Option<String>,
     /// Unix timestamp of the message.
     pub timestamp: i64,
     /// Message content.
     pub content: String,
     /// Type of message.
     pub msg_type: MessageType,
+    /// Unix timestamp of the latest edit, for exports that record edits.
+    #[serde(default)]
+    pub edited_at: Option<i64>,
+    /// Native message id, for exports that carry one. Import dedups on it
+    /// instead of on content when present.
+    #[serde(default)]
+    pub platform_message_id: Option<String>,
 }
 
 /// Type of chat message.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MessageType {
     /// Plain text message.
     Text,
@@ -74,10 +96,30 @@ pub struct ParsedChat {
     pub messages: Vec<ParsedMessage>,
     /// List of members in the chat.
     pub members: Vec<ChatMember>,
+    /// How a text export was decoded; `None` for structured or binary formats.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<SourceEncoding>,
+    /// Set when a truncated JSON export was parsed in salvage mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salvage: Option<JsonSalvage>,
+    /// True when the export itself states the chat type (e.g. a Discord DM
+    /// channel). Configured platform defaults never override it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub chat_type_known: bool,
+}
+
+/// Caller choices that change how a file is read.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions {
+    /// Decode text exports with this encoding instead of detecting it.
+    pub encoding: Option<&'static Encoding>,
+    /// Recover the complete messages of a truncated JSON export instead of
+    /// failing. Off by default.
+    pub salvage_json: bool,
 }
 
 /// Type of chat (private or group).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ChatType {
     /// One-on-one private chat.
     Private,
@@ -104,11 +146,50 @@ pub trait ChatParser: Send + Sync {
     fn can_parse(&self, path: &Path) -> bool;
     /// Parse the chat file and return structured data.
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError>;
+    /// Parse with caller-chosen [`ParseOptions`]. Options that do not apply
+    /// to a parser's format are ignored.
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        let _ = options;
+        self.parse(path)
+    }
+    /// Parse export content that is already in memory, such as an upload.
+    /// `chat_name_hint` stands in for the file stem, which many formats use
+    /// as the chat name.
+    fn parse_str(&self, content: &str, chat_name_hint: &str) -> Result<ParsedChat, ParseError> {
+        self.parse_str_with_options(content, chat_name_hint, &ParseOptions::default())
+    }
+    /// [`ChatParser::parse_str`] with caller-chosen [`ParseOptions`]; the
+    /// encoding option does not apply to text that is already decoded.
+    /// Formats that need a file on disk, like SQLite databases, keep this
+    /// default and fail with [`ParseError::UnsupportedFormat`].
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        let _ = (content, chat_name_hint, options);
+        Err(ParseError::UnsupportedFormat(format!(
+            "{} exports cannot be parsed from text",
+            self.name()
+        )))
+    }
+    /// Detection priority; a matching parser with higher priority wins over
+    /// lower-priority matches regardless of score. Built-ins use `0`.
+    fn priority(&self) -> i32 {
+        0
+    }
 }
 
 /// Registry of available chat parsers.
 pub struct ParserRegistry {
     parsers: Vec<Box<dyn ChatParser>>,
+    options: ParseOptions,
+    chat_type_defaults: std::collections::BTreeMap<String, ChatType>,
 }
 
 impl ParserRegistry {
@@ -116,6 +197,8 @@ impl ParserRegistry {
     pub fn new() -> Self {
         let mut registry = Self {
             parsers: Vec::new(),
+            options: ParseOptions::default(),
+            chat_type_defaults: std::collections::BTreeMap::new(),
         };
         registry.register_default_parsers();
         registry
@@ -140,21 +223,150 @@ impl ParserRegistry {
         self.parsers.push(Box::new(GoogleChatParser::new()));
         self.parsers.push(Box::new(ZoomParser::new()));
         self.parsers.push(Box::new(ViberParser::new()));
+        self.parsers.push(Box::new(AndroidSmsDbParser::new()));
+        self.parsers.push(Box::new(CsvChatParser::new()));
     }
 
     /// Register a new parser.
     ///
-    /// Adds a custom parser to the registry.
+    /// Adds a custom parser to the registry. Give it a positive
+    /// [`ChatParser::priority`] to take precedence over built-in parsers.
     pub fn register(&mut self, parser: Box<dyn ChatParser>) {
         self.parsers.push(parser);
     }
 
+    /// Decode text exports with the encoding named by `label` instead of
+    /// detecting it per file. Fails when the label is unknown.
+    pub fn set_encoding(&mut self, label: &str) -> Result<(), ParseError> {
+        self.options.encoding = Some(encoding_for_label(label)?);
+        Ok(())
+    }
+
+    /// Recover the complete messages of truncated JSON exports instead of
+    /// rejecting them. See [`ParsedChat::salvage`].
+    pub fn set_salvage_json(&mut self, salvage: bool) {
+        self.options.salvage_json = salvage;
+    }
+
+    /// Use `chat_type` for `platform` chats whose type the parser could only
+    /// guess. See [`ParserRegistry::resolve_chat_type`].
+    pub fn set_default_chat_type(&mut self, platform: &str, chat_type: ChatType) {
+        self.chat_type_defaults
+            .insert(platform.trim().to_ascii_lowercase(), chat_type);
+    }
+
+    /// Apply defaults written as `platform=private|group` pairs separated by
+    /// commas, e.g. `imessage=private,sms=private`. Returns how many were set.
+    pub fn set_chat_type_defaults(&mut self, spec: &str) -> Result<usize, ParseError> {
+        let mut added = 0;
+        for entry in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let (platform, chat_type) = entry.split_once('=').ok_or_else(|| {
+                ParseError::InvalidFormat(format!("expected platform=type, got '{}'", entry))
+            })?;
+            let chat_type = match chat_type.trim().to_ascii_lowercase().as_str() {
+                "private" => ChatType::Private,
+                "group" => ChatType::Group,
+                other => {
+                    return Err(ParseError::InvalidFormat(format!(
+                        "unknown chat type '{}' for '{}' (expected private or group)",
+                        other,
+                        platform.trim()
+                    )))
+                }
+            };
+            self.set_default_chat_type(platform, chat_type);
+            added += 1;
+        }
+        Ok(added)
+    }
+
+    /// Settle the chat type of a parsed chat.
+    ///
+    /// A type stated by the export is kept. Otherwise three or more members
+    /// make it a group, and with fewer members (or none known) the
+    /// platform's configured default, if any, replaces the parser's guess.
+    pub fn resolve_chat_type(&self, chat: &mut ParsedChat) {
+        if chat.chat_type_known {
+            return;
+        }
+        if chat.members.len() > 2 {
+            chat.chat_type = ChatType::Group;
+        } else if let Some(default) = self
+            .chat_type_defaults
+            .get(&chat.platform.to_ascii_lowercase())
+        {
+            chat.chat_type = default.clone();
+        }
+    }
+
+    /// Register a custom line pattern under its platform name.
+    ///
+    /// Fails when the pattern is invalid or the name is already taken.
+    pub fn register_line_pattern(&mut self, config: &LinePatternConfig) -> Result<(), ParseError> {
+        let parser = RegexLineParser::new(config)?;
+        if self
+            .parsers
+            .iter()
+            .any(|existing| existing.name().eq_ignore_ascii_case(parser.name()))
+        {
+            return Err(ParseError::InvalidFormat(format!(
+                "a parser named '{}' is already registered",
+                parser.name()
+            )));
+        }
+        self.parsers.push(Box::new(parser));
+        Ok(())
+    }
+
+    /// Register every pattern in a JSON file holding an array of
+    /// [`LinePatternConfig`] entries. Returns how many were added.
+    pub fn register_line_patterns_file(&mut self, path: &Path) -> Result<usize, ParseError> {
+        let content = std::fs::read_to_string(path)?;
+        let configs: Vec<LinePatternConfig> = serde_json::from_str(&content)?;
+        for config in &configs {
+            self.register_line_pattern(config)?;
+        }
+        Ok(configs.len())
+    }
+
+    /// Default registry plus the patterns named by [`LINE_PATTERNS_ENV`], if set.
+    pub fn with_configured_line_patterns() -> Result<Self, ParseError> {
+        let mut registry = Self::new();
+        if let Some(path) = std::env::var_os(LINE_PATTERNS_ENV).filter(|v| !v.is_empty()) {
+            let added = registry.register_line_patterns_file(Path::new(&path))?;
+            info!("Registered {} custom line pattern(s)", added);
+        }
+        Ok(registry)
+    }
+
+    /// [`ParserRegistry::with_configured_line_patterns`] plus the chat type
+    /// defaults in [`CHAT_TYPE_DEFAULTS_ENV`], if set. Errors name the
+    /// variable at fault.
+    pub fn from_env() -> Result<Self, ParseError> {
+        let mut registry = Self::with_configured_line_patterns().map_err(|e| {
+            ParseError::InvalidFormat(format!("invalid {}: {}", LINE_PATTERNS_ENV, e))
+        })?;
+        if let Ok(spec) = std::env::var(CHAT_TYPE_DEFAULTS_ENV) {
+            registry.set_chat_type_defaults(&spec).map_err(|e| {
+                ParseError::InvalidFormat(format!("invalid {}: {}", CHAT_TYPE_DEFAULTS_ENV, e))
+            })?;
+        }
+        Ok(registry)
+    }
+
     /// Detect the chat format and parse the file.
     ///
-    /// Tries each registered parser in order until one successfully parses the file.
+    /// Tries parsers by descending priority (ties in registration order) and
+    /// keeps the highest-priority, best-scoring successful parse.
     pub fn detect_and_parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
         let path_lower = path.to_string_lossy().to_lowercase();
-        let mut best_match: Option<(usize, ParsedChat, String)> = None;
+        let mut order: Vec<usize> = (0..self.parsers.len()).collect();
+        order.sort_by_key(|&idx| std::cmp::Reverse(self.parsers[idx].priority()));
+        let mut best_match: Option<((i32, usize), ParsedChat, String)> = None;
         let mut hinted_empty_fallback: Option<(ParsedChat, String)> = None;
         let mut last_hinted_error: Option<ParseError> = None;
         let mut saw_hinted_parser = false;
@@ -163,7 +375,8 @@ impl ParserRegistry {
         // Pass 1: respect parser-level hints for fast-path matching.
         // Pass 2: broaden to all parsers only if pass 1 did not produce a confident match.
         for pass in 0..=1 {
-            for (idx, parser) in self.parsers.iter().enumerate() {
+            for &idx in &order {
+                let parser = &self.parsers[idx];
                 let hinted = parser.can_parse(path);
                 if pass == 0 && !hinted {
                     continue;
@@ -176,16 +389,17 @@ impl ParserRegistry {
                     saw_hinted_parser = true;
                 }
 
-                match parser.parse(path) {
+                match parser.parse_with_options(path, &self.options) {
                     Ok(parsed) => {
                         let score = score_parsed_chat(&parsed, parser.name(), &path_lower, hinted);
                         if score > 0 {
+                            let rank = (parser.priority(), score);
                             let should_replace = best_match
                                 .as_ref()
-                                .map(|(best_score, _, _)| score > *best_score)
+                                .map(|(best_rank, _, _)| rank > *best_rank)
                                 .unwrap_or(true);
                             if should_replace {
-                                best_match = Some((score, parsed, parser.name().to_string()));
+                                best_match = Some((rank, parsed, parser.name().to_string()));
                             }
                         } else if hinted && hinted_empty_fallback.is_none() {
                             // Keep a deterministic fallback only for hinted parsers.
@@ -205,16 +419,18 @@ impl ParserRegistry {
             }
         }
 
-        if let Some((_, parsed, parser_name)) = best_match {
+        if let Some((_, mut parsed, parser_name)) = best_match {
             info!("Detected format: {}", parser_name);
+            self.resolve_chat_type(&mut parsed);
             return Ok(parsed);
         }
 
-        if let Some((parsed, parser_name)) = hinted_empty_fallback {
+        if let Some((mut parsed, parser_name)) = hinted_empty_fallback {
             warn!(
                 "Parser '{}' returned empty message set; accepting hinted fallback",
                 parser_name
             );
+            self.resolve_chat_type(&mut parsed);
             return Ok(parsed);
         }
 
@@ -229,6 +445,47 @@ impl ParserRegistry {
         ))
     }
 
+    /// Parse the file with the named parser, skipping format detection.
+    ///
+    /// Used when the caller knows the platform and detection would misfire,
+    /// e.g. for generically named files. Names match case-insensitively.
+    pub fn parse_with(&self, name: &str, path: &Path) -> Result<ParsedChat, ParseError> {
+        let parser = self.parser_named(name)?;
+        info!("Using forced format: {}", parser.name());
+        let mut parsed = parser.parse_with_options(path, &self.options)?;
+        self.resolve_chat_type(&mut parsed);
+        Ok(parsed)
+    }
+
+    /// Parse in-memory export content with the named parser. See
+    /// [`ChatParser::parse_str`] for `chat_name_hint`.
+    pub fn parse_str_with(
+        &self,
+        name: &str,
+        content: &str,
+        chat_name_hint: &str,
+    ) -> Result<ParsedChat, ParseError> {
+        let parser = self.parser_named(name)?;
+        let mut parsed = parser.parse_str_with_options(content, chat_name_hint, &self.options)?;
+        self.resolve_chat_type(&mut parsed);
+        Ok(parsed)
+    }
+
+    fn parser_named(&self, name: &str) -> Result<&dyn ChatParser, ParseError> {
+        let wanted = name.trim();
+        self.parsers
+            .iter()
+            .find(|parser| parser.name().eq_ignore_ascii_case(wanted))
+            .map(|parser| parser.as_ref())
+            .ok_or_else(|| {
+                ParseError::UnsupportedFormat(format!(
+                    "no parser registered for '{}' (available: {})",
+                    wanted,
+                    self.parser_names().join(", ")
+                ))
+            })
+    }
+
     /// Returns all registered parser names.
     pub fn parser_names(&self) -> Vec<String> {
         self.parsers
@@ -249,6 +506,66 @@ impl Default for ParserRegistry {
     }
 }
 
+/// Default length above which line-based parsers skip a line.
+pub const DEFAULT_MAX_LINE_BYTES: usize = 1024 * 1024;
+
+/// Bytes sniffed from the start of a file to decide whether it is binary.
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+/// Iterates `content` line by line, skipping (with a warning) lines longer
+/// than `max_line_bytes` so a malformed file cannot stall the line regexes.
+fn bounded_lines<'a>(
+    content: &'a str,
+    max_line_bytes: usize,
+    parser_name: &'a str,
+) -> impl Iterator<Item = &'a str> + 'a {
+    content.lines().filter(move |line| {
+        if line.len() > max_line_bytes {
+            warn!(
+                "{}: skipping {}-byte line (limit {} bytes)",
+                parser_name,
+                line.len(),
+                max_line_bytes
+            );
+            return false;
+        }
+        true
+    })
+}
+
+/// Heuristic: NUL bytes or a high share of control characters in the head.
+fn bytes_look_binary(head: &[u8]) -> bool {
+    if head.contains(&0) {
+        return true;
+    }
+    let control = head
+        .iter()
+        .filter(|&&byte| byte < 0x09 || (byte > 0x0d && byte < 0x20))
+        .count();
+    control * 10 > head.len()
+}
+
+fn file_looks_binary(path: &Path) -> bool {
+    use std::io::Read;
+
+    let mut head = [0u8; BINARY_SNIFF_BYTES];
+    let read = match std::fs::File::open(path).and_then(|mut file| file.read(&mut head)) {
+        Ok(read) => read,
+        Err(_) => return false,
+    };
+    bytes_look_binary(&head[..read])
+}
+
+fn reject_binary_file(path: &Path) -> Result<(), ParseError> {
+    if file_looks_binary(path) {
+        return Err(ParseError::UnsupportedFormat(format!(
+            "binary content in {}",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
 fn score_parsed_chat(
     parsed: &ParsedChat,
     parser_name: &str,
@@ -301,14 +618,30 @@ impl ChatParser for ManualReviewParser {
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
         let content = std::fs::read_to_string(path)?;
-        let value: serde_json::Value = serde_json::from_str(&content)?;
-        parse_manual_review_value(&value, path).ok_or_else(|| {
+        let chat_name_hint = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.parse_str(&content, &chat_name_hint)
+    }
+
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        _options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        let value: serde_json::Value = serde_json::from_str(content)?;
+        parse_manual_review_value(&value, chat_name_hint).ok_or_else(|| {
             ParseError::UnsupportedFormat("not a xenobot manual-review selection pack".to_string())
         })
     }
 }
 
-fn parse_manual_review_value(value: &serde_json::Value, path: &Path) -> Option<ParsedChat> {
+fn parse_manual_review_value(
+    value: &serde_json::Value,
+    chat_name_hint: &str,
+) -> Option<ParsedChat> {
     let schema = value_get_string(value, &["schema", "type"])?.to_ascii_lowercase();
     let capture_mode =
         value_get_string(value, &["captureMode", "capture_mode"])?.to_ascii_lowercase();
@@ -324,12 +657,13 @@ fn parse_manual_review_value(value: &serde_json::Value, path: &Path) -> Option<P
     }
 
     let chat_name = value_get_string(value, &["chatName", "chat_name"]).unwrap_or_else(|| {
-        path.file_stem()
-            .map(|stem| stem.to_string_lossy().to_string())
+        Some(chat_name_hint.to_string())
             .filter(|value| !value.trim().is_empty())
             .unwrap_or_else(|| "Manual Review".to_string())
     });
-    let chat_type = match value_get_string(value, &["chatType", "chat_type"])
+    let stated_chat_type = value_get_string(value, &["chatType", "chat_type"]);
+    let chat_type_known = stated_chat_type.is_some();
+    let chat_type = match stated_chat_type
         .unwrap_or_else(|| "group".to_string())
         .trim()
         .to_ascii_lowercase()
@@ -379,9 +713,12 @@ fn parse_manual_review_value(value: &serde_json::Value, path: &Path) -> Option<P
             messages.push(ParsedMessage {
                 sender,
                 sender_name,
+                sender_group_nickname: None,
                 timestamp,
                 content,
                 msg_type,
+                edited_at: None,
+                platform_message_id: None,
             });
         }
     }
@@ -430,9 +767,12 @@ fn parse_manual_review_value(value: &serde_json::Value, path: &Path) -> Option<P
             messages.push(ParsedMessage {
                 sender,
                 sender_name,
+                sender_group_nickname: None,
                 timestamp,
                 content,
                 msg_type,
+                edited_at: None,
+                platform_message_id: None,
             });
         }
     }
@@ -443,6 +783,9 @@ fn parse_manual_review_value(value: &serde_json::Value, path: &Path) -> Option<P
         chat_type,
         messages,
         members: members.into_values().collect(),
+        encoding: None,
+        salvage: None,
+        chat_type_known,
     })
 }
 
@@ -450,7 +793,7 @@ fn parse_manual_review_timestamp(value: &serde_json::Value) -> Option<i64> {
     use chrono::TimeZone;
 
     if let Some(ts) = value_get_i64(value, &["timestamp", "ts"]) {
-        return Some(ts);
+        return Some(normalize_epoch(ts));
     }
 
     let raw = value_get_string(value, &["timestamp", "ts"])?;
@@ -485,6 +828,7 @@ fn parse_manual_review_message_type(value: &serde_json::Value) -> MessageType {
 /// Parser for WhatsApp chat exports.
 pub struct WhatsAppParser {
     name_str: String,
+    max_line_bytes: usize,
 }
 
 impl WhatsAppParser {
@@ -493,8 +837,15 @@ impl WhatsAppParser {
     pub fn new() -> Self {
         Self {
             name_str: "whatsapp".to_string(),
+            max_line_bytes: DEFAULT_MAX_LINE_BYTES,
         }
     }
+
+    /// Overrides the length above which lines are skipped.
+    pub fn with_max_line_bytes(mut self, max_line_bytes: usize) -> Self {
+        self.max_line_bytes = max_line_bytes;
+        self
+    }
 }
 
 impl ChatParser for WhatsAppParser {
@@ -504,29 +855,62 @@ impl ChatParser for WhatsAppParser {
 
     fn can_parse(&self, path: &Path) -> bool {
         let path_str = path.to_string_lossy().to_lowercase();
-        path_str.contains("whatsapp") || path_str.ends_with(".txt")
+        (path_str.contains("whatsapp") || path_str.ends_with(".txt")) && !file_looks_binary(path)
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
-        let content = std::fs::read_to_string(path)?;
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        reject_binary_file(path)?;
+        let (content, source_encoding) = read_text_file(path, options.encoding)?;
+        let parsed = self.parse_str_with_options(&content, &file_stem_string(path)?, options)?;
+        Ok(ParsedChat {
+            encoding: Some(source_encoding),
+            ..parsed
+        })
+    }
+
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        _options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let mut messages = Vec::new();
         let mut members = std::collections::HashSet::new();
 
-        for line in content.lines() {
-            let line = line.trim();
-            if line.is_empty() {
+        // Lines without a `[date, time]` header belong to the previous
+        // message. A header without `sender:` is a system notice: it is not
+        // kept, but it ends the message before it.
+        let mut current: Option<ParsedMessage> = None;
+        for line in bounded_lines(content, self.max_line_bytes, &self.name_str) {
+            let line = line.trim_end();
+            if let Some(msg) = parse_whatsapp_line(line.trim_start()) {
+                messages.extend(current.replace(msg).map(finish_whatsapp_message));
                 continue;
             }
-
-            if let Some(msg) = parse_whatsapp_line(line) {
-                members.insert(msg.sender.clone());
-                messages.push(msg);
+            if whatsapp_line_has_header(line.trim_start()) {
+                messages.extend(current.take().map(finish_whatsapp_message));
+                continue;
+            }
+            if let Some(msg) = current.as_mut() {
+                msg.content.push('\n');
+                msg.content.push_str(line);
             }
         }
+        messages.extend(current.map(finish_whatsapp_message));
+        for msg in &messages {
+            members.insert(msg.sender.clone());
+        }
 
-        let chat_name = path
-            .file_stem()
-            .map(|s| s.to_string_lossy().to_string())
+        let chat_name = Some(chat_name_hint.to_string())
+            .filter(|name| !name.is_empty())
             .unwrap_or_else(|| "WhatsApp Chat".to_string());
 
         Ok(ParsedChat {
@@ -542,15 +926,20 @@ impl ChatParser for WhatsAppParser {
                     display_name: None,
                 })
                 .collect(),
+            encoding: None,
+            salvage: None,
+            chat_type_known: false,
         })
     }
 }
 
+/// `date, time` prefix of a WhatsApp line. iOS wraps it in brackets and
+/// Android follows it with ` - `. Depending on locale the time may lack
+/// seconds and carry an AM/PM marker, often after a narrow no-break space.
+const WHATSAPP_HEADER_PATTERN: &str = r"^\[?(\d{1,2}/\d{1,2}/\d{2,4}),?\s+(\d{1,2}:\d{2}(?::\d{2})?(?:\s?[AaPp]\.?\s?[Mm]\.?)?)\]?\s+(?:-\s+)?";
+
 fn parse_whatsapp_line(line: &str) -> Option<ParsedMessage> {
-    let pattern = regex::Regex::new(
-        r"^\[?(\d{1,2}/\d{1,2}/\d{2,4}),?\s+(\d{1,2}:\d{2}:\d{2})\]?\s+(.+?):\s+(.*)$",
-    )
-    .ok()?;
+    let pattern = regex::Regex::new(&format!(r"{}(.+?):\s+(.*)$", WHATSAPP_HEADER_PATTERN)).ok()?;
 
     let caps = pattern.captures(line)?;
 
@@ -563,23 +952,82 @@ fn parse_whatsapp_line(line: &str) -> Option<ParsedMessage> {
     Some(ParsedMessage {
         sender,
         sender_name: None,
+        sender_group_nickname: None,
         timestamp,
         content,
         msg_type: MessageType::Text,
+        edited_at: None,
+        platform_message_id: None,
     })
 }
 
+fn whatsapp_line_has_header(line: &str) -> bool {
+    regex::Regex::new(WHATSAPP_HEADER_PATTERN)
+        .map(|pattern| pattern.is_match(line))
+        .unwrap_or(false)
+}
+
+/// Drops blank lines left at the end of a multi-line message and classifies
+/// media placeholders once the full content is known.
+fn finish_whatsapp_message(mut msg: ParsedMessage) -> ParsedMessage {
+    msg.content.truncate(msg.content.trim_end().len());
+    msg.msg_type = classify_message_type(&msg.content, "whatsapp");
+    msg
+}
+
+/// Formats tried in order once the year has four digits and any AM/PM
+/// marker is `AM`/`PM` after a single space. Ambiguous dates are read
+/// month-first for 12-hour clocks (US locale) and day-first for 24-hour
+/// clocks without seconds.
+const WHATSAPP_TIMESTAMP_FORMATS: &[&str] = &[
+    "%m/%d/%Y %H:%M:%S",
+    "%d/%m/%Y %H:%M:%S",
+    "%m/%d/%Y %I:%M %p",
+    "%m/%d/%Y %I:%M:%S %p",
+    "%d/%m/%Y %I:%M %p",
+    "%d/%m/%Y %I:%M:%S %p",
+    "%d/%m/%Y %H:%M",
+    "%m/%d/%Y %H:%M",
+];
+
 fn parse_whatsapp_timestamp(s: &str) -> Option<i64> {
     use chrono::{NaiveDateTime, TimeZone, Utc};
-    NaiveDateTime::parse_from_str(s, "%m/%d/%Y %H:%M:%S")
-        .or_else(|_| NaiveDateTime::parse_from_str(s, "%d/%m/%Y %H:%M:%S"))
-        .ok()
+    let normalized = normalize_whatsapp_timestamp(s)?;
+    WHATSAPP_TIMESTAMP_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&normalized, format).ok())
         .map(|dt| Utc.from_utc_datetime(&dt).timestamp())
 }
 
+/// Rewrites `1/5/24, 9:05\u{202f}p.m.` as `1/5/2024 9:05 PM`. Two-digit
+/// years are taken as 20xx; WhatsApp exports cannot predate 2009.
+fn normalize_whatsapp_timestamp(s: &str) -> Option<String> {
+    let s = s.trim();
+    let (date, time) = s.split_once(char::is_whitespace)?;
+    let date = date.trim_end_matches(',');
+    let (day_month, year) = date.rsplit_once('/')?;
+    let year = if year.len() == 2 {
+        format!("20{}", year)
+    } else {
+        year.to_string()
+    };
+
+    let time: String = time
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '.')
+        .collect::<String>()
+        .to_ascii_uppercase();
+    let time = match time.strip_suffix("AM").or_else(|| time.strip_suffix("PM")) {
+        Some(clock) => format!("{} {}", clock, &time[clock.len()..]),
+        None => time,
+    };
+    Some(format!("{}/{} {}", day_month, year, time))
+}
+
 /// Parser for LINE chat exports.
 pub struct LINEParser {
     name_str: String,
+    max_line_bytes: usize,
 }
 
 impl LINEParser {
@@ -588,8 +1036,15 @@ impl LINEParser {
     pub fn new() -> Self {
         Self {
             name_str: "line".to_string(),
+            max_line_bytes: DEFAULT_MAX_LINE_BYTES,
         }
     }
+
+    /// Overrides the length above which lines are skipped.
+    pub fn with_max_line_bytes(mut self, max_line_bytes: usize) -> Self {
+        self.max_line_bytes = max_line_bytes;
+        self
+    }
 }
 
 impl ChatParser for LINEParser {
@@ -598,15 +1053,37 @@ impl ChatParser for LINEParser {
     }
 
     fn can_parse(&self, path: &Path) -> bool {
-        path.to_string_lossy().to_lowercase().contains("line")
+        path.to_string_lossy().to_lowercase().contains("line") && !file_looks_binary(path)
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
-        let content = std::fs::read_to_string(path)?;
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        reject_binary_file(path)?;
+        let (content, source_encoding) = read_text_file(path, options.encoding)?;
+        let parsed = self.parse_str_with_options(&content, &file_stem_string(path)?, options)?;
+        Ok(ParsedChat {
+            encoding: Some(source_encoding),
+            ..parsed
+        })
+    }
+
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        _options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let mut messages = Vec::new();
         let mut members = std::collections::HashSet::new();
 
-        for line in content.lines() {
+        for line in bounded_lines(content, self.max_line_bytes, &self.name_str) {
             let line = line.trim();
             if line.is_empty() {
                 continue;
@@ -620,11 +1097,7 @@ impl ChatParser for LINEParser {
 
         Ok(ParsedChat {
             platform: "line".to_string(),
-            chat_name: path
-                .file_stem()
-                .ok_or(ParseError::InvalidFormat("missing file stem".to_string()))?
-                .to_string_lossy()
-                .to_string(),
+            chat_name: chat_name_hint.to_string(),
             chat_type: ChatType::Group,
             messages,
             members: members
@@ -635,6 +1108,9 @@ impl ChatParser for LINEParser {
                     display_name: None,
                 })
                 .collect(),
+            encoding: None,
+            salvage: None,
+            chat_type_known: false,
         })
     }
 }
@@ -651,9 +1127,12 @@ fn parse_line_line(line: &str) -> Option<ParsedMessage> {
     Some(ParsedMessage {
         sender,
         sender_name: None,
+        sender_group_nickname: None,
         timestamp: parse_line_timestamp(&timestamp_str)?,
+        msg_type: classify_message_type(&content, "line"),
         content,
-        msg_type: MessageType::Text,
+        edited_at: None,
+        platform_message_id: None,
     })
 }
 
@@ -664,6 +1143,196 @@ fn parse_line_timestamp(s: &str) -> Option<i64> {
         .map(|dt| Utc.from_utc_datetime(&dt).timestamp())
 }
 
+/// Environment variable naming a JSON file with an array of
+/// [`LinePatternConfig`] entries to register alongside the built-ins.
+pub const LINE_PATTERNS_ENV: &str = "XENOBOT_LINE_PATTERNS";
+
+/// Environment variable with per-platform default chat types, written as
+/// `platform=private|group` pairs separated by commas.
+pub const CHAT_TYPE_DEFAULTS_ENV: &str = "XENOBOT_CHAT_TYPE_DEFAULTS";
+
+/// Named capture groups every custom line pattern must define.
+pub const LINE_PATTERN_GROUPS: [&str; 4] = ["date", "time", "sender", "content"];
+
+/// A user-defined line format for text exports the built-in parsers miss.
+///
+/// `timestamp_format` is a chrono format applied to `"{date} {time}"`, e.g.
+/// `"%d.%m.%Y %H:%M"`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinePatternConfig {
+    /// Platform name reported on parsed chats; also the parser name.
+    pub platform: String,
+    /// Regex with named `date`, `time`, `sender` and `content` groups.
+    pub pattern: String,
+    /// Chrono format for the joined date and time captures.
+    #[serde(alias = "timestampFormat")]
+    pub timestamp_format: String,
+}
+
+/// Line parser built from a [`LinePatternConfig`].
+pub struct RegexLineParser {
+    name_str: String,
+    pattern: regex::Regex,
+    timestamp_format: String,
+    max_line_bytes: usize,
+}
+
+impl RegexLineParser {
+    /// Compiles the pattern, rejecting configs that lack a required group
+    /// or carry an invalid timestamp format.
+    pub fn new(config: &LinePatternConfig) -> Result<Self, ParseError> {
+        let platform = config.platform.trim();
+        if platform.is_empty() {
+            return Err(ParseError::InvalidFormat(
+                "line pattern needs a platform name".to_string(),
+            ));
+        }
+        let pattern = regex::Regex::new(&config.pattern).map_err(|e| {
+            ParseError::InvalidFormat(format!("line pattern for '{}': {}", platform, e))
+        })?;
+        let names: std::collections::HashSet<&str> = pattern.capture_names().flatten().collect();
+        let missing: Vec<&str> = LINE_PATTERN_GROUPS
+            .iter()
+            .copied()
+            .filter(|group| !names.contains(group))
+            .collect();
+        if !missing.is_empty() {
+            return Err(ParseError::InvalidFormat(format!(
+                "line pattern for '{}' is missing named group(s): {}",
+                platform,
+                missing.join(", ")
+            )));
+        }
+        let format_is_valid = !config.timestamp_format.trim().is_empty()
+            && chrono::format::StrftimeItems::new(&config.timestamp_format)
+                .all(|item| !matches!(item, chrono::format::Item::Error));
+        if !format_is_valid {
+            return Err(ParseError::InvalidFormat(format!(
+                "line pattern for '{}' has an invalid timestamp format: {:?}",
+                platform, config.timestamp_format
+            )));
+        }
+        Ok(Self {
+            name_str: platform.to_ascii_lowercase(),
+            pattern,
+            timestamp_format: config.timestamp_format.clone(),
+            max_line_bytes: DEFAULT_MAX_LINE_BYTES,
+        })
+    }
+
+    /// Overrides the length above which lines are skipped.
+    pub fn with_max_line_bytes(mut self, max_line_bytes: usize) -> Self {
+        self.max_line_bytes = max_line_bytes;
+        self
+    }
+
+    fn parse_line(&self, line: &str) -> Option<ParsedMessage> {
+        use chrono::{NaiveDateTime, TimeZone, Utc};
+
+        let caps = self.pattern.captures(line)?;
+        let timestamp_str = format!(
+            "{} {}",
+            caps.name("date")?.as_str(),
+            caps.name("time")?.as_str()
+        );
+        let timestamp = NaiveDateTime::parse_from_str(&timestamp_str, &self.timestamp_format)
+            .ok()
+            .map(|dt| Utc.from_utc_datetime(&dt).timestamp())?;
+        let sender = caps.name("sender")?.as_str().trim().to_string();
+        if sender.is_empty() {
+            return None;
+        }
+        let content = caps.name("content")?.as_str();
+
+        Some(ParsedMessage {
+            sender,
+            sender_name: None,
+            sender_group_nickname: None,
+            timestamp,
+            content: content.to_string(),
+            msg_type: classify_message_type(content, &self.name_str),
+            edited_at: None,
+            platform_message_id: None,
+        })
+    }
+}
+
+impl ChatParser for RegexLineParser {
+    fn name(&self) -> &str {
+        &self.name_str
+    }
+
+    fn can_parse(&self, path: &Path) -> bool {
+        let path_str = path.to_string_lossy().to_lowercase();
+        (path_str.contains(&self.name_str) || path_str.ends_with(".txt"))
+            && !file_looks_binary(path)
+    }
+
+    fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        reject_binary_file(path)?;
+        let (content, source_encoding) = read_text_file(path, options.encoding)?;
+        let parsed = self.parse_str_with_options(&content, &file_stem_string(path)?, options)?;
+        Ok(ParsedChat {
+            encoding: Some(source_encoding),
+            ..parsed
+        })
+    }
+
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        _options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        let mut messages = Vec::new();
+        let mut members = std::collections::HashSet::new();
+
+        for line in bounded_lines(content, self.max_line_bytes, &self.name_str) {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(msg) = self.parse_line(line) {
+                members.insert(msg.sender.clone());
+                messages.push(msg);
+            }
+        }
+
+        Ok(ParsedChat {
+            platform: self.name_str.clone(),
+            chat_name: chat_name_hint.to_string(),
+            chat_type: ChatType::Group,
+            messages,
+            members: members
+                .into_iter()
+                .map(|id| ChatMember {
+                    id,
+                    name: None,
+                    display_name: None,
+                })
+                .collect(),
+            encoding: None,
+            salvage: None,
+            chat_type_known: false,
+        })
+    }
+
+    /// User patterns are deliberate overrides, so a successful match wins
+    /// over the built-in text parsers.
+    fn priority(&self) -> i32 {
+        1
+    }
+}
+
 fn file_stem_string(path: &Path) -> Result<String, ParseError> {
     Ok(path
         .file_stem()
@@ -708,6 +1377,21 @@ fn value_get_bool(value: &serde_json::Value, keys: &[&str]) -> Option<bool> {
     }
 }
 
+/// Members keyed on sender id, named from the first message that carries
+/// a sender name.
+fn members_from_messages(messages: &[ParsedMessage]) -> Vec<ChatMember> {
+    let mut members = std::collections::BTreeMap::new();
+    for msg in messages {
+        upsert_member(
+            &mut members,
+            msg.sender.clone(),
+            msg.sender_name.clone(),
+            msg.sender_name.clone(),
+        );
+    }
+    members.into_values().collect()
+}
+
 fn upsert_member(
     members: &mut std::collections::BTreeMap<String, ChatMember>,
     id: String,
@@ -751,6 +1435,7 @@ fn upsert_member(
 /// Parser for QQ chat exports.
 pub struct QQParser {
     name_str: String,
+    max_line_bytes: usize,
 }
 
 impl QQParser {
@@ -759,8 +1444,15 @@ impl QQParser {
     pub fn new() -> Self {
         Self {
             name_str: "qq".to_string(),
+            max_line_bytes: DEFAULT_MAX_LINE_BYTES,
         }
     }
+
+    /// Overrides the length above which lines are skipped.
+    pub fn with_max_line_bytes(mut self, max_line_bytes: usize) -> Self {
+        self.max_line_bytes = max_line_bytes;
+        self
+    }
 }
 
 impl ChatParser for QQParser {
@@ -769,27 +1461,48 @@ impl ChatParser for QQParser {
     }
 
     fn can_parse(&self, path: &Path) -> bool {
-        path.to_string_lossy().to_lowercase().contains("qq")
+        path.to_string_lossy().to_lowercase().contains("qq") && !file_looks_binary(path)
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
-        let content = std::fs::read_to_string(path)?;
-        let fallback_chat_name = file_stem_string(path)?;
+        self.parse_with_options(path, &ParseOptions::default())
+    }
 
-        if let Ok(root) = serde_json::from_str::<serde_json::Value>(&content) {
-            if let Some(parsed) = parse_qq_chat_exporter_json(&root, &fallback_chat_name) {
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        reject_binary_file(path)?;
+        let (content, source_encoding) = read_text_file(path, options.encoding)?;
+        let parsed = self.parse_str_with_options(&content, &file_stem_string(path)?, options)?;
+        Ok(ParsedChat {
+            encoding: Some(source_encoding),
+            ..parsed
+        })
+    }
+
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        _options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        if let Ok(root) = serde_json::from_str::<serde_json::Value>(content) {
+            if let Some(parsed) = parse_qq_chat_exporter_json(&root, chat_name_hint) {
                 return Ok(parsed);
             }
         }
 
-        if let Some(parsed) = parse_qq_official_export(&content, &fallback_chat_name) {
+        if let Some(parsed) = parse_qq_official_export(content, chat_name_hint, self.max_line_bytes)
+        {
             return Ok(parsed);
         }
 
         let mut messages = Vec::new();
         let mut members = std::collections::BTreeMap::new();
 
-        for line in content.lines() {
+        for line in bounded_lines(content, self.max_line_bytes, &self.name_str) {
             let line = line.trim();
             if line.is_empty() {
                 continue;
@@ -808,10 +1521,13 @@ impl ChatParser for QQParser {
 
         Ok(ParsedChat {
             platform: "qq".to_string(),
-            chat_name: fallback_chat_name,
+            chat_name: chat_name_hint.to_string(),
             chat_type: ChatType::Group,
             messages,
             members: members.into_values().collect(),
+            encoding: None,
+            salvage: None,
+            chat_type_known: false,
         })
     }
 }
@@ -829,9 +1545,12 @@ fn parse_qq_line(line: &str) -> Option<ParsedMessage> {
     Some(ParsedMessage {
         sender,
         sender_name: None,
+        sender_group_nickname: None,
         timestamp: parse_qq_timestamp(timestamp_str)?,
         content,
         msg_type,
+        edited_at: None,
+        platform_message_id: None,
     })
 }
 
@@ -863,26 +1582,8 @@ fn infer_qq_message_type(content: &str) -> MessageType {
     if trimmed.is_empty() {
         return MessageType::System;
     }
-    if matches!(trimmed, "[图片]" | "[Image]") {
-        return MessageType::Image;
-    }
-    if matches!(trimmed, "[视频]" | "[Video]") {
-        return MessageType::Video;
-    }
-    if matches!(trimmed, "[语音]" | "[Audio]" | "[Voice]") {
-        return MessageType::Audio;
-    }
-    if matches!(trimmed, "[文件]" | "[File]") {
-        return MessageType::File;
-    }
-    if matches!(trimmed, "[位置]" | "[地理位置]" | "[Location]") {
-        return MessageType::Location;
-    }
-    if matches!(trimmed, "[链接]" | "[卡片消息]" | "[Link]") {
-        return MessageType::Link;
-    }
-    if matches!(trimmed, "[表情]" | "[Sticker]") {
-        return MessageType::Sticker;
+    if let Some(msg_type) = bracket_placeholder_type(trimmed) {
+        return msg_type;
     }
     if trimmed.contains("加入了群聊")
         || trimmed.contains("退出了群聊")
@@ -896,9 +1597,134 @@ fn infer_qq_message_type(content: &str) -> MessageType {
     MessageType::Text
 }
 
-fn parse_qq_official_export(content: &str, fallback_chat_name: &str) -> Option<ParsedChat> {
+/// Maps a whole-message placeholder such as `[图片]`, `[Photo]`, `[图片: a.jpg]` or
+/// `[文件] report.pdf` to its media type. Only file placeholders may carry trailing
+/// text (the file name); anything else after the tag is treated as ordinary text.
+fn bracket_placeholder_type(content: &str) -> Option<MessageType> {
+    if content.contains('\n') {
+        return None;
+    }
+    let (inner, rest) = content.strip_prefix('[')?.split_once(']')?;
+    let tag = inner
+        .split([':', '：'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let msg_type = match tag.as_str() {
+        "图片" | "image" | "闪照" | "photo" | "picture" => MessageType::Image,
+        "视频" | "video" => MessageType::Video,
+        "语音" | "audio" | "voice" | "voice message" => MessageType::Audio,
+        "文件" | "file" => MessageType::File,
+        "位置" | "地理位置" | "location" => MessageType::Location,
+        "链接" | "卡片消息" | "link" => MessageType::Link,
+        "表情" | "sticker" => MessageType::Sticker,
+        _ => return None,
+    };
+    if !rest.trim().is_empty() && !matches!(msg_type, MessageType::File) {
+        return None;
+    }
+    Some(msg_type)
+}
+
+/// Message type for `content` exported by `platform`: media placeholders such
+/// as `<Media omitted>`, `image omitted`, `[Photo]`, `[Sticker]` or
+/// `<attached: IMG_0001.jpg>` map to their media type, anything else is text.
+fn classify_message_type(content: &str, platform: &str) -> MessageType {
+    let trimmed = content.trim().trim_start_matches('\u{200e}');
+    let placeholder = match platform {
+        "whatsapp" => whatsapp_placeholder_type(trimmed),
+        _ => None,
+    };
+    placeholder
+        .or_else(|| bracket_placeholder_type(trimmed))
+        .unwrap_or(MessageType::Text)
+}
+
+/// WhatsApp writes `<Media omitted>` (Android, no hint of the kind),
+/// `image omitted` (iOS), `<attached: 00000012-PHOTO-….jpg>` (iOS with media)
+/// or `IMG-20250102-WA0001.jpg (file attached)` (Android with media).
+fn whatsapp_placeholder_type(content: &str) -> Option<MessageType> {
+    if content.contains('\n') {
+        return None;
+    }
+    if let Some(name) = content
+        .strip_prefix("<attached:")
+        .and_then(|rest| rest.strip_suffix('>'))
+        .or_else(|| content.strip_suffix(" (file attached)"))
+    {
+        return Some(attachment_name_type(name.trim()));
+    }
+    let kind = content
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+        .strip_suffix(" omitted")?
+        .trim()
+        .to_ascii_lowercase();
+    match kind.as_str() {
+        "media" | "document" => Some(MessageType::File),
+        "image" | "photo" => Some(MessageType::Image),
+        "video" | "gif" => Some(MessageType::Video),
+        "audio" | "voice" => Some(MessageType::Audio),
+        "sticker" => Some(MessageType::Sticker),
+        _ => None,
+    }
+}
+
+/// Media type implied by an attachment file name.
+fn attachment_name_type(name: &str) -> MessageType {
+    let lower = name.to_ascii_lowercase();
+    if lower.contains("sticker") {
+        return MessageType::Sticker;
+    }
+    let extension = lower
+        .rsplit_once('.')
+        .map(|(_, ext)| ext)
+        .unwrap_or_default();
+    match extension {
+        "jpg" | "jpeg" | "png" | "heic" | "webp" => MessageType::Image,
+        "mp4" | "mov" | "3gp" | "gif" => MessageType::Video,
+        "opus" | "ogg" | "m4a" | "mp3" | "aac" | "amr" => MessageType::Audio,
+        _ => MessageType::File,
+    }
+}
+
+/// Parses a QQ text-export header line into `(timestamp, nickname, sender id)`.
+///
+/// Accepts both `2025-01-02 10:20:30 Nick(10001)` and the bracketed
+/// `[2025-1-2 9:20:30] Nick(10001)` form. The bracketed form must carry an id so
+/// single-line `[time] sender content` exports are not mistaken for headers.
+fn parse_qq_header_line(
+    line: &str,
+    header_pattern: &regex::Regex,
+    bracketed_header_pattern: &regex::Regex,
+) -> Option<(i64, String, String)> {
+    let captures = header_pattern
+        .captures(line)
+        .or_else(|| bracketed_header_pattern.captures(line))?;
+    let timestamp = parse_qq_timestamp(captures.get(1)?.as_str())?;
+    let raw_sender_name = captures.get(2)?.as_str();
+    let sender_name = clean_qq_nickname(raw_sender_name);
+    let sender_id = captures
+        .get(3)
+        .or_else(|| captures.get(4))
+        .map(|value| value.as_str().trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| sender_name.clone());
+    Some((timestamp, sender_name, sender_id))
+}
+
+fn parse_qq_official_export(
+    content: &str,
+    fallback_chat_name: &str,
+    max_line_bytes: usize,
+) -> Option<ParsedChat> {
     let header_pattern = regex::Regex::new(
-        r"^(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2})\s+(.+?)(?:\(([^)]+)\)|<([^>]+)>)?$",
+        r"^(\d{4}-\d{1,2}-\d{1,2} \d{1,2}:\d{2}:\d{2})\s+(.+?)(?:\(([^)]+)\)|<([^>]+)>)?$",
+    )
+    .ok()?;
+    let bracketed_header_pattern = regex::Regex::new(
+        r"^\[(\d{4}-\d{1,2}-\d{1,2}\s+\d{1,2}:\d{2}:\d{2})\]\s+(.+?)(?:\(([^)]+)\)|<([^>]+)>)$",
     )
     .ok()?;
     let group_pattern = regex::Regex::new(r"^消息对象:(.+)$").ok()?;
@@ -935,14 +1761,17 @@ fn parse_qq_official_export(content: &str, fallback_chat_name: &str) -> Option<P
                 messages.push(ParsedMessage {
                     sender: pending.sender_id,
                     sender_name: Some(pending.sender_name),
+                    sender_group_nickname: None,
                     timestamp: pending.timestamp,
                     content: content.clone(),
                     msg_type: infer_qq_message_type(&content),
+                    edited_at: None,
+                    platform_message_id: None,
                 });
             }
         };
 
-    for raw_line in content.lines() {
+    for raw_line in bounded_lines(content, max_line_bytes, "qq") {
         let line = raw_line.trim_end_matches('\r');
 
         if let Some(captures) = group_pattern.captures(line) {
@@ -955,20 +1784,12 @@ fn parse_qq_official_export(content: &str, fallback_chat_name: &str) -> Option<P
             continue;
         }
 
-        if let Some(captures) = header_pattern.captures(line) {
+        if let Some((timestamp, sender_name, sender_id)) =
+            parse_qq_header_line(line.trim(), &header_pattern, &bracketed_header_pattern)
+        {
             saw_header = true;
             push_current(current.take(), &mut messages, &mut members);
 
-            let timestamp = parse_qq_timestamp(captures.get(1)?.as_str())?;
-            let raw_sender_name = captures.get(2)?.as_str();
-            let sender_name = clean_qq_nickname(raw_sender_name);
-            let sender_id = captures
-                .get(3)
-                .or_else(|| captures.get(4))
-                .map(|value| value.as_str().trim().to_string())
-                .filter(|value| !value.is_empty())
-                .unwrap_or_else(|| sender_name.clone());
-
             current = Some(PendingQqMessage {
                 sender_id,
                 sender_name,
@@ -995,6 +1816,9 @@ fn parse_qq_official_export(content: &str, fallback_chat_name: &str) -> Option<P
         chat_type: ChatType::Group,
         messages,
         members: members.into_values().collect(),
+        encoding: None,
+        salvage: None,
+        chat_type_known: false,
     })
 }
 
@@ -1003,6 +1827,8 @@ fn parse_qq_chat_exporter_json(
     fallback_chat_name: &str,
 ) -> Option<ParsedChat> {
     let (chat_name, chat_type, raw_messages) = extract_qce_export_root(root, fallback_chat_name)?;
+    let chat_type_known = chat_type.is_some();
+    let chat_type = chat_type.unwrap_or(ChatType::Group);
     let mut parsed_messages = Vec::new();
     let mut members = std::collections::BTreeMap::new();
 
@@ -1025,13 +1851,16 @@ fn parse_qq_chat_exporter_json(
         chat_type,
         messages: parsed_messages,
         members: members.into_values().collect(),
+        encoding: None,
+        salvage: None,
+        chat_type_known,
     })
 }
 
 fn extract_qce_export_root<'a>(
     root: &'a serde_json::Value,
     fallback_chat_name: &str,
-) -> Option<(String, ChatType, &'a [serde_json::Value])> {
+) -> Option<(String, Option<ChatType>, &'a [serde_json::Value])> {
     let messages = root.get("messages")?.as_array()?;
     if !messages.iter().any(looks_like_qce_message) {
         return None;
@@ -1046,8 +1875,7 @@ fn extract_qce_export_root<'a>(
         .map(|raw| match raw.trim().to_ascii_lowercase().as_str() {
             "friend" | "private" | "direct" | "dm" => ChatType::Private,
             _ => ChatType::Group,
-        })
-        .unwrap_or(ChatType::Group);
+        });
 
     Some((chat_name, chat_type, messages.as_slice()))
 }
@@ -1092,11 +1920,18 @@ fn extract_qce_sender_identity(
     (sender_id, account_name, display_name)
 }
 
-fn normalize_epoch_seconds(timestamp: i64) -> i64 {
-    if timestamp > 1_000_000_000_000 {
-        timestamp / 1000
+/// Converts an epoch value of unknown unit to seconds.
+///
+/// The unit is inferred from magnitude: values above 1e15 are treated as
+/// microseconds, above 1e12 as milliseconds, and anything else as seconds.
+pub fn normalize_epoch(value: i64) -> i64 {
+    let magnitude = value.unsigned_abs();
+    if magnitude > 1_000_000_000_000_000 {
+        value / 1_000_000
+    } else if magnitude > 1_000_000_000_000 {
+        value / 1000
     } else {
-        timestamp
+        value
     }
 }
 
@@ -1224,7 +2059,7 @@ fn parse_qce_message_value(value: &serde_json::Value) -> Option<ParsedMessage> {
     }
 
     let timestamp = value_get_i64(value, &["timestamp"])
-        .map(normalize_epoch_seconds)
+        .map(normalize_epoch)
         .or_else(|| {
             value_get_string(value, &["time"]).and_then(|raw| {
                 chrono::DateTime::parse_from_rfc3339(raw.trim())
@@ -1238,9 +2073,12 @@ fn parse_qce_message_value(value: &serde_json::Value) -> Option<ParsedMessage> {
     Some(ParsedMessage {
         sender,
         sender_name,
+        sender_group_nickname: None,
         timestamp,
         content,
         msg_type,
+        edited_at: None,
+        platform_message_id: None,
     })
 }
 
@@ -1267,62 +2105,373 @@ impl ChatParser for TelegramParser {
     fn can_parse(&self, path: &Path) -> bool {
         path.to_string_lossy().to_lowercase().contains("telegram")
             || path.extension().map(|e| e == "json").unwrap_or(false)
+            || (path.is_dir() && path.join("result.json").is_file())
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
-        let content = std::fs::read_to_string(path)?;
-
-        #[derive(Deserialize)]
-        struct TelegramExport {
-            name: Option<String>,
-            #[serde(default)]
-            messages: Vec<TelegramMessage>,
-        }
+        self.parse_with_options(path, &ParseOptions::default())
+    }
 
-        #[derive(Deserialize)]
-        struct TelegramMessage {
-            #[serde(rename = "from")]
-            from: Option<String>,
-            #[serde(rename = "from_id")]
-            from_id: Option<String>,
-            date: String,
-            text: Option<String>,
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        if path.is_dir() {
+            return parse_telegram_export_dir(path);
         }
 
-        let export: TelegramExport = serde_json::from_str(&content)?;
+        let content = std::fs::read_to_string(path)?;
+        self.parse_str_with_options(&content, &file_stem_string(path)?, options)
+    }
 
-        let messages: Vec<ParsedMessage> = export
-            .messages
-            .iter()
-            .filter_map(|msg| {
-                let sender = msg
-                    .from
-                    .clone()
-                    .or(msg.from_id.clone())
-                    .unwrap_or_else(|| "Unknown".to_string());
-                let content = msg.text.clone().unwrap_or_default();
-                if content.is_empty() {
-                    return None;
-                }
-
-                Some(ParsedMessage {
-                    sender,
-                    sender_name: None,
-                    timestamp: parse_telegram_timestamp(&msg.date).unwrap_or(0),
-                    content,
-                    msg_type: MessageType::Text,
-                })
-            })
-            .collect();
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        _chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        let (export, salvage): (TelegramExport, _) =
+            parse_json_export(content, options.salvage_json)?;
+        let messages = telegram_messages_to_parsed(&collapse_telegram_edits(export.messages));
 
+        let members = members_from_messages(&messages);
         Ok(ParsedChat {
             platform: "telegram".to_string(),
             chat_name: export.name.unwrap_or_else(|| "Telegram Chat".to_string()),
             chat_type: ChatType::Group,
             messages,
-            members: vec![],
+            members,
+            encoding: None,
+            salvage,
+            chat_type_known: false,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct TelegramExport {
+    name: Option<String>,
+    #[serde(default)]
+    messages: Vec<TelegramMessage>,
+}
+
+#[derive(Deserialize)]
+struct TelegramMessage {
+    id: Option<i64>,
+    #[serde(rename = "from")]
+    from: Option<String>,
+    #[serde(rename = "from_id")]
+    from_id: Option<String>,
+    date: String,
+    text: Option<TelegramText>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    media_type: Option<String>,
+    photo: Option<String>,
+    file: Option<String>,
+    file_name: Option<String>,
+    sticker_emoji: Option<String>,
+    poll: Option<TelegramPoll>,
+    location_information: Option<TelegramLocation>,
+    /// Service event kind, e.g. `invite_members` or `pin_message`.
+    action: Option<String>,
+    actor: Option<String>,
+    actor_id: Option<String>,
+    title: Option<String>,
+    #[serde(default)]
+    members: Vec<String>,
+    edited: Option<String>,
+    edited_unixtime: Option<String>,
+}
+
+/// Telegram `text`: a plain string, or an array mixing strings with entity
+/// objects (`{"type": "link", "text": "..."}`) when the message has links,
+/// mentions or formatting.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TelegramText {
+    Plain(String),
+    Entities(Vec<TelegramTextPart>),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TelegramTextPart {
+    Plain(String),
+    Entity {
+        #[serde(default)]
+        text: String,
+    },
+}
+
+impl TelegramText {
+    fn to_plain(&self) -> String {
+        match self {
+            TelegramText::Plain(text) => text.clone(),
+            TelegramText::Entities(parts) => parts
+                .iter()
+                .map(|part| match part {
+                    TelegramTextPart::Plain(text) | TelegramTextPart::Entity { text } => {
+                        text.as_str()
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TelegramPoll {
+    question: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TelegramLocation {
+    latitude: f64,
+    longitude: f64,
+}
+
+impl TelegramMessage {
+    fn edited_at(&self) -> Option<i64> {
+        self.edited_unixtime
+            .as_deref()
+            .and_then(|raw| raw.trim().parse::<i64>().ok())
+            .or_else(|| self.edited.as_deref().and_then(parse_telegram_timestamp))
+    }
+}
+
+/// Keeps one entry per message id, preferring the most recently edited version,
+/// so an edited message and its earlier revision are not both imported. Entries
+/// without an id are kept as-is; order follows first appearance.
+fn collapse_telegram_edits(messages: Vec<TelegramMessage>) -> Vec<TelegramMessage> {
+    let mut index_by_id: std::collections::HashMap<i64, usize> = std::collections::HashMap::new();
+    let mut out: Vec<TelegramMessage> = Vec::with_capacity(messages.len());
+    for msg in messages {
+        let Some(id) = msg.id else {
+            out.push(msg);
+            continue;
+        };
+        match index_by_id.get(&id) {
+            Some(&slot) => {
+                let newer =
+                    msg.edited_at().unwrap_or(i64::MIN) > out[slot].edited_at().unwrap_or(i64::MIN);
+                if newer {
+                    out[slot] = msg;
+                }
+            }
+            None => {
+                index_by_id.insert(id, out.len());
+                out.push(msg);
+            }
+        }
+    }
+    out
+}
+
+/// Maps Telegram Desktop `type`/`media_type`/`photo`/`file` fields to a
+/// message type, falling back to text.
+fn telegram_native_message_type(msg: &TelegramMessage) -> MessageType {
+    if msg.kind.as_deref() == Some("service") {
+        return MessageType::System;
+    }
+    match msg.media_type.as_deref() {
+        Some("voice_message") | Some("audio_file") => return MessageType::Audio,
+        Some("video_file") | Some("video_message") | Some("animation") => {
+            return MessageType::Video
+        }
+        Some("sticker") => return MessageType::Sticker,
+        _ => {}
+    }
+    if msg.photo.is_some() {
+        return MessageType::Image;
+    }
+    if msg.file.is_some() {
+        return MessageType::File;
+    }
+    if msg.location_information.is_some() {
+        return MessageType::Location;
+    }
+    MessageType::Text
+}
+
+/// Message text, or a description of the media or service event when the
+/// text is empty. `None` only when the entry carries nothing at all.
+fn telegram_message_content(msg: &TelegramMessage, msg_type: &MessageType) -> Option<String> {
+    let non_empty = |value: Option<&str>| {
+        value
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let text = msg.text.as_ref().map(TelegramText::to_plain);
+    if let Some(text) = non_empty(text.as_deref()) {
+        return Some(text);
+    }
+    if let Some(action) = msg.action.as_deref() {
+        return Some(telegram_action_description(msg, action));
+    }
+    if let Some(poll) = &msg.poll {
+        return Some(match non_empty(poll.question.as_deref()) {
+            Some(question) => format!("Poll: {}", question),
+            None => "Poll".to_string(),
+        });
+    }
+    if let Some(location) = &msg.location_information {
+        return Some(format!("{}, {}", location.latitude, location.longitude));
+    }
+    // Exports made without media keep a "(File not included...)" note in
+    // place of the path, so the name comes from `file_name` first.
+    let attachment_name = || {
+        non_empty(msg.file_name.as_deref()).or_else(|| {
+            msg.file
+                .as_deref()
+                .or(msg.photo.as_deref())
+                .filter(|path| !path.starts_with('('))
+                .and_then(|path| Path::new(path).file_name())
+                .map(|name| name.to_string_lossy().to_string())
+        })
+    };
+    match msg_type {
+        MessageType::Sticker => {
+            Some(non_empty(msg.sticker_emoji.as_deref()).unwrap_or_else(|| "Sticker".to_string()))
+        }
+        MessageType::Image => Some(attachment_name().unwrap_or_else(|| "Photo".to_string())),
+        MessageType::Video | MessageType::Audio | MessageType::File => Some(
+            attachment_name()
+                .or_else(|| non_empty(msg.media_type.as_deref()))
+                .unwrap_or_else(|| "File".to_string()),
+        ),
+        _ => None,
+    }
+}
+
+/// Readable text for a Telegram service event.
+fn telegram_action_description(msg: &TelegramMessage, action: &str) -> String {
+    let actor = msg
+        .actor
+        .as_deref()
+        .or(msg.actor_id.as_deref())
+        .unwrap_or("Someone");
+    let members = msg.members.join(", ");
+    let title = msg.title.as_deref().unwrap_or_default();
+    match action {
+        "create_group" | "create_channel" => format!("{} created the group \"{}\"", actor, title),
+        "edit_group_title" => format!("{} changed the group name to \"{}\"", actor, title),
+        "edit_group_photo" => format!("{} changed the group photo", actor),
+        "delete_group_photo" => format!("{} removed the group photo", actor),
+        "invite_members" if !members.is_empty() => format!("{} added {}", actor, members),
+        "remove_members" if !members.is_empty() => format!("{} removed {}", actor, members),
+        "join_group_by_link" => format!("{} joined the group via invite link", actor),
+        "pin_message" => format!("{} pinned a message", actor),
+        "phone_call" | "group_call" => format!("{} started a call", actor),
+        other => format!("{}: {}", actor, other.replace('_', " ")),
+    }
+}
+
+fn telegram_messages_to_parsed(messages: &[TelegramMessage]) -> Vec<ParsedMessage> {
+    messages
+        .iter()
+        .filter_map(|msg| {
+            // Service events have no `from`; the actor stands in as sender.
+            let sender = msg
+                .from
+                .clone()
+                .or(msg.from_id.clone())
+                .or(msg.actor.clone())
+                .or(msg.actor_id.clone())
+                .unwrap_or_else(|| "Unknown".to_string());
+            let msg_type = telegram_native_message_type(msg);
+            let content = telegram_message_content(msg, &msg_type)?;
+
+            Some(ParsedMessage {
+                sender,
+                sender_name: msg.from.clone().or(msg.actor.clone()),
+                sender_group_nickname: None,
+                timestamp: parse_telegram_timestamp(&msg.date).unwrap_or(0),
+                content,
+                msg_type,
+                edited_at: msg.edited_at(),
+                platform_message_id: msg.id.map(|id| id.to_string()),
+            })
+        })
+        .collect()
+}
+
+/// List the JSON parts of a Telegram Desktop export folder.
+///
+/// The primary `result.json` comes first, followed by continuation parts such
+/// as `result2.json` or `messages2.json` in numeric order. HTML parts are not
+/// included. Returns an empty list when the folder has no `result.json`.
+pub fn telegram_export_parts(dir: &Path) -> Vec<std::path::PathBuf> {
+    let primary = dir.join("result.json");
+    if !primary.is_file() {
+        return Vec::new();
+    }
+
+    let mut continuations: Vec<(u64, std::path::PathBuf)> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && path != &primary)
+                .filter_map(|path| {
+                    let stem = path.file_stem()?.to_str()?.to_ascii_lowercase();
+                    let ext = path.extension()?.to_str()?;
+                    if !ext.eq_ignore_ascii_case("json") {
+                        return None;
+                    }
+                    let suffix = stem
+                        .strip_prefix("result")
+                        .or_else(|| stem.strip_prefix("messages"))?;
+                    let part = suffix.trim_start_matches(['_', '-']).parse::<u64>().ok()?;
+                    Some((part, path))
+                })
+                .collect()
         })
+        .unwrap_or_default();
+    continuations.sort();
+
+    let mut parts = vec![primary];
+    parts.extend(continuations.into_iter().map(|(_, path)| path));
+    parts
+}
+
+fn parse_telegram_export_dir(dir: &Path) -> Result<ParsedChat, ParseError> {
+    let parts = telegram_export_parts(dir);
+    if parts.is_empty() {
+        return Err(ParseError::UnsupportedFormat(format!(
+            "telegram export folder has no result.json: {}",
+            dir.display()
+        )));
+    }
+
+    let mut chat_name: Option<String> = None;
+    let mut merged: Vec<TelegramMessage> = Vec::new();
+    for part in &parts {
+        let content = std::fs::read_to_string(part)?;
+        let export: TelegramExport = serde_json::from_str(&content)?;
+        if chat_name.is_none() {
+            chat_name = export.name;
+        }
+        merged.extend(export.messages);
     }
+
+    // Continuation parts may overlap at their boundaries, possibly with a newer edit.
+    let mut messages = telegram_messages_to_parsed(&collapse_telegram_edits(merged));
+    messages.sort_by_key(|msg| msg.timestamp);
+
+    let members = members_from_messages(&messages);
+    Ok(ParsedChat {
+        platform: "telegram".to_string(),
+        chat_name: chat_name.unwrap_or_else(|| "Telegram Chat".to_string()),
+        chat_type: ChatType::Group,
+        messages,
+        members,
+        encoding: None,
+        salvage: None,
+        chat_type_known: false,
+    })
 }
 
 fn parse_telegram_timestamp(s: &str) -> Option<i64> {
@@ -1357,12 +2506,33 @@ impl ChatParser for DiscordParser {
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let content = std::fs::read_to_string(path)?;
-        let root: serde_json::Value = serde_json::from_str(&content)?;
-        let default_chat_name = file_stem_string(path)?;
+        self.parse_str_with_options(&content, &file_stem_string(path)?, options)
+    }
+
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        let (root, salvage): (serde_json::Value, _) =
+            parse_json_export(content, options.salvage_json)?;
         let (chat_name, chat_type, raw_messages) =
-            extract_discord_export_root(&root, &default_chat_name)?;
-        let mut parsed_messages = Vec::new();
+            extract_discord_export_root(&root, chat_name_hint)?;
+        let chat_type_known = chat_type.is_some();
+        let chat_type = chat_type.unwrap_or(ChatType::Group);
+        let mut parsed_messages: Vec<ParsedMessage> = Vec::new();
+        let mut slot_by_id: std::collections::HashMap<String, usize> =
+            std::collections::HashMap::new();
         let mut members = std::collections::BTreeMap::new();
 
         for raw_message in raw_messages {
@@ -1374,13 +2544,22 @@ impl ChatParser for DiscordParser {
                 continue;
             };
             let (account_name, display_name) = extract_discord_author_names(author);
-            let sender_name = display_name.clone().or(account_name.clone());
+            let sender_name =
+                value_get_string(author, DISCORD_GLOBAL_NAME_KEYS).or_else(|| account_name.clone());
+            let sender_group_nickname = value_get_string(author, DISCORD_NICKNAME_KEYS)
+                .filter(|nickname| Some(nickname) != sender_name.as_ref());
             let sender = value_get_string(author, &["ID", "id"])
                 .or_else(|| sender_name.clone())
                 .unwrap_or_else(|| "discord-unknown".to_string());
             let timestamp = value_get_string(raw_message, &["Timestamp", "timestamp"])
                 .and_then(|value| parse_discord_timestamp(&value))
                 .unwrap_or(0);
+            let edited_at = value_get_string(
+                raw_message,
+                &["TimestampEdited", "timestampEdited", "edited_timestamp"],
+            )
+            .and_then(|value| parse_discord_timestamp(&value));
+            let message_id = value_get_string(raw_message, &["ID", "id"]);
 
             let mut parts = Vec::new();
             if let Some(content) = value_get_string(raw_message, &["Content", "content"]) {
@@ -1392,7 +2571,11 @@ impl ChatParser for DiscordParser {
             let attachment_type = append_discord_attachments(raw_message, &mut parts);
             let has_embed = append_discord_embeds(raw_message, &mut parts);
             let has_sticker = append_discord_stickers(raw_message, &mut parts);
-            let raw_type = value_get_string(raw_message, &["Type", "type"]);
+            let raw_type = value_get_string(raw_message, &["Type", "type"]).map(|raw| {
+                discord_numeric_type_name(&raw)
+                    .map(str::to_string)
+                    .unwrap_or(raw)
+            });
             let msg_type = infer_discord_message_type(
                 raw_type.as_deref(),
                 attachment_type,
@@ -1414,13 +2597,35 @@ impl ChatParser for DiscordParser {
             }
 
             upsert_member(&mut members, sender.clone(), account_name, display_name);
-            parsed_messages.push(ParsedMessage {
+            let parsed = ParsedMessage {
                 sender,
                 sender_name,
+                sender_group_nickname,
                 timestamp,
                 content,
                 msg_type,
-            });
+                edited_at,
+                platform_message_id: message_id.clone(),
+            };
+            // Keep only the latest revision when an export repeats an edited message.
+            let existing_slot = message_id
+                .as_ref()
+                .and_then(|id| slot_by_id.get(id).copied());
+            match existing_slot {
+                Some(slot) => {
+                    let newer = parsed.edited_at.unwrap_or(i64::MIN)
+                        > parsed_messages[slot].edited_at.unwrap_or(i64::MIN);
+                    if newer {
+                        parsed_messages[slot] = parsed;
+                    }
+                }
+                None => {
+                    if let Some(id) = message_id {
+                        slot_by_id.insert(id, parsed_messages.len());
+                    }
+                    parsed_messages.push(parsed);
+                }
+            }
         }
 
         Ok(ParsedChat {
@@ -1429,6 +2634,9 @@ impl ChatParser for DiscordParser {
             chat_type,
             messages: parsed_messages,
             members: members.into_values().collect(),
+            encoding: None,
+            salvage,
+            chat_type_known,
         })
     }
 }
@@ -1440,18 +2648,15 @@ fn parse_discord_timestamp(s: &str) -> Option<i64> {
         .map(|dt| dt.timestamp())
 }
 
+/// Chat name, the chat type if the export states it, and the raw messages.
 fn extract_discord_export_root<'a>(
     root: &'a serde_json::Value,
     fallback_chat_name: &str,
-) -> Result<(String, ChatType, &'a [serde_json::Value]), ParseError> {
+) -> Result<(String, Option<ChatType>, &'a [serde_json::Value]), ParseError> {
     match root {
         serde_json::Value::Array(items) => {
             if items.iter().any(looks_like_discord_message) {
-                Ok((
-                    fallback_chat_name.to_string(),
-                    ChatType::Group,
-                    items.as_slice(),
-                ))
+                Ok((fallback_chat_name.to_string(), None, items.as_slice()))
             } else {
                 Err(ParseError::InvalidFormat(
                     "discord export array did not contain discord-like messages".to_string(),
@@ -1478,16 +2683,17 @@ fn extract_discord_export_root<'a>(
             let channel_name = value_get_any(root, &["channel"])
                 .and_then(|value| value_get_string(value, &["name", "Name"]))
                 .unwrap_or_else(|| fallback_chat_name.to_string());
-            let channel_type = value_get_any(root, &["channel"])
+            let chat_type = value_get_any(root, &["channel"])
                 .and_then(|value| value_get_string(value, &["type", "Type"]))
-                .unwrap_or_default();
-            let chat_type = if channel_type.eq_ignore_ascii_case("directmessage")
-                || channel_type.eq_ignore_ascii_case("dm")
-            {
-                ChatType::Private
-            } else {
-                ChatType::Group
-            };
+                .map(|channel_type| {
+                    if channel_type.eq_ignore_ascii_case("directmessage")
+                        || channel_type.eq_ignore_ascii_case("dm")
+                    {
+                        ChatType::Private
+                    } else {
+                        ChatType::Group
+                    }
+                });
             let chat_name = guild_name
                 .map(|guild| format!("{} / #{}", guild, channel_name))
                 .unwrap_or(channel_name);
@@ -1504,6 +2710,19 @@ fn looks_like_discord_message(value: &serde_json::Value) -> bool {
         && value_get_any(value, &["Timestamp", "timestamp"]).is_some()
 }
 
+/// Server-specific nickname keys on a Discord author.
+const DISCORD_NICKNAME_KEYS: &[&str] = &["nickname", "Nickname", "nickName", "NickName"];
+
+/// Account-wide display name keys on a Discord author.
+const DISCORD_GLOBAL_NAME_KEYS: &[&str] = &[
+    "displayName",
+    "DisplayName",
+    "display_name",
+    "globalName",
+    "GlobalName",
+    "global_name",
+];
+
 fn extract_discord_author_names(author: &serde_json::Value) -> (Option<String>, Option<String>) {
     let account_name = value_get_string(
         author,
@@ -1511,22 +2730,9 @@ fn extract_discord_author_names(author: &serde_json::Value) -> (Option<String>,
             "Name", "name", "username", "Username", "userName", "UserName",
         ],
     );
-    let display_name = value_get_string(
-        author,
-        &[
-            "nickname",
-            "Nickname",
-            "nickName",
-            "NickName",
-            "displayName",
-            "DisplayName",
-            "display_name",
-            "globalName",
-            "GlobalName",
-            "global_name",
-        ],
-    )
-    .or_else(|| account_name.clone());
+    let display_name = value_get_string(author, DISCORD_NICKNAME_KEYS)
+        .or_else(|| value_get_string(author, DISCORD_GLOBAL_NAME_KEYS))
+        .or_else(|| account_name.clone());
 
     (account_name, display_name)
 }
@@ -1601,8 +2807,8 @@ fn append_discord_embeds(message: &serde_json::Value, parts: &mut Vec<String>) -
 }
 
 fn append_discord_stickers(message: &serde_json::Value, parts: &mut Vec<String>) -> bool {
-    let Some(stickers) =
-        value_get_any(message, &["Stickers", "stickers"]).and_then(|value| value.as_array())
+    let Some(stickers) = value_get_any(message, &["Stickers", "stickers", "sticker_items"])
+        .and_then(|value| value.as_array())
     else {
         return false;
     };
@@ -1617,6 +2823,29 @@ fn append_discord_stickers(message: &serde_json::Value, parts: &mut Vec<String>)
     appended
 }
 
+/// Discord's own data and API dumps store `type` as a number; map the codes
+/// to the names DiscordChatExporter writes so both shapes classify alike.
+fn discord_numeric_type_name(raw: &str) -> Option<&'static str> {
+    let name = match raw.trim().parse::<u32>().ok()? {
+        0 => "Default",
+        1 => "RecipientAdd",
+        2 => "RecipientRemove",
+        3 => "Call",
+        4 => "ChannelNameChange",
+        5 => "ChannelIconChange",
+        6 => "ChannelPinnedMessage",
+        7 => "UserJoin",
+        8..=11 => "GuildBoost",
+        18 => "ThreadCreated",
+        19 => "Reply",
+        20 => "ChatInputCommand",
+        21 => "ThreadStarterMessage",
+        24 => "AutoModerationAction",
+        _ => return None,
+    };
+    Some(name)
+}
+
 fn infer_discord_message_type(
     raw_type: Option<&str>,
     attachment_type: Option<MessageType>,
@@ -1633,6 +2862,10 @@ fn infer_discord_message_type(
             | "threadStarterMessage" => {}
             "ChannelPinnedMessage"
             | "channelPinnedMessage"
+            | "ChannelNameChange"
+            | "channelNameChange"
+            | "ChannelIconChange"
+            | "channelIconChange"
             | "UserJoin"
             | "userJoin"
             | "RecipientAdd"
@@ -1691,11 +2924,30 @@ impl ChatParser for WeChatParser {
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let content = std::fs::read_to_string(path)?;
-        let root: serde_json::Value = serde_json::from_str(&content)?;
-        let default_chat_name = file_stem_string(path)?;
+        self.parse_str_with_options(&content, &file_stem_string(path)?, options)
+    }
+
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        let (root, salvage): (serde_json::Value, _) =
+            parse_json_export(content, options.salvage_json)?;
         let (chat_name, chat_type, raw_messages) =
-            extract_wechat_export_root(&root, &default_chat_name)?;
+            extract_wechat_export_root(&root, chat_name_hint)?;
+        let chat_type_known = chat_type.is_some();
+        let chat_type = chat_type.unwrap_or(ChatType::Group);
         let mut parsed_messages = Vec::new();
         let mut members = std::collections::BTreeMap::new();
 
@@ -1718,22 +2970,22 @@ impl ChatParser for WeChatParser {
             chat_type,
             messages: parsed_messages,
             members: members.into_values().collect(),
+            encoding: None,
+            salvage,
+            chat_type_known,
         })
     }
 }
 
+/// Chat name, the chat type if the export states it, and the raw messages.
 fn extract_wechat_export_root<'a>(
     root: &'a serde_json::Value,
     fallback_chat_name: &str,
-) -> Result<(String, ChatType, &'a [serde_json::Value]), ParseError> {
+) -> Result<(String, Option<ChatType>, &'a [serde_json::Value]), ParseError> {
     match root {
         serde_json::Value::Array(items) => {
             if items.iter().any(looks_like_wechat_message) {
-                Ok((
-                    fallback_chat_name.to_string(),
-                    ChatType::Group,
-                    items.as_slice(),
-                ))
+                Ok((fallback_chat_name.to_string(), None, items.as_slice()))
             } else {
                 Err(ParseError::InvalidFormat(
                     "wechat export array did not contain wechat-like messages".to_string(),
@@ -1782,8 +3034,7 @@ fn extract_wechat_export_root<'a>(
                             ChatType::Private
                         }
                     })
-                })
-                .unwrap_or(ChatType::Group);
+                });
 
             Ok((chat_name, chat_type, messages.as_slice()))
         }
@@ -1826,24 +3077,32 @@ fn infer_wechat_message_type(value: &serde_json::Value) -> MessageType {
         }
     }
 
-    match value_get_i64(value, &["type"]) {
-        Some(3) => MessageType::Image,
-        Some(34) => MessageType::Audio,
-        Some(43) => MessageType::Video,
-        Some(47) => MessageType::Sticker,
-        Some(48) => MessageType::Location,
-        Some(49) => MessageType::Link,
-        Some(10000) => MessageType::System,
-        _ => match value_get_i64(value, &["localType"]) {
-            Some(3) => MessageType::Image,
-            Some(34) => MessageType::Audio,
-            Some(43) => MessageType::Video,
-            Some(47) => MessageType::Sticker,
-            Some(48) => MessageType::Location,
-            Some(49) => MessageType::Link,
-            Some(10000) => MessageType::System,
-            _ => MessageType::Text,
+    value_get_i64(value, &["type"])
+        .and_then(|code| wechat_native_message_type(code, value))
+        .or_else(|| {
+            value_get_i64(value, &["localType"])
+                .and_then(|code| wechat_native_message_type(code, value))
+        })
+        .unwrap_or(MessageType::Text)
+}
+
+/// Maps a native WeChat `type`/`localType` code; `None` for unknown codes.
+///
+/// App messages (49) carry a sub type; sub type 6 is a file transfer.
+fn wechat_native_message_type(code: i64, value: &serde_json::Value) -> Option<MessageType> {
+    match code {
+        1 => Some(MessageType::Text),
+        3 => Some(MessageType::Image),
+        34 => Some(MessageType::Audio),
+        43 | 62 => Some(MessageType::Video),
+        47 => Some(MessageType::Sticker),
+        48 => Some(MessageType::Location),
+        49 => match value_get_i64(value, &["subType", "appMsgType", "sub_type"]) {
+            Some(6) => Some(MessageType::File),
+            _ => Some(MessageType::Link),
         },
+        10000 | 10002 => Some(MessageType::System),
+        _ => None,
     }
 }
 
@@ -1873,12 +3132,29 @@ fn parse_wechat_message_value(value: &serde_json::Value) -> Option<ParsedMessage
             .unwrap_or_else(|| "wechat-unknown".to_string())
     };
 
+    // Server ids are global; `localId` only numbers rows in one device's database.
+    let platform_message_id = value_get_string(
+        value,
+        &[
+            "serverId",
+            "msgSvrId",
+            "MsgSvrID",
+            "msg_svr_id",
+            "msgId",
+            "msg_id",
+        ],
+    )
+    .filter(|id| !id.trim().is_empty() && id.trim() != "0");
+
     Some(ParsedMessage {
         sender,
         sender_name,
+        sender_group_nickname: None,
         timestamp: value_get_i64(value, &["create_time", "createTime"]).unwrap_or(0),
         content,
         msg_type: infer_wechat_message_type(value),
+        edited_at: None,
+        platform_message_id,
     })
 }
 
@@ -1907,8 +3183,24 @@ impl ChatParser for InstagramParser {
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let content = std::fs::read_to_string(path)?;
+        self.parse_str_with_options(&content, &file_stem_string(path)?, options)
+    }
 
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         #[derive(Deserialize)]
         struct InstagramMessage {
             #[serde(rename = "sender")]
@@ -1919,7 +3211,8 @@ impl ChatParser for InstagramParser {
             content: Option<String>,
         }
 
-        let messages: Vec<InstagramMessage> = serde_json::from_str(&content)?;
+        let (messages, salvage): (Vec<InstagramMessage>, _) =
+            parse_json_export(content, options.salvage_json)?;
 
         let parsed_messages: Vec<ParsedMessage> = messages
             .iter()
@@ -1933,23 +3226,26 @@ impl ChatParser for InstagramParser {
                 Some(ParsedMessage {
                     sender,
                     sender_name: None,
-                    timestamp: msg.timestamp,
+                    sender_group_nickname: None,
+                    timestamp: normalize_epoch(msg.timestamp),
+                    msg_type: classify_message_type(&content, "instagram"),
                     content,
-                    msg_type: MessageType::Text,
+                    edited_at: None,
+                    platform_message_id: None,
                 })
             })
             .collect();
 
+        let members = members_from_messages(&parsed_messages);
         Ok(ParsedChat {
             platform: "instagram".to_string(),
-            chat_name: path
-                .file_stem()
-                .ok_or(ParseError::InvalidFormat("missing file stem".to_string()))?
-                .to_string_lossy()
-                .to_string(),
+            chat_name: chat_name_hint.to_string(),
             chat_type: ChatType::Private,
             messages: parsed_messages,
-            members: vec![],
+            members,
+            encoding: None,
+            salvage,
+            chat_type_known: false,
         })
     }
 }
@@ -1981,8 +3277,24 @@ impl ChatParser for IMessageParser {
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let content = std::fs::read_to_string(path)?;
+        self.parse_str_with_options(&content, &file_stem_string(path)?, options)
+    }
 
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         #[derive(Deserialize)]
         struct IMessage {
             text: Option<String>,
@@ -1990,7 +3302,8 @@ impl ChatParser for IMessageParser {
             date: Option<String>,
         }
 
-        let messages: Vec<IMessage> = serde_json::from_str(&content).unwrap_or_else(|_| vec![]);
+        let (messages, salvage): (Vec<IMessage>, _) =
+            parse_json_export(content, options.salvage_json).unwrap_or_else(|_| (vec![], None));
 
         let parsed_messages: Vec<ParsedMessage> = messages
             .iter()
@@ -2004,23 +3317,26 @@ impl ChatParser for IMessageParser {
                 Some(ParsedMessage {
                     sender,
                     sender_name: None,
+                    sender_group_nickname: None,
                     timestamp: parse_imessage_timestamp(msg.date.as_deref()).unwrap_or(0),
+                    msg_type: classify_message_type(&content, "imessage"),
                     content,
-                    msg_type: MessageType::Text,
+                    edited_at: None,
+                    platform_message_id: None,
                 })
             })
             .collect();
 
+        let members = members_from_messages(&parsed_messages);
         Ok(ParsedChat {
             platform: "imessage".to_string(),
-            chat_name: path
-                .file_stem()
-                .ok_or(ParseError::InvalidFormat("missing file stem".to_string()))?
-                .to_string_lossy()
-                .to_string(),
+            chat_name: chat_name_hint.to_string(),
             chat_type: ChatType::Private,
             messages: parsed_messages,
-            members: vec![],
+            members,
+            encoding: None,
+            salvage,
+            chat_type_known: false,
         })
     }
 }
@@ -2059,8 +3375,24 @@ impl ChatParser for MessengerParser {
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let content = std::fs::read_to_string(path)?;
+        self.parse_str_with_options(&content, &file_stem_string(path)?, options)
+    }
 
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         #[derive(Deserialize)]
         struct MessengerMessage {
             sender_name: Option<String>,
@@ -2068,8 +3400,8 @@ impl ChatParser for MessengerParser {
             content: Option<String>,
         }
 
-        let messages: Vec<MessengerMessage> =
-            serde_json::from_str(&content).unwrap_or_else(|_| vec![]);
+        let (messages, salvage): (Vec<MessengerMessage>, _) =
+            parse_json_export(content, options.salvage_json).unwrap_or_else(|_| (vec![], None));
 
         let parsed_messages: Vec<ParsedMessage> = messages
             .iter()
@@ -2083,23 +3415,26 @@ impl ChatParser for MessengerParser {
                 Some(ParsedMessage {
                     sender,
                     sender_name: None,
-                    timestamp: msg.timestamp_ms.unwrap_or(0) / 1000,
+                    sender_group_nickname: None,
+                    timestamp: normalize_epoch(msg.timestamp_ms.unwrap_or(0)),
+                    msg_type: classify_message_type(&content, "messenger"),
                     content,
-                    msg_type: MessageType::Text,
+                    edited_at: None,
+                    platform_message_id: None,
                 })
             })
             .collect();
 
+        let members = members_from_messages(&parsed_messages);
         Ok(ParsedChat {
             platform: "messenger".to_string(),
-            chat_name: path
-                .file_stem()
-                .ok_or(ParseError::InvalidFormat("missing file stem".to_string()))?
-                .to_string_lossy()
-                .to_string(),
+            chat_name: chat_name_hint.to_string(),
             chat_type: ChatType::Private,
             messages: parsed_messages,
-            members: vec![],
+            members,
+            encoding: None,
+            salvage,
+            chat_type_known: false,
         })
     }
 }
@@ -2131,8 +3466,24 @@ impl ChatParser for KakaoTalkParser {
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let content = std::fs::read_to_string(path)?;
+        self.parse_str_with_options(&content, &file_stem_string(path)?, options)
+    }
 
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         #[derive(Deserialize)]
         struct KakaoMessage {
             sender: Option<String>,
@@ -2140,7 +3491,8 @@ impl ChatParser for KakaoTalkParser {
             date: Option<String>,
         }
 
-        let messages: Vec<KakaoMessage> = serde_json::from_str(&content).unwrap_or_else(|_| vec![]);
+        let (messages, salvage): (Vec<KakaoMessage>, _) =
+            parse_json_export(content, options.salvage_json).unwrap_or_else(|_| (vec![], None));
 
         let parsed_messages: Vec<ParsedMessage> = messages
             .iter()
@@ -2154,23 +3506,26 @@ impl ChatParser for KakaoTalkParser {
                 Some(ParsedMessage {
                     sender,
                     sender_name: None,
+                    sender_group_nickname: None,
                     timestamp: parse_kakao_timestamp(msg.date.as_deref()).unwrap_or(0),
+                    msg_type: classify_message_type(&content, "kakaotalk"),
                     content,
-                    msg_type: MessageType::Text,
+                    edited_at: None,
+                    platform_message_id: None,
                 })
             })
             .collect();
 
+        let members = members_from_messages(&parsed_messages);
         Ok(ParsedChat {
             platform: "kakaotalk".to_string(),
-            chat_name: path
-                .file_stem()
-                .ok_or(ParseError::InvalidFormat("missing file stem".to_string()))?
-                .to_string_lossy()
-                .to_string(),
+            chat_name: chat_name_hint.to_string(),
             chat_type: ChatType::Group,
             messages: parsed_messages,
-            members: vec![],
+            members,
+            encoding: None,
+            salvage,
+            chat_type_known: false,
         })
     }
 }
@@ -2207,8 +3562,24 @@ impl ChatParser for SlackParser {
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let content = std::fs::read_to_string(path)?;
+        self.parse_str_with_options(&content, &file_stem_string(path)?, options)
+    }
 
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         #[derive(Deserialize)]
         struct SlackMessage {
             user: Option<String>,
@@ -2216,7 +3587,8 @@ impl ChatParser for SlackParser {
             text: Option<String>,
         }
 
-        let messages: Vec<SlackMessage> = serde_json::from_str(&content).unwrap_or_else(|_| vec![]);
+        let (messages, salvage): (Vec<SlackMessage>, _) =
+            parse_json_export(content, options.salvage_json).unwrap_or_else(|_| (vec![], None));
 
         let parsed_messages: Vec<ParsedMessage> = messages
             .iter()
@@ -2230,23 +3602,26 @@ impl ChatParser for SlackParser {
                 Some(ParsedMessage {
                     sender,
                     sender_name: None,
+                    sender_group_nickname: None,
                     timestamp: parse_slack_timestamp(msg.ts.as_deref()).unwrap_or(0),
+                    msg_type: classify_message_type(&content, "slack"),
                     content,
-                    msg_type: MessageType::Text,
+                    edited_at: None,
+                    platform_message_id: None,
                 })
             })
             .collect();
 
+        let members = members_from_messages(&parsed_messages);
         Ok(ParsedChat {
             platform: "slack".to_string(),
-            chat_name: path
-                .file_stem()
-                .ok_or(ParseError::InvalidFormat("missing file stem".to_string()))?
-                .to_string_lossy()
-                .to_string(),
+            chat_name: chat_name_hint.to_string(),
             chat_type: ChatType::Group,
             messages: parsed_messages,
-            members: vec![],
+            members,
+            encoding: None,
+            salvage,
+            chat_type_known: false,
         })
     }
 }
@@ -2282,8 +3657,24 @@ impl ChatParser for TeamsParser {
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let content = std::fs::read_to_string(path)?;
+        self.parse_str_with_options(&content, &file_stem_string(path)?, options)
+    }
 
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         #[derive(Deserialize)]
         struct TeamsMessage {
             from: Option<String>,
@@ -2291,7 +3682,8 @@ impl ChatParser for TeamsParser {
             content: Option<String>,
         }
 
-        let messages: Vec<TeamsMessage> = serde_json::from_str(&content).unwrap_or_else(|_| vec![]);
+        let (messages, salvage): (Vec<TeamsMessage>, _) =
+            parse_json_export(content, options.salvage_json).unwrap_or_else(|_| (vec![], None));
 
         let parsed_messages: Vec<ParsedMessage> = messages
             .iter()
@@ -2305,23 +3697,26 @@ impl ChatParser for TeamsParser {
                 Some(ParsedMessage {
                     sender,
                     sender_name: None,
+                    sender_group_nickname: None,
                     timestamp: parse_teams_timestamp(msg.date.as_deref()).unwrap_or(0),
+                    msg_type: classify_message_type(&content, "teams"),
                     content,
-                    msg_type: MessageType::Text,
+                    edited_at: None,
+                    platform_message_id: None,
                 })
             })
             .collect();
 
+        let members = members_from_messages(&parsed_messages);
         Ok(ParsedChat {
             platform: "teams".to_string(),
-            chat_name: path
-                .file_stem()
-                .ok_or(ParseError::InvalidFormat("missing file stem".to_string()))?
-                .to_string_lossy()
-                .to_string(),
+            chat_name: chat_name_hint.to_string(),
             chat_type: ChatType::Group,
             messages: parsed_messages,
-            members: vec![],
+            members,
+            encoding: None,
+            salvage,
+            chat_type_known: false,
         })
     }
 }
@@ -2358,8 +3753,24 @@ impl ChatParser for SignalParser {
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let content = std::fs::read_to_string(path)?;
+        self.parse_str_with_options(&content, &file_stem_string(path)?, options)
+    }
 
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         #[derive(Deserialize)]
         struct SignalMessage {
             sender: Option<String>,
@@ -2367,8 +3778,8 @@ impl ChatParser for SignalParser {
             body: Option<String>,
         }
 
-        let messages: Vec<SignalMessage> =
-            serde_json::from_str(&content).unwrap_or_else(|_| vec![]);
+        let (messages, salvage): (Vec<SignalMessage>, _) =
+            parse_json_export(content, options.salvage_json).unwrap_or_else(|_| (vec![], None));
 
         let parsed_messages: Vec<ParsedMessage> = messages
             .iter()
@@ -2382,23 +3793,26 @@ impl ChatParser for SignalParser {
                 Some(ParsedMessage {
                     sender,
                     sender_name: None,
-                    timestamp: msg.timestamp.unwrap_or(0) / 1000,
+                    sender_group_nickname: None,
+                    timestamp: normalize_epoch(msg.timestamp.unwrap_or(0)),
+                    msg_type: classify_message_type(&content, "signal"),
                     content,
-                    msg_type: MessageType::Text,
+                    edited_at: None,
+                    platform_message_id: None,
                 })
             })
             .collect();
 
+        let members = members_from_messages(&parsed_messages);
         Ok(ParsedChat {
             platform: "signal".to_string(),
-            chat_name: path
-                .file_stem()
-                .ok_or(ParseError::InvalidFormat("missing file stem".to_string()))?
-                .to_string_lossy()
-                .to_string(),
+            chat_name: chat_name_hint.to_string(),
             chat_type: ChatType::Private,
             messages: parsed_messages,
-            members: vec![],
+            members,
+            encoding: None,
+            salvage,
+            chat_type_known: false,
         })
     }
 }
@@ -2429,8 +3843,24 @@ impl ChatParser for SkypeParser {
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let content = std::fs::read_to_string(path)?;
+        self.parse_str_with_options(&content, &file_stem_string(path)?, options)
+    }
 
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         #[derive(Deserialize)]
         struct SkypeMessage {
             sender: Option<String>,
@@ -2438,7 +3868,8 @@ impl ChatParser for SkypeParser {
             msg_content: Option<String>,
         }
 
-        let messages: Vec<SkypeMessage> = serde_json::from_str(&content).unwrap_or_else(|_| vec![]);
+        let (messages, salvage): (Vec<SkypeMessage>, _) =
+            parse_json_export(content, options.salvage_json).unwrap_or_else(|_| (vec![], None));
 
         let parsed_messages: Vec<ParsedMessage> = messages
             .iter()
@@ -2452,23 +3883,26 @@ impl ChatParser for SkypeParser {
                 Some(ParsedMessage {
                     sender,
                     sender_name: None,
+                    sender_group_nickname: None,
                     timestamp: parse_skype_timestamp(msg.datetime.as_deref()).unwrap_or(0),
+                    msg_type: classify_message_type(&content, "skype"),
                     content,
-                    msg_type: MessageType::Text,
+                    edited_at: None,
+                    platform_message_id: None,
                 })
             })
             .collect();
 
+        let members = members_from_messages(&parsed_messages);
         Ok(ParsedChat {
             platform: "skype".to_string(),
-            chat_name: path
-                .file_stem()
-                .ok_or(ParseError::InvalidFormat("missing file stem".to_string()))?
-                .to_string_lossy()
-                .to_string(),
+            chat_name: chat_name_hint.to_string(),
             chat_type: ChatType::Private,
             messages: parsed_messages,
-            members: vec![],
+            members,
+            encoding: None,
+            salvage,
+            chat_type_known: false,
         })
     }
 }
@@ -2506,8 +3940,24 @@ impl ChatParser for GoogleChatParser {
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let content = std::fs::read_to_string(path)?;
+        self.parse_str_with_options(&content, &file_stem_string(path)?, options)
+    }
 
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         #[derive(Deserialize)]
         struct GoogleChatMessage {
             sender: Option<GoogleChatSender>,
@@ -2521,8 +3971,8 @@ impl ChatParser for GoogleChatParser {
             display_name: Option<String>,
         }
 
-        let messages: Vec<GoogleChatMessage> =
-            serde_json::from_str(&content).unwrap_or_else(|_| vec![]);
+        let (messages, salvage): (Vec<GoogleChatMessage>, _) =
+            parse_json_export(content, options.salvage_json).unwrap_or_else(|_| (vec![], None));
 
         let parsed_messages: Vec<ParsedMessage> = messages
             .iter()
@@ -2537,23 +3987,26 @@ impl ChatParser for GoogleChatParser {
                 Some(ParsedMessage {
                     sender: sender_name.clone(),
                     sender_name: Some(sender_name),
+                    sender_group_nickname: None,
                     timestamp: parse_googlechat_timestamp(msg.create_time.as_deref()).unwrap_or(0),
+                    msg_type: classify_message_type(&content, "googlechat"),
                     content,
-                    msg_type: MessageType::Text,
+                    edited_at: None,
+                    platform_message_id: None,
                 })
             })
             .collect();
 
+        let members = members_from_messages(&parsed_messages);
         Ok(ParsedChat {
             platform: "googlechat".to_string(),
-            chat_name: path
-                .file_stem()
-                .ok_or(ParseError::InvalidFormat("missing file stem".to_string()))?
-                .to_string_lossy()
-                .to_string(),
+            chat_name: chat_name_hint.to_string(),
             chat_type: ChatType::Group,
             messages: parsed_messages,
-            members: vec![],
+            members,
+            encoding: None,
+            salvage,
+            chat_type_known: false,
         })
     }
 }
@@ -2590,8 +4043,24 @@ impl ChatParser for ZoomParser {
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let content = std::fs::read_to_string(path)?;
+        self.parse_str_with_options(&content, &file_stem_string(path)?, options)
+    }
 
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         #[derive(Deserialize)]
         struct ZoomMessage {
             sender: Option<String>,
@@ -2599,7 +4068,8 @@ impl ChatParser for ZoomParser {
             message: Option<String>,
         }
 
-        let messages: Vec<ZoomMessage> = serde_json::from_str(&content).unwrap_or_else(|_| vec![]);
+        let (messages, salvage): (Vec<ZoomMessage>, _) =
+            parse_json_export(content, options.salvage_json).unwrap_or_else(|_| (vec![], None));
 
         let parsed_messages: Vec<ParsedMessage> = messages
             .iter()
@@ -2613,23 +4083,26 @@ impl ChatParser for ZoomParser {
                 Some(ParsedMessage {
                     sender,
                     sender_name: None,
+                    sender_group_nickname: None,
                     timestamp: parse_zoom_timestamp(msg.timestamp.as_deref()).unwrap_or(0),
+                    msg_type: classify_message_type(&content, "zoom"),
                     content,
-                    msg_type: MessageType::Text,
+                    edited_at: None,
+                    platform_message_id: None,
                 })
             })
             .collect();
 
+        let members = members_from_messages(&parsed_messages);
         Ok(ParsedChat {
             platform: "zoom".to_string(),
-            chat_name: path
-                .file_stem()
-                .ok_or(ParseError::InvalidFormat("missing file stem".to_string()))?
-                .to_string_lossy()
-                .to_string(),
+            chat_name: chat_name_hint.to_string(),
             chat_type: ChatType::Group,
             messages: parsed_messages,
-            members: vec![],
+            members,
+            encoding: None,
+            salvage,
+            chat_type_known: false,
         })
     }
 }
@@ -2666,8 +4139,24 @@ impl ChatParser for ViberParser {
     }
 
     fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         let content = std::fs::read_to_string(path)?;
+        self.parse_str_with_options(&content, &file_stem_string(path)?, options)
+    }
 
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
         #[derive(Deserialize)]
         struct ViberMessage {
             sender: Option<String>,
@@ -2675,7 +4164,8 @@ impl ChatParser for ViberParser {
             text: Option<String>,
         }
 
-        let messages: Vec<ViberMessage> = serde_json::from_str(&content).unwrap_or_else(|_| vec![]);
+        let (messages, salvage): (Vec<ViberMessage>, _) =
+            parse_json_export(content, options.salvage_json).unwrap_or_else(|_| (vec![], None));
 
         let parsed_messages: Vec<ParsedMessage> = messages
             .iter()
@@ -2689,23 +4179,26 @@ impl ChatParser for ViberParser {
                 Some(ParsedMessage {
                     sender,
                     sender_name: None,
+                    sender_group_nickname: None,
                     timestamp: parse_viber_timestamp(msg.date_time.as_deref()).unwrap_or(0),
+                    msg_type: classify_message_type(&content, "viber"),
                     content,
-                    msg_type: MessageType::Text,
+                    edited_at: None,
+                    platform_message_id: None,
                 })
             })
             .collect();
 
+        let members = members_from_messages(&parsed_messages);
         Ok(ParsedChat {
             platform: "viber".to_string(),
-            chat_name: path
-                .file_stem()
-                .ok_or(ParseError::InvalidFormat("missing file stem".to_string()))?
-                .to_string_lossy()
-                .to_string(),
+            chat_name: chat_name_hint.to_string(),
             chat_type: ChatType::Private,
             messages: parsed_messages,
-            members: vec![],
+            members,
+            encoding: None,
+            salvage,
+            chat_type_known: false,
         })
     }
 }
@@ -2716,55 +4209,499 @@ fn parse_viber_timestamp(s: Option<&str>) -> Option<i64> {
         .map(|dt| dt.timestamp())
 }
 
-macro_rules! impl_default_parser_via_new {
-    ($($parser:ty),+ $(,)?) => {
-        $(
-            impl Default for $parser {
-                fn default() -> Self {
-                    Self::new()
-                }
-            }
-        )+
-    };
+/// Parser for generic CSV chat exports.
+///
+/// The header row names the columns; `timestamp`/`time`/`date`,
+/// `sender`/`from`/`author` and `content`/`text`/`message` are recognised
+/// case-insensitively and other columns are ignored. Comma, semicolon and tab
+/// delimiters are detected from the header.
+pub struct CsvChatParser {
+    name_str: String,
 }
 
-impl_default_parser_via_new!(
-    WhatsAppParser,
-    LINEParser,
-    QQParser,
-    TelegramParser,
-    DiscordParser,
-    WeChatParser,
-    InstagramParser,
-    IMessageParser,
-    MessengerParser,
-    KakaoTalkParser,
-    SlackParser,
-    TeamsParser,
-    SignalParser,
-    SkypeParser,
-    GoogleChatParser,
-    ZoomParser,
-    ViberParser,
-);
+impl CsvChatParser {
+    /// Creates a new CsvChatParser instance.
+    pub fn new() -> Self {
+        Self {
+            name_str: "csv".to_string(),
+        }
+    }
+}
 
-#[cfg(test)]
-mod tests {
-    use super::{ParseError, ParserRegistry};
-    use std::collections::HashSet;
-    use std::sync::atomic::{AtomicU64, Ordering};
-    use std::time::{SystemTime, UNIX_EPOCH};
+const CSV_TIMESTAMP_COLUMNS: &[&str] = &["timestamp", "time", "date"];
+const CSV_SENDER_COLUMNS: &[&str] = &["sender", "from", "author"];
+const CSV_CONTENT_COLUMNS: &[&str] = &["content", "text", "message"];
 
-    static TEST_FILE_SEQ: AtomicU64 = AtomicU64::new(0);
+impl ChatParser for CsvChatParser {
+    fn name(&self) -> &str {
+        &self.name_str
+    }
 
-    fn write_temp_file(prefix: &str, extension: &str, content: &str) -> std::path::PathBuf {
-        let epoch_nanos = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_nanos())
-            .unwrap_or(0);
-        let seq = TEST_FILE_SEQ.fetch_add(1, Ordering::Relaxed);
-        let path = std::env::temp_dir().join(format!(
-            "xenobot_parser_{prefix}_{epoch_nanos}_{seq}.{extension}"
+    fn can_parse(&self, path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+            && !file_looks_binary(path)
+    }
+
+    fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        self.parse_with_options(path, &ParseOptions::default())
+    }
+
+    fn parse_with_options(
+        &self,
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        reject_binary_file(path)?;
+        let (content, source_encoding) = read_text_file(path, options.encoding)?;
+        let parsed = self.parse_str_with_options(&content, &file_stem_string(path)?, options)?;
+        Ok(ParsedChat {
+            encoding: Some(source_encoding),
+            ..parsed
+        })
+    }
+
+    fn parse_str_with_options(
+        &self,
+        content: &str,
+        chat_name_hint: &str,
+        _options: &ParseOptions,
+    ) -> Result<ParsedChat, ParseError> {
+        let mut records = csv_records(content).into_iter();
+        let header: Vec<String> = records
+            .next()
+            .ok_or_else(|| ParseError::InvalidFormat("csv export is empty".to_string()))?
+            .iter()
+            .map(|name| name.trim().to_ascii_lowercase())
+            .collect();
+        let column = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| header.iter().position(|column| column == name))
+        };
+        let (Some(ts_col), Some(sender_col), Some(content_col)) = (
+            column(CSV_TIMESTAMP_COLUMNS),
+            column(CSV_SENDER_COLUMNS),
+            column(CSV_CONTENT_COLUMNS),
+        ) else {
+            return Err(ParseError::InvalidFormat(format!(
+                "csv header needs timestamp, sender and content columns, found: {}",
+                header.join(", ")
+            )));
+        };
+
+        let mut messages = Vec::new();
+        let mut members = std::collections::BTreeMap::new();
+        for record in records {
+            let field = |idx: usize| record.get(idx).map(|value| value.trim()).unwrap_or("");
+            let (sender, content) = (field(sender_col), field(content_col));
+            if sender.is_empty() || content.is_empty() {
+                continue;
+            }
+            let Some(timestamp) = parse_csv_timestamp(field(ts_col)) else {
+                continue;
+            };
+            upsert_member(&mut members, sender.to_string(), None, None);
+            messages.push(ParsedMessage {
+                sender: sender.to_string(),
+                sender_name: None,
+                sender_group_nickname: None,
+                timestamp,
+                content: content.to_string(),
+                msg_type: classify_message_type(content, "csv"),
+                edited_at: None,
+                platform_message_id: None,
+            });
+        }
+
+        Ok(ParsedChat {
+            platform: "csv".to_string(),
+            chat_name: chat_name_hint.to_string(),
+            chat_type: ChatType::Group,
+            messages,
+            members: members.into_values().collect(),
+            encoding: None,
+            salvage: None,
+            chat_type_known: false,
+        })
+    }
+}
+
+/// Split CSV text into records. Quoted fields may hold delimiters, doubled
+/// quotes and line breaks; the delimiter is whichever of `,` `;` and tab
+/// appears most in the first line.
+fn csv_records(content: &str) -> Vec<Vec<String>> {
+    let first_line = content.lines().next().unwrap_or_default();
+    let delimiter = [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|candidate| first_line.matches(*candidate).count())
+        .unwrap_or(',');
+
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|value| !value.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+            }
+            c => field.push(c),
+        }
+    }
+    record.push(field);
+    if record.iter().any(|value| !value.is_empty()) {
+        records.push(record);
+    }
+    records
+}
+
+/// RFC3339, then unix seconds, then `%Y-%m-%d %H:%M:%S` read as UTC.
+fn parse_csv_timestamp(raw: &str) -> Option<i64> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(raw) {
+        return Some(dt.timestamp());
+    }
+    if let Ok(secs) = raw.parse::<i64>() {
+        return Some(secs);
+    }
+    chrono::NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|dt| dt.and_utc().timestamp())
+}
+
+/// Parser for raw Android telephony databases (`mmssms.db`).
+///
+/// Reads the `sms`, `pdu`/`mms` and `part` tables read-only. MMS text bodies
+/// live in `part` rows with `ct = 'text/plain'` and are joined back onto
+/// their parent message.
+pub struct AndroidSmsDbParser {
+    name_str: String,
+}
+
+impl AndroidSmsDbParser {
+    /// Creates a new AndroidSmsDbParser instance.
+    pub fn new() -> Self {
+        Self {
+            name_str: "sms".to_string(),
+        }
+    }
+}
+
+/// Android `sms.type` / `pdu.msg_box` value for received messages.
+const ANDROID_SMS_TYPE_INBOX: i64 = 1;
+/// Local sender id used for outgoing SMS/MMS rows.
+const ANDROID_SMS_SELF_SENDER: &str = "me";
+
+impl ChatParser for AndroidSmsDbParser {
+    fn name(&self) -> &str {
+        &self.name_str
+    }
+
+    fn can_parse(&self, path: &Path) -> bool {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.eq_ignore_ascii_case("mmssms.db"))
+            .unwrap_or(false)
+    }
+
+    fn parse(&self, path: &Path) -> Result<ParsedChat, ParseError> {
+        if !path.is_file() {
+            return Err(ParseError::InvalidFormat(format!(
+                "not an sms database file: {}",
+                path.display()
+            )));
+        }
+        let conn = rusqlite::Connection::open_with_flags(
+            path,
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(|e| ParseError::Parse(format!("open sms database: {}", e)))?;
+
+        let has_sms = android_sms_table_exists(&conn, "sms")?;
+        let mms_table = if android_sms_table_exists(&conn, "pdu")? {
+            Some("pdu")
+        } else if android_sms_table_exists(&conn, "mms")? {
+            Some("mms")
+        } else {
+            None
+        };
+        if !has_sms && mms_table.is_none() {
+            return Err(ParseError::InvalidFormat(
+                "database has no sms or mms tables".to_string(),
+            ));
+        }
+
+        let mut rows: Vec<(i64, ParsedMessage)> = Vec::new();
+        if has_sms {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT _id, address, date, body, type FROM sms ORDER BY date ASC, _id ASC",
+                )
+                .map_err(|e| ParseError::Parse(e.to_string()))?;
+            let mapped = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        row.get::<_, Option<i64>>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                        row.get::<_, Option<i64>>(4)?,
+                    ))
+                })
+                .map_err(|e| ParseError::Parse(e.to_string()))?;
+            for row in mapped {
+                let (id, address, date, body, kind) =
+                    row.map_err(|e| ParseError::Parse(e.to_string()))?;
+                let content = body.unwrap_or_default();
+                if content.trim().is_empty() {
+                    continue;
+                }
+                rows.push((
+                    id,
+                    android_sms_message(address, date, content, kind, MessageType::Text),
+                ));
+            }
+        }
+
+        if let Some(table) = mms_table {
+            let has_addr = android_sms_table_exists(&conn, "addr")?;
+            let has_part = android_sms_table_exists(&conn, "part")?;
+            let sql = format!(
+                "SELECT _id, date, msg_box FROM {} ORDER BY date ASC, _id ASC",
+                table
+            );
+            let mut stmt = conn
+                .prepare(&sql)
+                .map_err(|e| ParseError::Parse(e.to_string()))?;
+            let mapped = stmt
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, Option<i64>>(1)?,
+                        row.get::<_, Option<i64>>(2)?,
+                    ))
+                })
+                .map_err(|e| ParseError::Parse(e.to_string()))?;
+            for row in mapped {
+                let (id, date, msg_box) = row.map_err(|e| ParseError::Parse(e.to_string()))?;
+                let (text, has_media) = if has_part {
+                    android_mms_parts(&conn, id)?
+                } else {
+                    (String::new(), false)
+                };
+                let (content, msg_type) = if !text.trim().is_empty() {
+                    (text, MessageType::Text)
+                } else if has_media {
+                    ("[MMS attachment]".to_string(), MessageType::File)
+                } else {
+                    continue;
+                };
+                let address = if has_addr {
+                    android_mms_address(&conn, id, msg_box)?
+                } else {
+                    None
+                };
+                // MMS `date` is stored in seconds, SMS `date` in milliseconds.
+                let date_ms = date.map(|d| d.saturating_mul(1000));
+                rows.push((
+                    id,
+                    android_sms_message(address, date_ms, content, msg_box, msg_type),
+                ));
+            }
+        }
+
+        rows.sort_by(|a, b| a.1.timestamp.cmp(&b.1.timestamp).then(a.0.cmp(&b.0)));
+
+        let mut members: Vec<ChatMember> = Vec::new();
+        for (_, message) in &rows {
+            if message.sender == ANDROID_SMS_SELF_SENDER
+                || members.iter().any(|m| m.id == message.sender)
+            {
+                continue;
+            }
+            members.push(ChatMember {
+                id: message.sender.clone(),
+                name: None,
+                display_name: None,
+            });
+        }
+
+        Ok(ParsedChat {
+            platform: "sms".to_string(),
+            chat_name: file_stem_string(path)?,
+            chat_type: if members.len() > 1 {
+                ChatType::Group
+            } else {
+                ChatType::Private
+            },
+            messages: rows.into_iter().map(|(_, message)| message).collect(),
+            members,
+            encoding: None,
+            salvage: None,
+            chat_type_known: false,
+        })
+    }
+}
+
+fn android_sms_table_exists(conn: &rusqlite::Connection, table: &str) -> Result<bool, ParseError> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [table],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|count| count > 0)
+    .map_err(|e| ParseError::Parse(format!("read sms database schema: {}", e)))
+}
+
+fn android_sms_message(
+    address: Option<String>,
+    date_ms: Option<i64>,
+    content: String,
+    kind: Option<i64>,
+    msg_type: MessageType,
+) -> ParsedMessage {
+    let inbound = kind == Some(ANDROID_SMS_TYPE_INBOX);
+    let sender = if inbound {
+        address
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty())
+            .unwrap_or_else(|| "unknown".to_string())
+    } else {
+        ANDROID_SMS_SELF_SENDER.to_string()
+    };
+    ParsedMessage {
+        sender,
+        sender_name: None,
+        sender_group_nickname: None,
+        timestamp: date_ms.unwrap_or(0) / 1000,
+        content,
+        msg_type,
+        edited_at: None,
+        platform_message_id: None,
+    }
+}
+
+/// Collects the `text/plain` parts of an MMS and reports whether any
+/// non-text, non-SMIL part (image, audio, ...) is attached.
+fn android_mms_parts(
+    conn: &rusqlite::Connection,
+    mms_id: i64,
+) -> Result<(String, bool), ParseError> {
+    let mut stmt = conn
+        .prepare("SELECT ct, text FROM part WHERE mid = ?1 ORDER BY seq ASC, _id ASC")
+        .map_err(|e| ParseError::Parse(e.to_string()))?;
+    let mapped = stmt
+        .query_map([mms_id], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+            ))
+        })
+        .map_err(|e| ParseError::Parse(e.to_string()))?;
+
+    let mut texts = Vec::new();
+    let mut has_media = false;
+    for row in mapped {
+        let (content_type, text) = row.map_err(|e| ParseError::Parse(e.to_string()))?;
+        let content_type = content_type.unwrap_or_default().to_ascii_lowercase();
+        if content_type == "text/plain" {
+            if let Some(text) = text.filter(|t| !t.trim().is_empty()) {
+                texts.push(text);
+            }
+        } else if content_type != "application/smil" {
+            has_media = true;
+        }
+    }
+    Ok((texts.join("\n"), has_media))
+}
+
+/// Resolves the remote party of an MMS from the `addr` table: the `from`
+/// address (type 137) for received messages, the first `to` (151) otherwise.
+fn android_mms_address(
+    conn: &rusqlite::Connection,
+    mms_id: i64,
+    msg_box: Option<i64>,
+) -> Result<Option<String>, ParseError> {
+    let addr_type = if msg_box == Some(ANDROID_SMS_TYPE_INBOX) {
+        137
+    } else {
+        151
+    };
+    let mut stmt = conn
+        .prepare("SELECT address FROM addr WHERE msg_id = ?1 AND type = ?2 ORDER BY _id LIMIT 1")
+        .map_err(|e| ParseError::Parse(e.to_string()))?;
+    let mut rows = stmt
+        .query_map([mms_id, addr_type], |row| row.get::<_, Option<String>>(0))
+        .map_err(|e| ParseError::Parse(e.to_string()))?;
+    match rows.next() {
+        Some(row) => Ok(row.map_err(|e| ParseError::Parse(e.to_string()))?),
+        None => Ok(None),
+    }
+}
+
+macro_rules! impl_default_parser_via_new {
+    ($($parser:ty),+ $(,)?) => {
+        $(
+            impl Default for $parser {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+        )+
+    };
+}
+
+impl_default_parser_via_new!(
+    WhatsAppParser,
+    LINEParser,
+    QQParser,
+    TelegramParser,
+    DiscordParser,
+    WeChatParser,
+    InstagramParser,
+    IMessageParser,
+    MessengerParser,
+    KakaoTalkParser,
+    SlackParser,
+    TeamsParser,
+    SignalParser,
+    SkypeParser,
+    GoogleChatParser,
+    ZoomParser,
+    ViberParser,
+    AndroidSmsDbParser,
+    CsvChatParser,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    static TEST_FILE_SEQ: AtomicU64 = AtomicU64::new(0);
+
+    fn write_temp_file(prefix: &str, extension: &str, content: &str) -> std::path::PathBuf {
+        let epoch_nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let seq = TEST_FILE_SEQ.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!(
+            "xenobot_parser_{prefix}_{epoch_nanos}_{seq}.{extension}"
         ));
         std::fs::write(&path, content).expect("write temp parser fixture");
         path
@@ -2793,6 +4730,7 @@ mod tests {
             "googlechat",
             "zoom",
             "viber",
+            "csv",
         ];
 
         for parser_name in expected {
@@ -2809,6 +4747,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn custom_line_pattern_parses_matching_lines() {
+        let mut registry = ParserRegistry::new();
+        let config = LinePatternConfig {
+            platform: "ircbouncer".to_string(),
+            pattern: r"^(?P<date>\d{2}\.\d{2}\.\d{4}) (?P<time>\d{2}:\d{2}) <(?P<sender>[^>]+)> (?P<content>.*)$"
+                .to_string(),
+            timestamp_format: "%d.%m.%Y %H:%M".to_string(),
+        };
+        registry
+            .register_line_pattern(&config)
+            .expect("valid pattern registers");
+
+        let fixture = write_temp_file(
+            "ircbouncer",
+            "log",
+            "01.03.2025 08:05 <alice> morning all\nnot a chat line\n01.03.2025 08:06 <bob> hi\n",
+        );
+        let parsed = registry
+            .parse_with("ircbouncer", &fixture)
+            .expect("custom pattern should parse");
+        let _ = std::fs::remove_file(&fixture);
+
+        assert_eq!(parsed.platform, "ircbouncer");
+        assert_eq!(parsed.messages.len(), 2);
+        assert_eq!(parsed.messages[0].sender, "alice");
+        assert_eq!(parsed.messages[0].content, "morning all");
+        assert_eq!(parsed.messages[0].timestamp, 1_740_816_300);
+        assert_eq!(parsed.members.len(), 2);
+
+        let missing_time = LinePatternConfig {
+            pattern: r"^(?P<date>\S+) (?P<sender>\S+): (?P<content>.*)$".to_string(),
+            ..config.clone()
+        };
+        let err = registry
+            .register_line_pattern(&missing_time)
+            .expect_err("pattern without a time group is rejected");
+        assert!(matches!(err, ParseError::InvalidFormat(ref msg) if msg.contains("time")));
+        assert!(registry.register_line_pattern(&config).is_err());
+    }
+
     #[test]
     fn detect_and_parse_uses_content_sniff_when_path_hint_is_missing() {
         let registry = ParserRegistry::new();
@@ -3073,6 +5052,43 @@ mod tests {
         let _ = std::fs::remove_file(&fixture);
     }
 
+    #[test]
+    fn qq_parser_assembles_bracketed_header_blocks_with_placeholders() {
+        let registry = ParserRegistry::new();
+        let fixture = write_temp_file(
+            "qq_bracketed_export",
+            "txt",
+            "[2025-1-2 9:20:30] Bob (work)(10002)\nmorning all\nsecond line\n\n[2025-01-02 09:21:00] Alice(10001)\n[图片: IMG_0042.jpg]\n[2025-01-02 09:22:10] Alice(10001)\n[文件] roadmap.pdf\n",
+        );
+
+        let parsed = registry
+            .detect_and_parse(&fixture)
+            .expect("bracketed qq export should parse");
+        assert_eq!(parsed.platform, "qq");
+        assert_eq!(parsed.messages.len(), 3);
+        assert_eq!(parsed.messages[0].sender, "10002");
+        assert_eq!(
+            parsed.messages[0].sender_name.as_deref(),
+            Some("Bob (work)")
+        );
+        assert_eq!(parsed.messages[0].content, "morning all\nsecond line");
+        assert_eq!(parsed.messages[0].timestamp, 1_735_809_630);
+        assert!(matches!(
+            parsed.messages[0].msg_type,
+            super::MessageType::Text
+        ));
+        assert!(matches!(
+            parsed.messages[1].msg_type,
+            super::MessageType::Image
+        ));
+        assert!(matches!(
+            parsed.messages[2].msg_type,
+            super::MessageType::File
+        ));
+        assert_eq!(parsed.members.len(), 2);
+        let _ = std::fs::remove_file(&fixture);
+    }
+
     #[test]
     fn qq_parser_supports_qce_json_export_with_resources_and_sender_profiles() {
         let registry = ParserRegistry::new();
@@ -3127,8 +5143,14 @@ mod tests {
         assert_eq!(parsed.messages.len(), 2);
         assert_eq!(
             parsed.messages[0].sender_name.as_deref(),
+            Some("Alice Global")
+        );
+        assert_eq!(
+            parsed.messages[0].sender_group_nickname.as_deref(),
             Some("Captain Alice")
         );
+        assert_eq!(parsed.messages[1].sender_name.as_deref(), Some("Bob"));
+        assert_eq!(parsed.messages[1].sender_group_nickname, None);
         assert!(parsed.messages[0].content.contains("[Image: diagram.png]"));
         assert!(parsed.messages[0]
             .content
@@ -3149,52 +5171,840 @@ mod tests {
     }
 
     #[test]
-    fn wechat_parser_supports_weflow_root_and_session_metadata() {
-        let registry = ParserRegistry::new();
+    fn discord_parser_reads_official_lowercase_export_shape() {
         let fixture = write_temp_file(
-            "weflow_export",
+            "discord_official",
             "json",
-            r#"{"weflow":{"version":"1.0.0"},"session":{"wxid":"launch-room@chatroom","nickname":"Launch Room","remark":"","displayName":"Launch Room","type":"群聊"},"messages":[{"localId":1,"createTime":1735813230,"type":"文本消息","content":"hello wechat","isSend":0,"senderUsername":"wxid_alice","senderDisplayName":"Alice"},{"localId":2,"createTime":1735813290,"type":"系统消息","content":"Bob joined the room","isSend":null,"senderUsername":"system","senderDisplayName":"System"}]}"#,
+            r#"[
+                {"id":"100","type":0,"content":"morning all","channel_id":"c1","author":{"id":"u1","username":"alice","global_name":"Alice","discriminator":"0"},"attachments":[{"id":"a1","filename":"notes.pdf","url":"https://cdn.example/notes.pdf"}],"embeds":[],"timestamp":"2025-01-02T10:20:30.123000+00:00","edited_timestamp":"2025-01-02T10:25:00.000000+00:00"},
+                {"id":"101","type":7,"content":"","channel_id":"c1","author":{"id":"u2","username":"bob","global_name":null},"attachments":[],"embeds":[],"timestamp":"2025-01-02T10:21:00.000000+00:00","edited_timestamp":null},
+                {"id":"102","type":19,"content":"","channel_id":"c1","author":{"id":"u2","username":"bob","global_name":null},"sticker_items":[{"id":"s1","name":"wave"}],"timestamp":"2025-01-02T10:22:00.000000+00:00"}
+            ]"#,
         );
 
-        let parsed = registry
-            .detect_and_parse(&fixture)
-            .expect("weflow root should parse");
-        assert_eq!(parsed.platform, "wechat");
-        assert_eq!(parsed.chat_name, "Launch Room");
-        assert!(matches!(parsed.chat_type, super::ChatType::Group));
-        assert_eq!(parsed.messages.len(), 2);
-        assert!(matches!(
-            parsed.messages[1].msg_type,
-            super::MessageType::System
-        ));
+        let parsed = super::ChatParser::parse(&super::DiscordParser::new(), &fixture)
+            .expect("official discord export should parse");
+        assert_eq!(parsed.messages.len(), 3);
+
+        let first = &parsed.messages[0];
+        assert_eq!(first.sender, "u1");
+        assert_eq!(first.sender_name.as_deref(), Some("Alice"));
+        assert_eq!(first.content, "morning all\n[File: notes.pdf]");
+        assert_eq!(first.msg_type, MessageType::File);
+        assert_eq!(first.timestamp, 1_735_813_230);
+        assert_eq!(first.edited_at, Some(1_735_813_500));
+        assert_eq!(first.platform_message_id.as_deref(), Some("100"));
+
+        assert_eq!(parsed.messages[1].sender_name.as_deref(), Some("bob"));
+        assert_eq!(parsed.messages[1].content, "[System: UserJoin]");
+        assert_eq!(parsed.messages[1].msg_type, MessageType::System);
+        assert_eq!(parsed.messages[2].content, "[Sticker: wave]");
+        assert_eq!(parsed.messages[2].msg_type, MessageType::Sticker);
         let _ = std::fs::remove_file(&fixture);
     }
 
     #[test]
-    fn wechat_parser_supports_weflow_http_api_root_without_session() {
-        let registry = ParserRegistry::new();
+    fn salvage_mode_recovers_truncated_discord_array() {
         let fixture = write_temp_file(
-            "weflow_http_api_export",
+            "discord_truncated",
             "json",
-            r#"{"success":true,"talker":"launch-room@chatroom","count":2,"hasMore":false,"messages":[{"localId":1,"serverId":"456","localType":1,"createTime":1738713600,"isSend":0,"senderUsername":"wxid_member","content":"你好","rawContent":"你好","parsedContent":"Launch readiness still looks good."},{"localId":2,"localType":3,"createTime":1738713660,"isSend":0,"senderUsername":"wxid_member","content":"[图片]","parsedContent":"[图片]","mediaType":"image","mediaFileName":"abc123.jpg","mediaLocalPath":"/tmp/weflow/api-media/launch-room/images/abc123.jpg"}]}"#,
+            r#"[
+                {"id":"100","type":0,"content":"first","author":{"id":"u1","username":"alice"},"timestamp":"2025-01-02T10:20:30.000000+00:00"},
+                {"id":"101","type":0,"content":"second","author":{"id":"u2","username":"bob"},"timestamp":"2025-01-02T10:21:00.000000+00:00"},
+                {"id":"102","type":0,"content":"cut off mid-wri"#,
         );
 
+        let mut registry = ParserRegistry::new();
+        assert!(
+            registry.parse_with("discord", &fixture).is_err(),
+            "strict parsing stays the default"
+        );
+
+        registry.set_salvage_json(true);
         let parsed = registry
-            .detect_and_parse(&fixture)
-            .expect("weflow http api root should parse");
-        assert_eq!(parsed.platform, "wechat");
-        assert_eq!(parsed.chat_name, "launch-room@chatroom");
-        assert!(matches!(parsed.chat_type, super::ChatType::Group));
-        assert_eq!(parsed.messages.len(), 2);
+            .parse_with("discord", &fixture)
+            .expect("salvaged discord export");
+        let contents: Vec<&str> = parsed
+            .messages
+            .iter()
+            .map(|msg| msg.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["first", "second"]);
+        let salvage = parsed.salvage.expect("salvage report");
+        assert_eq!(salvage.line, 4);
+        assert!(salvage.kept_bytes < salvage.total_bytes);
         assert_eq!(
-            parsed.messages[0].content,
-            "Launch readiness still looks good."
+            salvage.total_bytes as u64,
+            std::fs::metadata(&fixture).expect("fixture metadata").len()
         );
-        assert!(matches!(
-            parsed.messages[1].msg_type,
+        let _ = std::fs::remove_file(&fixture);
+    }
+
+    #[test]
+    fn csv_parser_maps_header_variants_and_timestamp_formats() {
+        let fixture = write_temp_file(
+            "support_log",
+            "csv",
+            "\u{feff}Date,Channel,From,Text\r\n\
+             2025-01-02T10:20:30Z,#help,alice,\"hello, world\"\r\n\
+             1735813290,#help,bob,\"multi\nline \"\"quoted\"\"\"\r\n\
+             2025-01-02 10:22:30,#help,alice,[Image]\r\n\
+             yesterday,#help,bob,unparseable timestamp is skipped\r\n\
+             2025-01-02 10:23:30,#help,,no sender is skipped\r\n",
+        );
+
+        let registry = ParserRegistry::new();
+        let parsed = registry
+            .detect_and_parse(&fixture)
+            .expect("csv export should parse");
+        assert_eq!(parsed.platform, "csv");
+        let summary: Vec<(&str, i64, &str)> = parsed
+            .messages
+            .iter()
+            .map(|msg| (msg.sender.as_str(), msg.timestamp, msg.content.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("alice", 1_735_813_230, "hello, world"),
+                ("bob", 1_735_813_290, "multi\nline \"quoted\""),
+                ("alice", 1_735_813_350, "[Image]"),
+            ]
+        );
+        assert_eq!(parsed.messages[2].msg_type, MessageType::Image);
+        assert_eq!(parsed.members.len(), 2);
+
+        let missing = write_temp_file("no_sender", "csv", "time,text\n1735813290,hi\n");
+        assert!(matches!(
+            super::ChatParser::parse(&CsvChatParser::new(), &missing),
+            Err(ParseError::InvalidFormat(_))
+        ));
+        let _ = std::fs::remove_file(&fixture);
+        let _ = std::fs::remove_file(&missing);
+    }
+
+    #[test]
+    fn wechat_parser_supports_weflow_root_and_session_metadata() {
+        let registry = ParserRegistry::new();
+        let fixture = write_temp_file(
+            "weflow_export",
+            "json",
+            r#"{"weflow":{"version":"1.0.0"},"session":{"wxid":"launch-room@chatroom","nickname":"Launch Room","remark":"","displayName":"Launch Room","type":"群聊"},"messages":[{"localId":1,"createTime":1735813230,"type":"文本消息","content":"hello wechat","isSend":0,"senderUsername":"wxid_alice","senderDisplayName":"Alice"},{"localId":2,"createTime":1735813290,"type":"系统消息","content":"Bob joined the room","isSend":null,"senderUsername":"system","senderDisplayName":"System"}]}"#,
+        );
+
+        let parsed = registry
+            .detect_and_parse(&fixture)
+            .expect("weflow root should parse");
+        assert_eq!(parsed.platform, "wechat");
+        assert_eq!(parsed.chat_name, "Launch Room");
+        assert!(matches!(parsed.chat_type, super::ChatType::Group));
+        assert_eq!(parsed.messages.len(), 2);
+        assert!(matches!(
+            parsed.messages[1].msg_type,
+            super::MessageType::System
+        ));
+        let _ = std::fs::remove_file(&fixture);
+    }
+
+    #[test]
+    fn wechat_parser_supports_weflow_http_api_root_without_session() {
+        let registry = ParserRegistry::new();
+        let fixture = write_temp_file(
+            "weflow_http_api_export",
+            "json",
+            r#"{"success":true,"talker":"launch-room@chatroom","count":2,"hasMore":false,"messages":[{"localId":1,"serverId":"456","localType":1,"createTime":1738713600,"isSend":0,"senderUsername":"wxid_member","content":"你好","rawContent":"你好","parsedContent":"Launch readiness still looks good."},{"localId":2,"localType":3,"createTime":1738713660,"isSend":0,"senderUsername":"wxid_member","content":"[图片]","parsedContent":"[图片]","mediaType":"image","mediaFileName":"abc123.jpg","mediaLocalPath":"/tmp/weflow/api-media/launch-room/images/abc123.jpg"}]}"#,
+        );
+
+        let parsed = registry
+            .detect_and_parse(&fixture)
+            .expect("weflow http api root should parse");
+        assert_eq!(parsed.platform, "wechat");
+        assert_eq!(parsed.chat_name, "launch-room@chatroom");
+        assert!(matches!(parsed.chat_type, super::ChatType::Group));
+        assert_eq!(parsed.messages.len(), 2);
+        assert_eq!(
+            parsed.messages[0].content,
+            "Launch readiness still looks good."
+        );
+        assert_eq!(
+            parsed.messages[0].platform_message_id.as_deref(),
+            Some("456")
+        );
+        assert_eq!(parsed.messages[1].platform_message_id, None);
+        assert!(matches!(
+            parsed.messages[1].msg_type,
             super::MessageType::Image
         ));
         let _ = std::fs::remove_file(&fixture);
     }
+
+    #[test]
+    fn telegram_parser_merges_split_export_parts_and_dedups_by_id() {
+        let epoch_nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let seq = TEST_FILE_SEQ.fetch_add(1, Ordering::Relaxed);
+        let dir =
+            std::env::temp_dir().join(format!("xenobot_parser_telegram_split_{epoch_nanos}_{seq}"));
+        std::fs::create_dir_all(&dir).expect("create telegram export dir");
+        std::fs::write(
+            dir.join("result.json"),
+            r#"{"name":"Launch Room","messages":[{"id":1,"from":"Alice","date":"2025-01-02T10:20:30Z","text":"first"},{"id":3,"from":"Bob","date":"2025-01-02T10:22:30Z","text":"third"}]}"#,
+        )
+        .expect("write base part");
+        std::fs::write(
+            dir.join("result2.json"),
+            r#"{"messages":[{"id":3,"from":"Bob","date":"2025-01-02T10:22:30Z","text":"third"},{"id":2,"from":"Alice","date":"2025-01-02T10:21:30Z","text":"second"},{"id":4,"from":"Alice","date":"2025-01-02T10:23:30Z","text":"fourth"}]}"#,
+        )
+        .expect("write continuation part");
+
+        let parts = super::telegram_export_parts(&dir);
+        assert_eq!(parts.len(), 2);
+        assert!(parts[0].ends_with("result.json"));
+
+        let parsed = ParserRegistry::new()
+            .detect_and_parse(&dir)
+            .expect("split telegram export folder should parse");
+        assert_eq!(parsed.platform, "telegram");
+        assert_eq!(parsed.chat_name, "Launch Room");
+        let contents: Vec<&str> = parsed
+            .messages
+            .iter()
+            .map(|msg| msg.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["first", "second", "third", "fourth"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn telegram_parser_keeps_latest_edit_for_repeated_message_id() {
+        let fixture = write_temp_file(
+            "telegram_edited_export",
+            "json",
+            r#"{"name":"Launch Room","messages":[{"id":7,"from":"Alice","date":"2025-01-02T10:20:30","text":"draft"},{"id":8,"from":"Bob","date":"2025-01-02T10:21:30","text":"ack"},{"id":7,"from":"Alice","date":"2025-01-02T10:20:30","edited":"2025-01-02T10:25:00","edited_unixtime":"1735813500","text":"final wording"}]}"#,
+        );
+
+        let parsed = super::ChatParser::parse(&super::TelegramParser::new(), &fixture)
+            .expect("telegram export with edits should parse");
+        assert_eq!(parsed.messages.len(), 2);
+        assert_eq!(parsed.messages[0].content, "final wording");
+        assert_eq!(parsed.messages[0].edited_at, Some(1_735_813_500));
+        assert_eq!(parsed.messages[1].content, "ack");
+        assert_eq!(parsed.messages[1].edited_at, None);
+        let _ = std::fs::remove_file(&fixture);
+    }
+
+    #[test]
+    fn forced_encoding_overrides_detection_and_is_reported() {
+        let dir = std::env::temp_dir().join(format!(
+            "xenobot_forced_encoding_{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("time")
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).expect("create dir");
+        let path = dir.join("group.txt");
+        let (encoded, _, _) = encoding_rs::GBK.encode(
+            "[01/03/2024, 10:00:00] 张三: 大家好，今天下午三点开会\n[01/03/2024, 10:01:00] 李四: 收到，我会准时参加\n",
+        );
+        std::fs::write(&path, &encoded).expect("write export");
+
+        let mut registry = ParserRegistry::new();
+        let detected = registry
+            .parse_with("whatsapp", &path)
+            .expect("detected parse");
+        let encoding = detected.encoding.expect("text parsers report encoding");
+        assert_eq!(encoding.name, "GBK");
+        assert!(!encoding.forced);
+        assert_eq!(detected.messages[0].sender, "张三");
+
+        registry.set_encoding("windows-1252").expect("known label");
+        let forced = registry
+            .parse_with("whatsapp", &path)
+            .expect("forced parse");
+        let encoding = forced.encoding.expect("text parsers report encoding");
+        assert_eq!(encoding.name, "windows-1252");
+        assert!(encoding.forced);
+        assert_ne!(forced.messages[0].sender, "张三");
+
+        assert!(registry.set_encoding("klingon").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn telegram_parser_flattens_text_entity_arrays() {
+        let fixture = write_temp_file(
+            "telegram_entities_export",
+            "json",
+            r#"{"name":"Links","messages":[
+                {"id":1,"from":"Alice","date":"2025-01-02T10:00:00","text":["See ",{"type":"link","text":"https://example.com"}," and ask ",{"type":"mention","text":"@bob"},{"type":"bold","text":" today"}]},
+                {"id":2,"from":"Bob","date":"2025-01-02T10:01:00","text":"plain reply"},
+                {"id":3,"from":"Bob","date":"2025-01-02T10:02:00","text":[]}
+            ]}"#,
+        );
+
+        let parsed = super::ChatParser::parse(&super::TelegramParser::new(), &fixture)
+            .expect("telegram export with entity arrays should parse");
+        let contents: Vec<&str> = parsed
+            .messages
+            .iter()
+            .map(|msg| msg.content.as_str())
+            .collect();
+        assert_eq!(
+            contents,
+            vec!["See https://example.com and ask @bob today", "plain reply"]
+        );
+        let _ = std::fs::remove_file(&fixture);
+    }
+
+    #[test]
+    fn json_parsers_infer_members_from_message_senders() {
+        let telegram = write_temp_file(
+            "telegram_members",
+            "json",
+            r#"{"name":"Team","messages":[
+                {"id":1,"from":"Alice","from_id":"user1","date":"2025-01-02T10:00:00","text":"hi"},
+                {"id":2,"from":"Bob","from_id":"user2","date":"2025-01-02T10:01:00","text":"hey"},
+                {"id":3,"from":"Alice","from_id":"user1","date":"2025-01-02T10:02:00","text":"again"}
+            ]}"#,
+        );
+        let parsed = TelegramParser::new()
+            .parse(&telegram)
+            .expect("parse telegram");
+        let members: Vec<(&str, Option<&str>)> = parsed
+            .members
+            .iter()
+            .map(|member| (member.id.as_str(), member.name.as_deref()))
+            .collect();
+        assert_eq!(
+            members,
+            vec![("Alice", Some("Alice")), ("Bob", Some("Bob"))]
+        );
+
+        let slack = write_temp_file("slack_members", "json", include_str!("fixtures/slack.json"));
+        let parsed = SlackParser::new().parse(&slack).expect("parse slack");
+        let ids: Vec<&str> = parsed
+            .members
+            .iter()
+            .map(|member| member.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["U1", "U2"]);
+
+        let _ = std::fs::remove_file(&telegram);
+        let _ = std::fs::remove_file(&slack);
+    }
+
+    #[test]
+    fn telegram_parser_keeps_media_and_service_messages() {
+        let fixture = write_temp_file(
+            "telegram_media_export",
+            "json",
+            r#"{"name":"Trip","messages":[
+                {"id":1,"type":"service","date":"2025-01-02T10:00:00","actor":"Alice","action":"invite_members","members":["Bob"],"text":""},
+                {"id":2,"from":"Bob","date":"2025-01-02T10:01:00","photo":"photos/photo_1.jpg","text":""},
+                {"id":3,"from":"Bob","date":"2025-01-02T10:02:00","file":"(File not included. Change data exporting settings to download.)","file_name":"itinerary.pdf","mime_type":"application/pdf","text":""},
+                {"id":4,"from":"Alice","date":"2025-01-02T10:03:00","media_type":"sticker","sticker_emoji":"👍","file":"stickers/sticker.webp","text":""},
+                {"id":5,"from":"Alice","date":"2025-01-02T10:04:00","poll":{"question":"Lunch?","answers":[]},"text":""},
+                {"id":6,"from":"Bob","date":"2025-01-02T10:05:00","location_information":{"latitude":48.85,"longitude":2.35},"text":""},
+                {"id":7,"from":"Bob","date":"2025-01-02T10:06:00","text":""}
+            ]}"#,
+        );
+
+        let parsed = super::ChatParser::parse(&super::TelegramParser::new(), &fixture)
+            .expect("telegram export with media should parse");
+        let summary: Vec<(&str, &str, MessageType)> = parsed
+            .messages
+            .iter()
+            .map(|msg| {
+                (
+                    msg.sender.as_str(),
+                    msg.content.as_str(),
+                    msg.msg_type.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Alice", "Alice added Bob", MessageType::System),
+                ("Bob", "photo_1.jpg", MessageType::Image),
+                ("Bob", "itinerary.pdf", MessageType::File),
+                ("Alice", "👍", MessageType::Sticker),
+                ("Alice", "Poll: Lunch?", MessageType::Text),
+                ("Bob", "48.85, 2.35", MessageType::Location),
+            ]
+        );
+        let _ = std::fs::remove_file(&fixture);
+    }
+
+    #[test]
+    fn android_sms_db_parser_reads_sms_and_mms_text_parts() {
+        let epoch_nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let seq = TEST_FILE_SEQ.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("xenobot_parser_sms_{epoch_nanos}_{seq}"));
+        std::fs::create_dir_all(&dir).expect("create sms fixture dir");
+        let db_path = dir.join("mmssms.db");
+        let conn = rusqlite::Connection::open(&db_path).expect("create sms fixture db");
+        conn.execute_batch(
+            r#"
+            CREATE TABLE sms (_id INTEGER PRIMARY KEY, address TEXT, date INTEGER, body TEXT, type INTEGER);
+            CREATE TABLE pdu (_id INTEGER PRIMARY KEY, date INTEGER, msg_box INTEGER);
+            CREATE TABLE part (_id INTEGER PRIMARY KEY, mid INTEGER, seq INTEGER, ct TEXT, text TEXT);
+            CREATE TABLE addr (_id INTEGER PRIMARY KEY, msg_id INTEGER, address TEXT, type INTEGER);
+            INSERT INTO sms VALUES (1, '+15550001', 1735812000000, 'hi there', 1);
+            INSERT INTO sms VALUES (2, '+15550001', 1735812060000, 'hello back', 2);
+            INSERT INTO sms VALUES (3, '+15550001', 1735812070000, '', 1);
+            INSERT INTO pdu VALUES (10, 1735812120, 1);
+            INSERT INTO part VALUES (1, 10, -1, 'application/smil', '<smil/>');
+            INSERT INTO part VALUES (2, 10, 0, 'image/jpeg', NULL);
+            INSERT INTO part VALUES (3, 10, 1, 'text/plain', 'photo caption');
+            INSERT INTO addr VALUES (1, 10, '+15550001', 137);
+            "#,
+        )
+        .expect("seed sms fixture");
+        drop(conn);
+
+        let parser = super::AndroidSmsDbParser::new();
+        assert!(super::ChatParser::can_parse(&parser, &db_path));
+
+        let parsed = ParserRegistry::new()
+            .detect_and_parse(&db_path)
+            .expect("mmssms.db should parse");
+        assert_eq!(parsed.platform, "sms");
+        let rows: Vec<(&str, &str, i64)> = parsed
+            .messages
+            .iter()
+            .map(|msg| (msg.sender.as_str(), msg.content.as_str(), msg.timestamp))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("+15550001", "hi there", 1735812000),
+                ("me", "hello back", 1735812060),
+                ("+15550001", "photo caption", 1735812120),
+            ]
+        );
+        assert_eq!(parsed.members.len(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn parse_with_forces_named_parser_on_generic_file_name() {
+        let fixture = write_temp_file(
+            "generic_export",
+            "json",
+            r#"[{"sender":"+15550001","timestamp":1735812000000,"body":"hello"},{"sender":"+15550002","timestamp":1735812060000,"body":"hi"}]"#,
+        );
+        let registry = ParserRegistry::new();
+
+        let parsed = registry
+            .parse_with("Signal", &fixture)
+            .expect("forced signal parser should parse generic file");
+        assert_eq!(parsed.platform, "signal");
+        assert_eq!(parsed.messages.len(), 2);
+        assert_eq!(parsed.messages[0].timestamp, 1735812000);
+
+        let err = registry
+            .parse_with("no-such-platform", &fixture)
+            .expect_err("unknown parser name must be rejected");
+        assert!(matches!(err, ParseError::UnsupportedFormat(_)));
+
+        let _ = std::fs::remove_file(&fixture);
+    }
+
+    #[test]
+    fn parse_with_discord_skips_telegram_for_generic_json() {
+        let fixture = write_temp_file(
+            "export",
+            "json",
+            r#"{"guild":{"id":"g1","name":"Guild"},"channel":{"id":"c1","type":"GuildTextChat","name":"general"},"messages":[{"id":"1","type":"Default","timestamp":"2025-01-02T10:20:30Z","author":{"id":"u1","name":"alice"},"content":"hello"}]}"#,
+        );
+        let registry = ParserRegistry::new();
+        assert!(TelegramParser::new().can_parse(&fixture));
+
+        let parsed = registry
+            .parse_with("discord", &fixture)
+            .expect("forced discord parser");
+        assert_eq!(parsed.platform, "discord");
+        assert_eq!(parsed.messages.len(), 1);
+        assert_eq!(parsed.messages[0].sender, "u1");
+
+        match registry.parse_with("irc", &fixture) {
+            Err(ParseError::UnsupportedFormat(message)) => {
+                assert!(message.contains("discord"), "{message}");
+            }
+            other => panic!(
+                "expected unsupported format, got {:?}",
+                other.map(|c| c.platform)
+            ),
+        }
+
+        let _ = std::fs::remove_file(&fixture);
+    }
+
+    #[test]
+    fn normalize_epoch_maps_seconds_millis_and_micros_to_seconds() {
+        assert_eq!(normalize_epoch(1_735_812_000), 1_735_812_000);
+        assert_eq!(normalize_epoch(1_735_812_000_123), 1_735_812_000);
+        assert_eq!(normalize_epoch(1_735_812_000_123_456), 1_735_812_000);
+        assert_eq!(normalize_epoch(0), 0);
+    }
+
+    #[test]
+    fn json_parsers_accept_mixed_timestamp_units() {
+        let fixture = write_temp_file(
+            "signal_mixed_units",
+            "json",
+            r#"[{"sender":"A","timestamp":1735812000,"body":"s"},{"sender":"B","timestamp":1735812000000,"body":"ms"},{"sender":"C","timestamp":1735812000000000,"body":"us"}]"#,
+        );
+        let parsed = SignalParser::new().parse(&fixture).expect("parse signal");
+        let timestamps: Vec<i64> = parsed.messages.iter().map(|msg| msg.timestamp).collect();
+        assert_eq!(timestamps, vec![1735812000, 1735812000, 1735812000]);
+
+        let _ = std::fs::remove_file(&fixture);
+    }
+
+    #[test]
+    fn detect_and_parse_prefers_high_priority_custom_parser() {
+        struct CustomJsonParser;
+
+        impl ChatParser for CustomJsonParser {
+            fn name(&self) -> &str {
+                "custom-json"
+            }
+
+            fn can_parse(&self, path: &Path) -> bool {
+                path.to_string_lossy().ends_with(".json")
+            }
+
+            fn parse(&self, _path: &Path) -> Result<ParsedChat, ParseError> {
+                Ok(ParsedChat {
+                    platform: "custom".to_string(),
+                    chat_name: "custom".to_string(),
+                    chat_type: ChatType::Private,
+                    messages: vec![ParsedMessage {
+                        sender: "bot".to_string(),
+                        sender_name: None,
+                        sender_group_nickname: None,
+                        timestamp: 1,
+                        content: "custom".to_string(),
+                        msg_type: MessageType::Text,
+                        edited_at: None,
+                        platform_message_id: None,
+                    }],
+                    members: Vec::new(),
+                    encoding: None,
+                    salvage: None,
+                    chat_type_known: false,
+                })
+            }
+
+            fn priority(&self) -> i32 {
+                10
+            }
+        }
+
+        let fixture = write_temp_file(
+            "signal_priority",
+            "json",
+            r#"[{"sender":"+15550001","timestamp":1735812000000,"body":"hello"},{"sender":"+15550002","timestamp":1735812060000,"body":"hi"}]"#,
+        );
+        let mut registry = ParserRegistry::new();
+        assert_eq!(
+            registry
+                .detect_and_parse(&fixture)
+                .expect("built-in")
+                .platform,
+            "signal"
+        );
+
+        registry.register(Box::new(CustomJsonParser));
+        let parsed = registry
+            .detect_and_parse(&fixture)
+            .expect("custom parser should win");
+        assert_eq!(parsed.platform, "custom");
+
+        let _ = std::fs::remove_file(&fixture);
+    }
+
+    #[test]
+    fn line_parsers_skip_giant_lines_and_reject_binary_files() {
+        let giant = "x".repeat(4096);
+        let fixture = write_temp_file(
+            "whatsapp_giant_line",
+            "txt",
+            &format!("{giant}\n[01/02/2025, 10:20:30] Alice: still parsed\n"),
+        );
+        let parsed = WhatsAppParser::new()
+            .with_max_line_bytes(1024)
+            .parse(&fixture)
+            .expect("giant line should be skipped, not fatal");
+        assert_eq!(parsed.messages.len(), 1);
+        assert_eq!(parsed.messages[0].content, "still parsed");
+
+        let binary = std::env::temp_dir().join(format!(
+            "xenobot_parser_binary_{}_{}.txt",
+            std::process::id(),
+            TEST_FILE_SEQ.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&binary, [0x7f, b'E', b'L', b'F', 0, 0, 1, 2, 3]).expect("write binary");
+        assert!(!WhatsAppParser::new().can_parse(&binary));
+        assert!(matches!(
+            WhatsAppParser::new().parse(&binary),
+            Err(ParseError::UnsupportedFormat(_))
+        ));
+
+        let _ = std::fs::remove_file(&fixture);
+        let _ = std::fs::remove_file(&binary);
+    }
+
+    #[test]
+    fn whatsapp_parser_keeps_continuation_lines() {
+        let fixture = write_temp_file(
+            "whatsapp_multiline",
+            "txt",
+            concat!(
+                "[01/02/2025, 10:20:30] Alice: Release notes:\n",
+                "first paragraph\n",
+                "\n",
+                "Bob added Carol\n",
+                "[01/02/2025, 10:21:00] Bob: ok\n",
+                "[01/02/2025, 10:22:00] Bob added Dave\n",
+                "stray line after a notice\n",
+                "[01/02/2025, 10:23:00] Carol: last one\n",
+                "  - trailing item\n",
+                "\n",
+            ),
+        );
+        let parsed = WhatsAppParser::new()
+            .parse(&fixture)
+            .expect("parse whatsapp");
+        let contents: Vec<&str> = parsed
+            .messages
+            .iter()
+            .map(|msg| msg.content.as_str())
+            .collect();
+        assert_eq!(
+            contents,
+            vec![
+                "Release notes:\nfirst paragraph\n\nBob added Carol",
+                "ok",
+                "last one\n  - trailing item",
+            ]
+        );
+        assert_eq!(parsed.members.len(), 3);
+
+        let _ = std::fs::remove_file(&fixture);
+    }
+
+    #[test]
+    fn whatsapp_parser_reads_12_hour_and_no_seconds_timestamps() {
+        let fixture = write_temp_file(
+            "whatsapp_locale_times",
+            "txt",
+            concat!(
+                "1/5/24, 9:05\u{202f}PM - Alice: evening\n",
+                "[1/5/24, 12:30:15 AM] Bob: after midnight\n",
+                "25/12/2024, 18:45 - Carol: no seconds\n",
+                "1/5/24, 9:06 PM - Messages are end-to-end encrypted\n",
+            ),
+        );
+        let parsed = WhatsAppParser::new()
+            .parse(&fixture)
+            .expect("parse whatsapp");
+        let stamps: Vec<(&str, i64)> = parsed
+            .messages
+            .iter()
+            .map(|msg| (msg.sender.as_str(), msg.timestamp))
+            .collect();
+        assert_eq!(
+            stamps,
+            vec![
+                ("Alice", 1_704_488_700), // 2024-01-05 21:05:00
+                ("Bob", 1_704_414_615),   // 2024-01-05 00:30:15
+                ("Carol", 1_735_152_300), // 2024-12-25 18:45:00
+            ]
+        );
+
+        assert_eq!(
+            parse_whatsapp_timestamp("12/31/99, 11:59 p.m."),
+            parse_whatsapp_timestamp("12/31/2099 23:59:00")
+        );
+        assert_eq!(parse_whatsapp_timestamp("13/13/2024 10:00"), None);
+
+        let _ = std::fs::remove_file(&fixture);
+    }
+
+    #[test]
+    fn media_placeholders_are_classified_by_platform() {
+        for (content, platform, expected) in [
+            ("<Media omitted>", "whatsapp", MessageType::File),
+            ("\u{200e}image omitted", "whatsapp", MessageType::Image),
+            ("video omitted", "whatsapp", MessageType::Video),
+            ("audio omitted", "whatsapp", MessageType::Audio),
+            ("sticker omitted", "whatsapp", MessageType::Sticker),
+            (
+                "<attached: 00000012-PHOTO-2025-01-02-10-20-30.jpg>",
+                "whatsapp",
+                MessageType::Image,
+            ),
+            (
+                "<attached: 00000013-STICKER-2025-01-02.webp>",
+                "whatsapp",
+                MessageType::Sticker,
+            ),
+            (
+                "PTT-20250102-WA0003.opus (file attached)",
+                "whatsapp",
+                MessageType::Audio,
+            ),
+            ("[Photo]", "line", MessageType::Image),
+            ("[Sticker]", "line", MessageType::Sticker),
+            ("[Voice message]", "line", MessageType::Audio),
+            ("[Video]", "slack", MessageType::Video),
+            (
+                "the image omitted from the deck",
+                "whatsapp",
+                MessageType::Text,
+            ),
+            ("<Media omitted>", "slack", MessageType::Text),
+            ("[Photo] from the trip", "line", MessageType::Text),
+            ("ship it", "whatsapp", MessageType::Text),
+        ] {
+            assert_eq!(
+                classify_message_type(content, platform),
+                expected,
+                "{platform}: {content}"
+            );
+        }
+    }
+
+    #[test]
+    fn native_type_codes_map_to_message_types() {
+        let voice = serde_json::json!({
+            "localType": 34,
+            "createTime": 1735813230,
+            "senderUsername": "wxid_alice",
+            "content": "[语音]"
+        });
+        assert!(matches!(
+            infer_wechat_message_type(&voice),
+            MessageType::Audio
+        ));
+        let file = serde_json::json!({"type": 49, "subType": 6});
+        assert!(matches!(
+            infer_wechat_message_type(&file),
+            MessageType::File
+        ));
+        let unknown = serde_json::json!({"type": 424242});
+        assert!(matches!(
+            infer_wechat_message_type(&unknown),
+            MessageType::Text
+        ));
+
+        let fixture = write_temp_file(
+            "telegram_native_types",
+            "json",
+            r#"{"name":"Ops","messages":[{"id":1,"from":"Alice","date":"2025-01-02T10:20:30","text":"voice note","media_type":"voice_message"},{"id":2,"from":"Bob","date":"2025-01-02T10:21:30","text":"look","photo":"photos/1.jpg"},{"id":3,"from":"Carol","date":"2025-01-02T10:22:30","text":"plain"}]}"#,
+        );
+        let parsed = TelegramParser::new()
+            .parse(&fixture)
+            .expect("parse telegram");
+        let kinds: Vec<String> = parsed
+            .messages
+            .iter()
+            .map(|msg| format!("{:?}", msg.msg_type))
+            .collect();
+        assert_eq!(kinds, vec!["Audio", "Image", "Text"]);
+
+        let _ = std::fs::remove_file(&fixture);
+    }
+
+    #[test]
+    fn configured_chat_type_default_applies_when_members_are_unknown() {
+        let mut registry = ParserRegistry::new();
+        assert_eq!(
+            registry
+                .set_chat_type_defaults("imessage=private, Slack=group")
+                .expect("valid defaults"),
+            2
+        );
+        assert!(registry.set_chat_type_defaults("sms=channel").is_err());
+        assert!(registry.set_chat_type_defaults("sms").is_err());
+
+        let mut chat = ParsedChat {
+            platform: "iMessage".to_string(),
+            chat_name: "Unknown".to_string(),
+            chat_type: ChatType::Group,
+            messages: vec![],
+            members: vec![],
+            encoding: None,
+            salvage: None,
+            chat_type_known: false,
+        };
+        registry.resolve_chat_type(&mut chat);
+        assert_eq!(chat.chat_type, ChatType::Private);
+
+        chat.chat_type = ChatType::Group;
+        chat.chat_type_known = true;
+        registry.resolve_chat_type(&mut chat);
+        assert_eq!(chat.chat_type, ChatType::Group);
+
+        let fixture = write_temp_file(
+            "imessage_group_by_members",
+            "json",
+            r#"[{"text":"hi","sender":"Alice","date":"2025-01-02T10:20:30Z"},{"text":"hey","sender":"Bob","date":"2025-01-02T10:21:30Z"},{"text":"yo","sender":"Carol","date":"2025-01-02T10:22:30Z"}]"#,
+        );
+        let parsed = registry
+            .parse_with("imessage", &fixture)
+            .expect("parse imessage");
+        assert_eq!(parsed.members.len(), 3);
+        assert_eq!(parsed.chat_type, ChatType::Group);
+
+        let _ = std::fs::remove_file(&fixture);
+    }
+
+    #[test]
+    fn parse_str_matches_parsing_the_same_file() {
+        let whatsapp =
+            "[01/02/2025, 10:20:30] Alice: hello\n[01/02/2025, 10:21:30] Bob: hi Alice\n";
+        let from_str = WhatsAppParser::new()
+            .parse_str(whatsapp, "family")
+            .expect("parse whatsapp text");
+        assert_eq!(from_str.chat_name, "family");
+        assert_eq!(from_str.messages.len(), 2);
+        assert!(from_str.encoding.is_none());
+
+        let fixture = write_temp_file("parse_str_whatsapp", "txt", whatsapp);
+        let from_file = WhatsAppParser::new()
+            .parse(&fixture)
+            .expect("parse whatsapp file");
+        assert_eq!(
+            serde_json::to_value(&from_file.messages).unwrap(),
+            serde_json::to_value(&from_str.messages).unwrap()
+        );
+        assert!(from_file.encoding.is_some());
+        let _ = std::fs::remove_file(&fixture);
+
+        let registry = ParserRegistry::new();
+        let discord = registry
+            .parse_str_with(
+                "Discord",
+                r#"{"channel":{"name":"general"},"messages":[{"id":"1","timestamp":"2025-01-02T10:20:30Z","content":"hi","author":{"id":"42","name":"alice"}}]}"#,
+                "upload",
+            )
+            .expect("parse discord text");
+        assert_eq!(discord.messages.len(), 1);
+        assert!(matches!(
+            registry.parse_str_with("sms", "", "mmssms"),
+            Err(ParseError::UnsupportedFormat(_))
+        ));
+    }
 }
@@ -0,0 +1,146 @@
+//! Recovery of truncated JSON exports.
+//!
+//! An export cut off mid-write (full disk, interrupted download) fails strict
+//! parsing as a whole even though most of its messages are intact. Salvage
+//! keeps every array element that ends before the break and closes the
+//! containers left open, so the parsers see a shorter but valid document.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Where a salvaged JSON export was cut.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonSalvage {
+    /// Bytes of the original file kept before the repaired closing brackets.
+    pub kept_bytes: usize,
+    /// Size of the original file.
+    pub total_bytes: usize,
+    /// 1-based line at which strict parsing failed.
+    pub line: usize,
+    /// 1-based column at which strict parsing failed.
+    pub column: usize,
+}
+
+/// Deserialize a JSON export. With `salvage`, a document that is truncated
+/// or malformed part-way is cut back to its last complete array element and
+/// parsed again; the original error is returned if that fails too.
+pub(crate) fn parse_json_export<T: DeserializeOwned>(
+    content: &str,
+    salvage: bool,
+) -> Result<(T, Option<JsonSalvage>), serde_json::Error> {
+    let error = match serde_json::from_str(content) {
+        Ok(value) => return Ok((value, None)),
+        Err(error) => error,
+    };
+    if !salvage || !(error.is_eof() || error.is_syntax()) {
+        return Err(error);
+    }
+
+    let (line, column) = (error.line(), error.column());
+    let mut stop = error_offset(content, line, column);
+    while !content.is_char_boundary(stop) {
+        stop -= 1;
+    }
+    let Some((repaired, kept_bytes)) = close_after_last_complete_element(&content[..stop]) else {
+        return Err(error);
+    };
+    let Ok(value) = serde_json::from_str(&repaired) else {
+        return Err(error);
+    };
+    warn!(
+        "Salvaged truncated JSON: kept {} of {} bytes (strict parse failed at line {}, column {})",
+        kept_bytes,
+        content.len(),
+        line,
+        column
+    );
+    Ok((
+        value,
+        Some(JsonSalvage {
+            kept_bytes,
+            total_bytes: content.len(),
+            line,
+            column,
+        }),
+    ))
+}
+
+/// Byte offset of a serde_json error position (columns count bytes).
+fn error_offset(content: &str, line: usize, column: usize) -> usize {
+    let line_start = content
+        .match_indices('\n')
+        .nth(line.saturating_sub(2))
+        .map(|(index, _)| index + 1)
+        .filter(|_| line > 1)
+        .unwrap_or(0);
+    (line_start + column).min(content.len())
+}
+
+/// Cut `content` after the last complete element of any array and append
+/// the brackets still open there. Returns the repaired text and the number
+/// of original bytes kept, or `None` if no element completed.
+fn close_after_last_complete_element(content: &str) -> Option<(String, usize)> {
+    let mut open: Vec<u8> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut cut: Option<(usize, Vec<u8>)> = None;
+
+    for (index, byte) in content.bytes().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'[' | b'{' => open.push(byte),
+            b']' | b'}' => {
+                open.pop();
+                if open.last() == Some(&b'[') {
+                    cut = Some((index + 1, open.clone()));
+                }
+            }
+            // Everything before a separating comma in an array is complete,
+            // including scalar elements.
+            b',' if open.last() == Some(&b'[') => cut = Some((index, open.clone())),
+            _ => {}
+        }
+    }
+
+    let (kept_bytes, open) = cut?;
+    let mut repaired = content[..kept_bytes].to_string();
+    for bracket in open.iter().rev() {
+        repaired.push(if *bracket == b'[' { ']' } else { '}' });
+    }
+    Some((repaired, kept_bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncated_arrays_keep_complete_elements_only() {
+        let content =
+            r#"{"name": "x", "messages": [{"id": 1, "text": "a, [b]"}, {"id": 2}, {"id": 3, "te"#;
+        let (value, salvage) =
+            parse_json_export::<serde_json::Value>(content, true).expect("salvaged");
+        assert_eq!(value["name"], "x");
+        assert_eq!(value["messages"].as_array().map(Vec::len), Some(2));
+        let salvage = salvage.expect("salvage report");
+        assert_eq!(
+            &content[..salvage.kept_bytes],
+            r#"{"name": "x", "messages": [{"id": 1, "text": "a, [b]"}, {"id": 2}"#
+        );
+        assert_eq!(salvage.total_bytes, content.len());
+
+        assert!(parse_json_export::<serde_json::Value>(content, false).is_err());
+        assert!(parse_json_export::<Vec<u32>>("[1, 2, 3", true).is_ok_and(|(v, _)| v == [1, 2]));
+        assert!(parse_json_export::<serde_json::Value>(r#"{"messages": "#, true).is_err());
+    }
+}
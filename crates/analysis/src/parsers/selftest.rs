@@ -0,0 +1,270 @@
+//! Embedded sample exports for checking the default parsers.
+//!
+//! Every built-in parser has a small fixture compiled into the binary. Running
+//! them writes each fixture to a scratch directory and parses it with its own
+//! parser (no format detection), so a build can confirm which formats it
+//! supports without any files from the user.
+
+use super::{ParseError, ParserRegistry};
+use serde::Serialize;
+use std::path::Path;
+
+/// How a fixture is put on disk before parsing.
+enum FixtureSource {
+    /// Written verbatim.
+    File(&'static str),
+    /// SQL script run against a fresh SQLite database.
+    SqliteScript(&'static str),
+}
+
+struct ParserFixture {
+    parser: &'static str,
+    file_name: &'static str,
+    source: FixtureSource,
+    messages: usize,
+}
+
+const BUILTIN_FIXTURES: &[ParserFixture] = &[
+    ParserFixture {
+        parser: "manual-review",
+        file_name: "manual-review.json",
+        source: FixtureSource::File(include_str!("fixtures/manual-review.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "whatsapp",
+        file_name: "whatsapp.txt",
+        source: FixtureSource::File(include_str!("fixtures/whatsapp.txt")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "line",
+        file_name: "line.txt",
+        source: FixtureSource::File(include_str!("fixtures/line.txt")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "qq",
+        file_name: "qq.txt",
+        source: FixtureSource::File(include_str!("fixtures/qq.txt")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "telegram",
+        file_name: "telegram.json",
+        source: FixtureSource::File(include_str!("fixtures/telegram.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "discord",
+        file_name: "discord.json",
+        source: FixtureSource::File(include_str!("fixtures/discord.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "wechat",
+        file_name: "wechat.json",
+        source: FixtureSource::File(include_str!("fixtures/wechat.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "instagram",
+        file_name: "instagram.json",
+        source: FixtureSource::File(include_str!("fixtures/instagram.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "imessage",
+        file_name: "imessage.json",
+        source: FixtureSource::File(include_str!("fixtures/imessage.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "messenger",
+        file_name: "messenger.json",
+        source: FixtureSource::File(include_str!("fixtures/messenger.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "kakaotalk",
+        file_name: "kakaotalk.json",
+        source: FixtureSource::File(include_str!("fixtures/kakaotalk.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "slack",
+        file_name: "slack.json",
+        source: FixtureSource::File(include_str!("fixtures/slack.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "teams",
+        file_name: "teams.json",
+        source: FixtureSource::File(include_str!("fixtures/teams.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "signal",
+        file_name: "signal.json",
+        source: FixtureSource::File(include_str!("fixtures/signal.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "skype",
+        file_name: "skype.json",
+        source: FixtureSource::File(include_str!("fixtures/skype.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "googlechat",
+        file_name: "googlechat.json",
+        source: FixtureSource::File(include_str!("fixtures/googlechat.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "zoom",
+        file_name: "zoom.json",
+        source: FixtureSource::File(include_str!("fixtures/zoom.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "viber",
+        file_name: "viber.json",
+        source: FixtureSource::File(include_str!("fixtures/viber.json")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "sms",
+        file_name: "mmssms.db",
+        source: FixtureSource::SqliteScript(include_str!("fixtures/sms.sql")),
+        messages: 2,
+    },
+    ParserFixture {
+        parser: "csv",
+        file_name: "chat.csv",
+        source: FixtureSource::File(include_str!("fixtures/chat.csv")),
+        messages: 2,
+    },
+];
+
+/// Outcome of running one parser against its fixture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FixtureStatus {
+    /// Parsed with the expected message count.
+    Pass,
+    /// Failed to parse or produced the wrong number of messages.
+    Fail,
+    /// No built-in fixture, e.g. for a custom line pattern.
+    Skip,
+}
+
+/// Fixture result for one registered parser.
+#[derive(Debug, Clone, Serialize)]
+pub struct ParserFixtureReport {
+    /// Parser name.
+    pub parser: String,
+    /// Pass, fail or skip.
+    pub status: FixtureStatus,
+    /// Messages parsed from the fixture.
+    pub messages: usize,
+    /// Messages the fixture contains.
+    pub expected_messages: usize,
+    /// Failure reason or skip note; empty on pass.
+    pub detail: String,
+}
+
+impl ParserRegistry {
+    /// Parse each registered parser's built-in fixture with that parser.
+    ///
+    /// Fixtures are written to a temporary directory that is removed
+    /// afterwards. Parsers without a fixture are reported as skipped.
+    pub fn run_builtin_fixtures(&self) -> Vec<ParserFixtureReport> {
+        let scratch_dir = std::env::temp_dir().join(format!(
+            "xenobot-parser-fixtures-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_micros()
+        ));
+        let reports = self
+            .parsers
+            .iter()
+            .map(|parser| {
+                let name = parser.name().to_string();
+                let Some(fixture) = BUILTIN_FIXTURES
+                    .iter()
+                    .find(|fixture| fixture.parser == name)
+                else {
+                    return ParserFixtureReport {
+                        parser: name,
+                        status: FixtureStatus::Skip,
+                        messages: 0,
+                        expected_messages: 0,
+                        detail: "no built-in fixture".to_string(),
+                    };
+                };
+                let path = scratch_dir.join(fixture.parser).join(fixture.file_name);
+                let (status, messages, detail) = match write_fixture(&path, &fixture.source)
+                    .and_then(|()| parser.parse(&path))
+                {
+                    Ok(chat) if chat.messages.len() == fixture.messages => {
+                        (FixtureStatus::Pass, chat.messages.len(), String::new())
+                    }
+                    Ok(chat) => (
+                        FixtureStatus::Fail,
+                        chat.messages.len(),
+                        format!(
+                            "expected {} messages, parsed {}",
+                            fixture.messages,
+                            chat.messages.len()
+                        ),
+                    ),
+                    Err(error) => (FixtureStatus::Fail, 0, error.to_string()),
+                };
+                ParserFixtureReport {
+                    parser: name,
+                    status,
+                    messages,
+                    expected_messages: fixture.messages,
+                    detail,
+                }
+            })
+            .collect();
+        let _ = std::fs::remove_dir_all(&scratch_dir);
+        reports
+    }
+}
+
+fn write_fixture(path: &Path, source: &FixtureSource) -> Result<(), ParseError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    match source {
+        FixtureSource::File(content) => std::fs::write(path, content)?,
+        FixtureSource::SqliteScript(sql) => rusqlite::Connection::open(path)
+            .and_then(|conn| conn.execute_batch(sql))
+            .map_err(|e| ParseError::Parse(format!("build sqlite fixture: {}", e)))?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_default_parser_passes_its_builtin_fixture() {
+        let registry = ParserRegistry::new();
+        let reports = registry.run_builtin_fixtures();
+        assert_eq!(reports.len(), registry.parser_count());
+        for report in &reports {
+            assert_eq!(
+                report.status,
+                FixtureStatus::Pass,
+                "{}: {}",
+                report.parser,
+                report.detail
+            );
+            assert_eq!(report.messages, report.expected_messages);
+        }
+    }
+}
@@ -0,0 +1,17 @@
+-- Media files attached to imported messages.
+-- `local_path` is stored relative to the configured media root so a database
+-- plus its media folder stays relocatable; legacy rows may hold absolute paths.
+
+CREATE TABLE IF NOT EXISTS message_media (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    message_id INTEGER NOT NULL,
+    media_kind TEXT NOT NULL,
+    local_path TEXT NOT NULL,
+    mime_type TEXT,
+    file_size INTEGER,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (message_id) REFERENCES message(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_media_message
+ON message_media(message_id);
@@ -0,0 +1,3 @@
+-- Latest edit time reported by the source export, when the platform records edits.
+
+ALTER TABLE message ADD COLUMN edited_at INTEGER;
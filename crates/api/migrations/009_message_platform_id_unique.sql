@@ -0,0 +1,16 @@
+-- Native message ids are unique within a chat. Older imports could store the
+-- same id twice; keep it on the earliest row so the index can be built.
+
+UPDATE message
+SET platform_message_id = NULL
+WHERE platform_message_id IS NOT NULL
+  AND id NOT IN (
+    SELECT MIN(id)
+    FROM message
+    WHERE platform_message_id IS NOT NULL
+    GROUP BY meta_id, platform_message_id
+  );
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_message_meta_platform_id_unique
+    ON message(meta_id, platform_message_id)
+    WHERE platform_message_id IS NOT NULL;
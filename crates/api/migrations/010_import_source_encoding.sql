@@ -0,0 +1,3 @@
+-- Text encoding the source export was decoded with, for diagnosing mojibake.
+
+ALTER TABLE import_source_checkpoint ADD COLUMN source_encoding TEXT;
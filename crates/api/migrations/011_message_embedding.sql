@@ -0,0 +1,13 @@
+-- Precomputed semantic search vectors, one per message.
+-- `vector` holds `dim` little-endian f32 values. `model` names the vectorizer
+-- that produced it; search ignores rows from any other model and embeds
+-- those messages on the fly instead.
+
+CREATE TABLE IF NOT EXISTS message_embedding (
+    message_id INTEGER PRIMARY KEY,
+    model TEXT NOT NULL,
+    dim INTEGER NOT NULL,
+    vector BLOB NOT NULL,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (message_id) REFERENCES message(id) ON DELETE CASCADE
+);
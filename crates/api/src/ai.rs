@@ -22,14 +22,21 @@ use std::{
     time::{SystemTime, UNIX_EPOCH},
 };
 use tracing::instrument;
+use utoipa::{OpenApi, ToSchema};
 
 use crate::ApiError;
 
+/// OpenAPI description of the documented search routes.
+#[derive(OpenApi)]
+#[openapi(paths(search_messages, semantic_search_messages, hybrid_search))]
+pub(crate) struct SearchApiDoc;
+
 /// AI API router.
 pub fn router() -> Router {
     Router::new()
         .route("/search-messages", post(search_messages))
         .route("/semantic-search-messages", post(semantic_search_messages))
+        .route("/hybrid-search", post(hybrid_search))
         .route("/message-context", post(get_message_context))
         .route("/recent-messages", post(get_recent_messages))
         .route("/all-recent-messages", post(get_all_recent_messages))
@@ -120,7 +127,7 @@ struct ExportProgress {
     message: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct SearchMessagesRequest {
     session_id: String,
@@ -131,7 +138,7 @@ struct SearchMessagesRequest {
     sender_id: Option<i32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct SemanticSearchMessagesRequest {
     session_id: String,
@@ -151,7 +158,34 @@ struct SemanticSearchMessageResult {
     similarity: f32,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct HybridSearchRequest {
+    session_id: String,
+    query: String,
+    filter: Option<TimeFilter>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+    /// Minimum similarity for a semantic match to count.
+    threshold: Option<f32>,
+    sender_id: Option<i32>,
+    /// Weight of the keyword ranking in the fused score.
+    lexical_weight: Option<f32>,
+    /// Weight of the semantic ranking in the fused score.
+    semantic_weight: Option<f32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct HybridSearchMessageResult {
+    #[serde(flatten)]
+    message: SearchMessageResult,
+    score: f32,
+    lexical_score: Option<f32>,
+    semantic_score: Option<f32>,
+}
+
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct TimeFilter {
     start_ts: Option<i64>,
@@ -852,6 +886,13 @@ async fn load_conversation_payload(
     Ok((id, session_id, created_at, updated_at, payload))
 }
 
+#[utoipa::path(
+    post,
+    path = "/ai/search-messages",
+    tag = "search",
+    request_body = SearchMessagesRequest,
+    responses((status = 200, description = "Keyword matches with total count"))
+)]
 #[instrument]
 async fn search_messages(
     Json(req): Json<SearchMessagesRequest>,
@@ -883,6 +924,13 @@ async fn search_messages(
     })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/ai/semantic-search-messages",
+    tag = "search",
+    request_body = SemanticSearchMessagesRequest,
+    responses((status = 200, description = "Messages ranked by semantic similarity"))
+)]
 #[instrument]
 async fn semantic_search_messages(
     Json(req): Json<SemanticSearchMessagesRequest>,
@@ -903,13 +951,54 @@ async fn semantic_search_messages(
     let semantic_window = (offset as usize).saturating_add(limit as usize);
     let lexical_prefilter_limit = semantic_window.saturating_mul(300).clamp(500, 20_000) as u32;
 
-    let candidates = query_messages(
+    let scored = score_semantic_candidates(
         pool.as_ref(),
         meta_id,
         req.filter.as_ref(),
         sender_ids.as_deref(),
+        query,
+        threshold,
+        lexical_prefilter_limit,
+    )
+    .await?;
+    let total_count = scored.len();
+    let paged_messages = scored
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .collect::<Vec<_>>();
+    let page_count = paged_messages.len();
+
+    Ok(Json(serde_json::json!({
+        "messages": paged_messages,
+        "count": page_count,
+        "totalCount": total_count,
+        "limit": limit,
+        "offset": offset,
+        "threshold": threshold,
+        "queryRewritten": rewritten_query,
+        "prefilterCount": lexical_prefilter_limit,
+    })))
+}
+
+/// Score the newest `candidate_limit` messages of a session against `query`
+/// by embedding similarity and keep those reaching `threshold`, best first.
+async fn score_semantic_candidates(
+    pool: &SqlitePool,
+    meta_id: i64,
+    filter: Option<&TimeFilter>,
+    sender_ids: Option<&[i32]>,
+    query: &str,
+    threshold: f32,
+    candidate_limit: u32,
+) -> Result<Vec<SemanticSearchMessageResult>, ApiError> {
+    let candidates = query_messages(
+        pool,
+        meta_id,
+        filter,
+        sender_ids,
         &[],
-        Some(lexical_prefilter_limit),
+        Some(candidate_limit),
         Some(0),
         None,
         None,
@@ -924,7 +1013,7 @@ async fn semantic_search_messages(
         if content.is_empty() {
             continue;
         }
-        let score = cosine_similarity(&query_embedding, &embed_text_for_semantic(&content));
+        let score = cosine_similarity(&query_embedding, &embed_text_for_semantic(content));
         if score >= threshold {
             candidate.is_hit = true;
             scored.push(SemanticSearchMessageResult {
@@ -941,8 +1030,127 @@ async fn semantic_search_messages(
             .then_with(|| b.message.timestamp.cmp(&a.message.timestamp))
             .then_with(|| b.message.id.cmp(&a.message.id))
     });
-    let total_count = scored.len();
-    let paged_messages = scored
+    Ok(scored)
+}
+
+/// Smoothing constant for reciprocal rank fusion in hybrid search.
+const HYBRID_RRF_K: f32 = 60.0;
+
+#[utoipa::path(
+    post,
+    path = "/ai/hybrid-search",
+    tag = "search",
+    request_body = HybridSearchRequest,
+    responses((status = 200, description = "Keyword and semantic matches fused into one ranking"))
+)]
+#[instrument]
+async fn hybrid_search(
+    Json(req): Json<HybridSearchRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let meta_id = parse_meta_id(&req.session_id)?;
+    let rewritten_query = rewrite_semantic_query(&req.query);
+    let semantic_query = rewritten_query.trim();
+    let keywords = normalize_keywords(Some(
+        req.query.split_whitespace().map(str::to_string).collect(),
+    ));
+    if semantic_query.is_empty() || keywords.is_empty() {
+        return Err(ApiError::InvalidRequest(
+            "query cannot be empty".to_string(),
+        ));
+    }
+    let lexical_weight = req.lexical_weight.unwrap_or(1.0);
+    let semantic_weight = req.semantic_weight.unwrap_or(1.0);
+    if !(lexical_weight >= 0.0 && semantic_weight >= 0.0) || lexical_weight + semantic_weight <= 0.0
+    {
+        return Err(ApiError::InvalidRequest(
+            "lexicalWeight and semanticWeight must be non-negative and not both 0".to_string(),
+        ));
+    }
+    let pool = get_pool().await?;
+    let threshold = req.threshold.unwrap_or(0.2).clamp(-1.0, 1.0);
+    let limit = req.limit.unwrap_or(20).max(1).min(200);
+    let offset = req.offset.unwrap_or(0);
+    let sender_ids = req.sender_id.map(|v| vec![v]);
+    // Both rankings go deeper than the page so fusion can surface messages
+    // that neither puts near the top on its own.
+    let depth = (offset.saturating_add(limit))
+        .saturating_mul(5)
+        .clamp(50, 1_000);
+
+    let mut lexical: Vec<(SearchMessageResult, f32)> = query_messages(
+        pool.as_ref(),
+        meta_id,
+        req.filter.as_ref(),
+        sender_ids.as_deref(),
+        &keywords,
+        Some(depth),
+        Some(0),
+        None,
+        None,
+        true,
+    )
+    .await?
+    .into_iter()
+    .map(|message| {
+        let text = message.content.to_lowercase();
+        let matched = keywords
+            .iter()
+            .filter(|k| text.contains(k.as_str()))
+            .count();
+        let score = matched as f32 / keywords.len() as f32;
+        (message, score)
+    })
+    .collect();
+    // Stable sort: equal keyword coverage keeps newest-first order.
+    lexical.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+    let semantic = score_semantic_candidates(
+        pool.as_ref(),
+        meta_id,
+        req.filter.as_ref(),
+        sender_ids.as_deref(),
+        semantic_query,
+        threshold,
+        depth.saturating_mul(300).clamp(500, 20_000),
+    )
+    .await?;
+
+    let mut fused: std::collections::HashMap<i64, HybridSearchMessageResult> =
+        std::collections::HashMap::new();
+    for (rank, (message, lexical_score)) in lexical.into_iter().enumerate() {
+        let entry = fused
+            .entry(message.id)
+            .or_insert_with(|| HybridSearchMessageResult {
+                message,
+                score: 0.0,
+                lexical_score: None,
+                semantic_score: None,
+            });
+        entry.score += lexical_weight / (HYBRID_RRF_K + rank as f32 + 1.0);
+        entry.lexical_score = Some(lexical_score);
+    }
+    for (rank, result) in semantic.into_iter().enumerate() {
+        let entry = fused
+            .entry(result.message.id)
+            .or_insert_with(|| HybridSearchMessageResult {
+                message: result.message,
+                score: 0.0,
+                lexical_score: None,
+                semantic_score: None,
+            });
+        entry.score += semantic_weight / (HYBRID_RRF_K + rank as f32 + 1.0);
+        entry.semantic_score = Some(result.similarity);
+    }
+
+    let mut ranked: Vec<HybridSearchMessageResult> = fused.into_values().collect();
+    ranked.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| b.message.timestamp.cmp(&a.message.timestamp))
+            .then_with(|| b.message.id.cmp(&a.message.id))
+    });
+    let total_count = ranked.len();
+    let paged_messages = ranked
         .into_iter()
         .skip(offset as usize)
         .take(limit as usize)
@@ -956,8 +1164,9 @@ async fn semantic_search_messages(
         "limit": limit,
         "offset": offset,
         "threshold": threshold,
+        "lexicalWeight": lexical_weight,
+        "semanticWeight": semantic_weight,
         "queryRewritten": rewritten_query,
-        "prefilterCount": lexical_prefilter_limit,
     })))
 }
 
@@ -3,9 +3,14 @@
 //! Provides HTTP endpoints equivalent to Xenobot's `chatApi` IPC methods.
 
 use axum::{
-    extract::{Path, Query},
-    response::sse::{Event, KeepAlive, Sse},
-    routing::{delete, get, post},
+    body::Body,
+    extract::{DefaultBodyLimit, Path, Query},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{delete, get, patch, post},
     Json, Router,
 };
 use futures::{stream, TryStreamExt};
@@ -23,21 +28,30 @@ use std::{
     time::{Duration, Instant},
 };
 use tracing::{instrument, warn};
+use utoipa::{IntoParams, OpenApi, ToSchema};
 use xenobot_analysis::parsers::{
-    ChatType as AnalysisChatType, MessageType as AnalysisMessageType, ParserRegistry,
+    ChatType as AnalysisChatType, MessageType as AnalysisMessageType,
+    ParsedChat as AnalysisParsedChat, ParserRegistry,
 };
 
 use crate::database::repository::{
-    ChatMeta, ImportProgress, ImportSourceCheckpoint, MemberActivity, Message,
+    ChatMeta, ImportProgress, ImportSourceCheckpoint, MemberActivity, Message, MessageExportFilter,
     MessageLengthDistributionResult, MessageTypeDistribution, TimeActivity,
     TimeFilter as RepoTimeFilter, TimeRange,
 };
+use crate::events::{message_created_subscribed, publish_message_created, ImportProgressReporter};
 use crate::ApiError;
 use xenobot_core::webhook::{
     append_dead_letter_entry, build_dead_letter_entry, merge_webhook_dispatch_stats,
-    webhook_rule_matches_event, WebhookDispatchStats, WebhookMessageCreatedEvent, WebhookRule,
+    send_webhook_event, webhook_rule_matches_event, WebhookDispatchStats,
+    WebhookMessageCreatedEvent, WebhookRetryPolicy, WebhookRule,
 };
 
+/// OpenAPI description of the documented chat routes.
+#[derive(OpenApi)]
+#[openapi(paths(import, get_sessions, export_messages_stream))]
+pub(crate) struct ChatApiDoc;
+
 /// Chat API router.
 pub fn router() -> Router {
     Router::new()
@@ -46,7 +60,10 @@ pub fn router() -> Router {
         .route("/run-migration", post(run_migration))
         // File operations
         .route("/select-file", get(select_file))
-        .route("/import", post(import))
+        .route(
+            "/import",
+            post(import).layer(DefaultBodyLimit::max(INLINE_IMPORT_MAX_BODY_BYTES)),
+        )
         .route("/import-batch", post(import_batch))
         .route("/detect-format", post(detect_format))
         .route("/import-with-options", post(import_with_options))
@@ -55,7 +72,10 @@ pub fn router() -> Router {
         .route("/sessions", get(get_sessions))
         .route("/sessions/:session_id", get(get_session))
         .route("/sessions/:session_id", delete(delete_session))
+        .route("/sessions/:session_id", patch(update_session))
         .route("/sessions/:session_id/rename", post(rename_session))
+        // Reply threads
+        .route("/messages/:message_id/thread", get(get_message_thread))
         // Analysis endpoints
         .route(
             "/sessions/:session_id/available-years",
@@ -176,6 +196,7 @@ pub fn router() -> Router {
             post(incremental_import),
         )
         // Export and temp files
+        .route("/export", get(export_messages_stream))
         .route(
             "/export-sessions-to-temp-files",
             post(export_sessions_to_temp_files),
@@ -195,6 +216,22 @@ pub struct TimeFilter {
     end_ts: Option<i64>,
 }
 
+/// Query for `GET /chat/messages/:message_id/thread`.
+#[derive(Debug, Deserialize)]
+pub struct MessageThreadQuery {
+    /// Ancestors to follow, capped at [`MESSAGE_THREAD_MAX_DEPTH`].
+    depth: Option<usize>,
+    /// Also return direct replies.
+    include_replies: Option<bool>,
+}
+
+/// Ancestors returned when `depth` is omitted.
+const MESSAGE_THREAD_DEFAULT_DEPTH: usize = 50;
+/// Upper bound for `depth`.
+const MESSAGE_THREAD_MAX_DEPTH: usize = 500;
+/// Direct replies returned with `include_replies=true`.
+const MESSAGE_THREAD_MAX_REPLIES: i64 = 200;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ImportProgressResponse {
     pub total: u64,
@@ -215,7 +252,7 @@ pub struct SessionResponse {
     pub member_count: Option<i64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct AnalysisSession {
     pub id: i64,
     pub name: String,
@@ -244,6 +281,7 @@ pub struct MemberResponse {
     pub account_name: Option<String>,
     pub group_nickname: Option<String>,
     pub aliases: Vec<String>,
+    pub message_count: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -318,10 +356,14 @@ struct ImportParseStats {
 struct ParsedMessage {
     sender_platform_id: String,
     sender_name: Option<String>,
+    /// Per-group name when the source records one; storage falls back to
+    /// `sender_name`.
+    sender_group_nickname: Option<String>,
     ts: i64,
     msg_type: i64,
     content: Option<String>,
     platform_message_id: Option<String>,
+    edited_at: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -383,8 +425,12 @@ struct ApiWebhookItem {
     sender: Option<String>,
     #[serde(default)]
     keyword: Option<String>,
+    #[serde(default, alias = "msgType")]
+    msg_type: Option<i64>,
     #[serde(default, alias = "createdAt")]
     created_at: Option<String>,
+    #[serde(default)]
+    secret: Option<String>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -401,6 +447,8 @@ struct ApiWebhookDispatchSettings {
     retry_attempts: Option<u32>,
     #[serde(default, alias = "retryBaseDelayMs")]
     retry_base_delay_ms: Option<u64>,
+    #[serde(default, alias = "retryJitter")]
+    retry_jitter: Option<bool>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -419,6 +467,17 @@ struct WebhookDispatchConfig {
     flush_interval_ms: u64,
     retry_attempts: u32,
     retry_base_delay_ms: u64,
+    retry_jitter: bool,
+}
+
+impl WebhookDispatchConfig {
+    fn retry_policy(&self) -> WebhookRetryPolicy {
+        WebhookRetryPolicy {
+            attempts: self.retry_attempts,
+            base_delay_ms: self.retry_base_delay_ms,
+            jitter: self.retry_jitter,
+        }
+    }
 }
 
 impl Default for WebhookDispatchConfig {
@@ -430,6 +489,7 @@ impl Default for WebhookDispatchConfig {
             flush_interval_ms: WEBHOOK_FLUSH_INTERVAL_MS_DEFAULT,
             retry_attempts: WEBHOOK_RETRY_ATTEMPTS_DEFAULT,
             retry_base_delay_ms: WEBHOOK_RETRY_BASE_DELAY_MS_DEFAULT,
+            retry_jitter: false,
         }
     }
 }
@@ -464,10 +524,21 @@ fn api_webhook_item_to_rule(item: &ApiWebhookItem) -> WebhookRule {
         meta_id: item.meta_id,
         sender: item.sender.clone(),
         keyword: item.keyword.clone(),
+        msg_type: item.msg_type,
         created_at: item.created_at.clone(),
+        secret: item.secret.clone(),
     }
 }
 
+/// Current signing secret of each configured webhook, keyed by webhook id.
+pub(crate) fn read_api_webhook_secrets() -> HashMap<String, String> {
+    read_api_webhook_config()
+        .rules
+        .into_iter()
+        .filter_map(|rule| rule.secret.map(|secret| (rule.id, secret)))
+        .collect()
+}
+
 fn sanitize_webhook_dispatch_settings(value: &ApiWebhookDispatchSettings) -> WebhookDispatchConfig {
     fn clamp_usize(value: Option<usize>, default_value: usize, min: usize, max: usize) -> usize {
         value.unwrap_or(default_value).clamp(min, max)
@@ -508,6 +579,7 @@ fn sanitize_webhook_dispatch_settings(value: &ApiWebhookDispatchSettings) -> Web
             0,
             5_000,
         ),
+        retry_jitter: value.retry_jitter.unwrap_or(false),
     }
 }
 
@@ -565,6 +637,7 @@ async fn dispatch_api_webhook_message_created(
     event: &WebhookMessageCreatedEvent,
 ) -> WebhookDispatchStats {
     let mut stats = WebhookDispatchStats::default();
+    let retry = dispatch.retry_policy();
     for item in items {
         if !webhook_rule_matches_event(item, event) {
             stats.filtered += 1;
@@ -575,15 +648,11 @@ async fn dispatch_api_webhook_message_created(
         let mut delivered = false;
         let mut attempts_used = 0u32;
         let mut last_error = "unknown delivery failure".to_string();
-        for attempt in 0..dispatch.retry_attempts {
+        for attempt in 0..retry.attempts {
             attempts_used = attempt.saturating_add(1);
-            let send_result = client
-                .post(&item.url)
-                .header("X-Xenobot-Event", &event.event_type)
-                .header("X-Xenobot-Webhook-Id", &item.id)
-                .json(event)
-                .send()
-                .await;
+            let send_result =
+                send_webhook_event(client, &item.url, &item.id, event, item.secret.as_deref())
+                    .await;
 
             match send_result {
                 Ok(resp) if resp.status().is_success() => {
@@ -593,18 +662,14 @@ async fn dispatch_api_webhook_message_created(
                 }
                 Ok(resp) => {
                     last_error = format!("http status {}", resp.status());
-                    if attempt.saturating_add(1) < dispatch.retry_attempts {
-                        let backoff_factor = 1_u64 << attempt.min(10);
-                        let wait_ms = dispatch.retry_base_delay_ms.saturating_mul(backoff_factor);
-                        tokio::time::sleep(Duration::from_millis(wait_ms)).await;
+                    if attempt.saturating_add(1) < retry.attempts {
+                        tokio::time::sleep(retry.delay(attempt)).await;
                     }
                 }
                 Err(err) => {
                     last_error = err.to_string();
-                    if attempt.saturating_add(1) < dispatch.retry_attempts {
-                        let backoff_factor = 1_u64 << attempt.min(10);
-                        let wait_ms = dispatch.retry_base_delay_ms.saturating_mul(backoff_factor);
-                        tokio::time::sleep(Duration::from_millis(wait_ms)).await;
+                    if attempt.saturating_add(1) < retry.attempts {
+                        tokio::time::sleep(retry.delay(attempt)).await;
                     }
                 }
             }
@@ -1105,10 +1170,12 @@ fn parse_message_from_object(
     Some(ParsedMessage {
         sender_platform_id,
         sender_name: normalized_sender_name,
+        sender_group_nickname: None,
         ts,
         msg_type,
         content,
         platform_message_id,
+        edited_at: None,
     })
 }
 
@@ -1232,10 +1299,12 @@ fn parse_chat_payload_from_text(
         messages.push(ParsedMessage {
             sender_platform_id: "text-importer".to_string(),
             sender_name: Some("文本导入".to_string()),
+            sender_group_nickname: None,
             ts: base_ts + idx as i64,
             msg_type: 0,
             content: Some(content.to_string()),
             platform_message_id: None,
+            edited_at: None,
         });
     }
     ParsedChatPayload {
@@ -1439,8 +1508,17 @@ async fn parse_with_analysis_registry(
     .ok()
     .flatten()?;
 
+    analysis_chat_to_payload(parsed_chat, || infer_platform_from_path(file_path))
+}
+
+/// Normalize an analysis-crate chat into the import payload shape, using
+/// `fallback_platform` when the parser did not name a platform.
+fn analysis_chat_to_payload(
+    parsed_chat: AnalysisParsedChat,
+    fallback_platform: impl FnOnce() -> String,
+) -> Option<(DetectedFormat, ParsedChatPayload, ImportParseStats)> {
     let platform = if parsed_chat.platform.trim().is_empty() {
-        infer_platform_from_path(file_path)
+        fallback_platform()
     } else {
         parsed_chat.platform.to_ascii_lowercase()
     };
@@ -1490,26 +1568,30 @@ async fn parse_with_analysis_registry(
         }
         let sender_platform_id = format!("{}:{}", platform, sender);
         let msg_type_code = analysis_message_type_to_code(&msg.msg_type);
-        let (_, normalized_sender_name) = canonicalize_member_names(
+        let (normalized_sender_name, normalized_group_nickname) = canonicalize_member_names(
             &sender_platform_id,
             msg.sender_name.clone(),
-            msg.sender_name.clone(),
+            msg.sender_group_nickname
+                .clone()
+                .or(msg.sender_name.clone()),
             Some(msg_type_code),
         );
         upsert_parsed_member_profile(
             &mut members,
             sender_platform_id.clone(),
             normalized_sender_name.clone(),
-            normalized_sender_name.clone(),
+            normalized_group_nickname.clone(),
             Some(msg_type_code),
         );
         messages.push(ParsedMessage {
             sender_platform_id,
             sender_name: normalized_sender_name,
+            sender_group_nickname: normalized_group_nickname,
             ts: msg.timestamp,
             msg_type: msg_type_code,
             content: Some(content.to_string()),
-            platform_message_id: None,
+            platform_message_id: msg.platform_message_id.clone(),
+            edited_at: msg.edited_at,
         });
     }
 
@@ -1595,6 +1677,13 @@ fn normalized_platform_message_id_for_signature(platform_message_id: Option<&str
     platform_message_id.unwrap_or_default().trim().to_string()
 }
 
+/// Dedup key for a message that carries a native id: the id alone, so a
+/// repeated "ok" with distinct ids is kept and an edited message is not.
+fn platform_message_id_signature(platform_message_id: Option<&str>) -> Option<String> {
+    let id = normalized_platform_message_id_for_signature(platform_message_id);
+    (!id.is_empty()).then(|| format!("id|{}", id))
+}
+
 fn signature_by_platform_with_message_id(
     sender_platform_id: &str,
     ts: i64,
@@ -1602,6 +1691,9 @@ fn signature_by_platform_with_message_id(
     content: Option<&str>,
     platform_message_id: Option<&str>,
 ) -> String {
+    if let Some(signature) = platform_message_id_signature(platform_message_id) {
+        return signature;
+    }
     format!(
         "{}|{}|{}|{}|{}",
         sender_platform_id,
@@ -1619,6 +1711,9 @@ fn signature_by_sender_id_with_message_id(
     content: Option<&str>,
     platform_message_id: Option<&str>,
 ) -> String {
+    if let Some(signature) = platform_message_id_signature(platform_message_id) {
+        return signature;
+    }
     format!(
         "{}|{}|{}|{}|{}",
         sender_id,
@@ -1743,6 +1838,7 @@ async fn upsert_source_checkpoint(
         last_duplicate_messages: duplicates,
         status: status.to_string(),
         error_message,
+        source_encoding: None,
     })
     .await
     .map_err(|e| ApiError::Database(e.to_string()))
@@ -1820,7 +1916,7 @@ async fn run_import_with_chat_index(
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
     let parse_result = parse_import_file(file_path, chat_index).await;
-    let (detected, payload, mut stats) = match parse_result {
+    let (detected, payload, stats) = match parse_result {
         Ok(v) => v,
         Err(e) => {
             let _ = repo.fail_import(progress_id, &e.to_string()).await;
@@ -1845,22 +1941,51 @@ async fn run_import_with_chat_index(
         }
     };
 
+    persist_import_payload(
+        &repo,
+        &pool,
+        progress_id,
+        started_at,
+        Some((file_path, &source_fingerprint)),
+        detected,
+        payload,
+        stats,
+    )
+    .await
+}
+
+/// Write a parsed payload as a new chat, finishing the import-progress row
+/// and, for file imports, the source checkpoint.
+#[allow(clippy::too_many_arguments)]
+async fn persist_import_payload(
+    repo: &crate::database::Repository,
+    pool: &SqlitePool,
+    progress_id: i64,
+    started_at: i64,
+    source: Option<(&str, &SourceCheckpointFingerprint)>,
+    detected: DetectedFormat,
+    payload: ParsedChatPayload,
+    mut stats: ImportParseStats,
+) -> Result<serde_json::Value, ApiError> {
+    let source_kind = "api-import";
     if payload.messages.is_empty() {
         let _ = repo.fail_import(progress_id, "no messages parsed").await;
-        let _ = upsert_source_checkpoint(
-            &repo,
-            source_kind,
-            file_path,
-            &source_fingerprint,
-            Some(payload.platform.as_str()),
-            Some(payload.name.as_str()),
-            None,
-            0,
-            0,
-            "failed",
-            Some("no messages parsed".to_string()),
-        )
-        .await;
+        if let Some((source_path, fingerprint)) = source {
+            let _ = upsert_source_checkpoint(
+                repo,
+                source_kind,
+                source_path,
+                fingerprint,
+                Some(payload.platform.as_str()),
+                Some(payload.name.as_str()),
+                None,
+                0,
+                0,
+                "failed",
+                Some("no messages parsed".to_string()),
+            )
+            .await;
+        }
         return Ok(serde_json::json!({
             "success": false,
             "error": "error.no_messages",
@@ -1876,7 +2001,7 @@ async fn run_import_with_chat_index(
             .bind(progress_id)
             .bind(payload.messages.len() as i32)
             .bind("saving")
-            .execute(&*pool)
+            .execute(pool)
             .await;
     let payload_platform = payload.platform.clone();
     let payload_name = payload.name.clone();
@@ -1906,7 +2031,8 @@ async fn run_import_with_chat_index(
         None
     } else {
         Some(
-            reqwest::Client::builder()
+            xenobot_core::http_client::outbound_client_builder()
+                .map_err(|e| ApiError::Http(e.to_string()))?
                 .timeout(Duration::from_millis(webhook_dispatch.request_timeout_ms))
                 .build()
                 .map_err(|e| ApiError::Http(e.to_string()))?,
@@ -1917,10 +2043,14 @@ async fn run_import_with_chat_index(
     let mut webhook_queue_first_enqueued_at: Option<Instant> = None;
 
     let mut processed: i32 = 0;
+    let mut progress_events = ImportProgressReporter::new(
+        source.map(|(source_path, _)| source_path.to_string()),
+        payload_messages.len() as u64,
+    );
     let write_result = async {
         let mut member_cache: HashMap<String, i64> = HashMap::new();
         for member in &payload_members {
-            let member_id = ensure_member_profile_and_history(&repo, member, started_at).await?;
+            let member_id = ensure_member_profile_and_history(repo, member, started_at).await?;
             member_cache.insert(member.platform_id.clone(), member_id);
         }
 
@@ -1931,9 +2061,12 @@ async fn run_import_with_chat_index(
                 let profile = ParsedMemberProfile {
                     platform_id: msg.sender_platform_id.clone(),
                     account_name: msg.sender_name.clone(),
-                    group_nickname: msg.sender_name.clone(),
+                    group_nickname: msg
+                        .sender_group_nickname
+                        .clone()
+                        .or_else(|| msg.sender_name.clone()),
                 };
-                let created = ensure_member_profile_and_history(&repo, &profile, msg.ts).await?;
+                let created = ensure_member_profile_and_history(repo, &profile, msg.ts).await?;
                 member_cache.insert(profile.platform_id, created);
                 created
             };
@@ -1943,18 +2076,22 @@ async fn run_import_with_chat_index(
                     id: 0,
                     sender_id,
                     sender_account_name: msg.sender_name.clone(),
-                    sender_group_nickname: msg.sender_name.clone(),
+                    sender_group_nickname: msg
+                        .sender_group_nickname
+                        .clone()
+                        .or_else(|| msg.sender_name.clone()),
                     ts: msg.ts,
                     msg_type: msg.msg_type,
                     content: msg.content.clone(),
                     reply_to_message_id: None,
                     platform_message_id: msg.platform_message_id.clone(),
+                    edited_at: msg.edited_at,
                     meta_id,
                 })
                 .await
                 .map_err(|e| ApiError::Database(e.to_string()))?;
 
-            if let Some(client) = webhook_client.as_ref() {
+            if webhook_client.is_some() || message_created_subscribed() {
                 let event = WebhookMessageCreatedEvent {
                     event_type: "message.created".to_string(),
                     platform: payload_platform.clone(),
@@ -1967,25 +2104,30 @@ async fn run_import_with_chat_index(
                     msg_type: msg.msg_type,
                     content: msg.content.clone(),
                 };
-                if webhook_queue.is_empty() {
-                    webhook_queue_first_enqueued_at = Some(Instant::now());
-                }
-                webhook_queue.push(event);
-                let queue_age = webhook_queue_first_enqueued_at.map(|t| t.elapsed());
-                if should_flush_webhook_queue(webhook_queue.len(), queue_age, &webhook_dispatch) {
-                    let stats = dispatch_api_webhook_batch(
-                        client,
-                        &webhook_items,
-                        &mut webhook_queue,
-                        &webhook_dispatch,
-                    )
-                    .await;
-                    merge_webhook_dispatch_stats(&mut webhook_stats, &stats);
-                    webhook_queue_first_enqueued_at = None;
+                publish_message_created(&event);
+                if let Some(client) = webhook_client.as_ref() {
+                    if webhook_queue.is_empty() {
+                        webhook_queue_first_enqueued_at = Some(Instant::now());
+                    }
+                    webhook_queue.push(event);
+                    let queue_age = webhook_queue_first_enqueued_at.map(|t| t.elapsed());
+                    if should_flush_webhook_queue(webhook_queue.len(), queue_age, &webhook_dispatch)
+                    {
+                        let stats = dispatch_api_webhook_batch(
+                            client,
+                            &webhook_items,
+                            &mut webhook_queue,
+                            &webhook_dispatch,
+                        )
+                        .await;
+                        merge_webhook_dispatch_stats(&mut webhook_stats, &stats);
+                        webhook_queue_first_enqueued_at = None;
+                    }
                 }
             }
 
             processed += 1;
+            progress_events.report(processed as u64, processed as u64, 0);
             if processed % 200 == 0 {
                 let _ = repo.update_progress(progress_id, processed, "saving").await;
             }
@@ -2008,45 +2150,51 @@ async fn run_import_with_chat_index(
     .await;
 
     if let Err(err) = write_result {
+        progress_events.finish("error", Some(err.to_string()));
         let _ = repo.update_progress(progress_id, processed, "failed").await;
         let _ = repo.fail_import(progress_id, &err.to_string()).await;
+        if let Some((source_path, fingerprint)) = source {
+            let _ = upsert_source_checkpoint(
+                repo,
+                source_kind,
+                source_path,
+                fingerprint,
+                Some(payload_platform.as_str()),
+                Some(payload_name.as_str()),
+                Some(meta_id),
+                i64::from(processed.max(0)),
+                0,
+                "failed",
+                Some(err.to_string()),
+            )
+            .await;
+        }
+        return Err(err);
+    }
+
+    progress_events.finish("complete", None);
+    stats.messages_written = processed as usize;
+    if stats.messages_received >= stats.messages_written {
+        stats.messages_skipped = stats.messages_received - stats.messages_written;
+    }
+    let _ = repo.complete_import(progress_id, now_ts()).await;
+    if let Some((source_path, fingerprint)) = source {
         let _ = upsert_source_checkpoint(
-            &repo,
+            repo,
             source_kind,
-            file_path,
-            &source_fingerprint,
+            source_path,
+            fingerprint,
             Some(payload_platform.as_str()),
             Some(payload_name.as_str()),
             Some(meta_id),
             i64::from(processed.max(0)),
             0,
-            "failed",
-            Some(err.to_string()),
+            "completed",
+            None,
         )
         .await;
-        return Err(err);
     }
 
-    stats.messages_written = processed as usize;
-    if stats.messages_received >= stats.messages_written {
-        stats.messages_skipped = stats.messages_received - stats.messages_written;
-    }
-    let _ = repo.complete_import(progress_id, now_ts()).await;
-    let _ = upsert_source_checkpoint(
-        &repo,
-        source_kind,
-        file_path,
-        &source_fingerprint,
-        Some(payload_platform.as_str()),
-        Some(payload_name.as_str()),
-        Some(meta_id),
-        i64::from(processed.max(0)),
-        0,
-        "completed",
-        None,
-    )
-    .await;
-
     Ok(serde_json::json!({
         "success": true,
         "sessionId": meta_id.to_string(),
@@ -2183,6 +2331,9 @@ async fn run_migration() -> Result<Json<serde_json::Value>, ApiError> {
     let migrations = Migrator::new(FsPath::new(&migrations_dir()))
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
+    crate::database::reconcile_legacy_migrations(&pool, &migrations)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
     migrations
         .run(&pool)
         .await
@@ -2232,13 +2383,157 @@ struct ParsedBatchSource {
     messages: Vec<ParsedMessage>,
 }
 
-#[instrument]
-async fn import(Json(req): Json<ImportRequest>) -> Result<Json<serde_json::Value>, ApiError> {
+/// Body limit for `POST /chat/import`; inline payloads carry the whole export.
+const INLINE_IMPORT_MAX_BODY_BYTES: usize = 8 * 1024 * 1024;
+
+/// `POST /chat/import` body: a server-side file path, raw export text, or an
+/// already-parsed chat. `chat` wins over `content`, which wins over `file_path`.
+#[derive(Debug, Deserialize, ToSchema)]
+struct ChatImportRequest {
+    #[serde(default)]
+    file_path: Option<String>,
+    /// Raw export text, e.g. a WhatsApp `.txt` export.
+    #[serde(default)]
+    content: Option<String>,
+    /// Platform hint for `content`/`chat` (`whatsapp`, `telegram`, ...).
+    #[serde(default)]
+    platform: Option<String>,
+    /// Session name for inline payloads.
+    #[serde(default)]
+    chat_name: Option<String>,
+    /// Pre-parsed chat in the analysis `ParsedChat` shape.
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    chat: Option<AnalysisParsedChat>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/chat/import",
+    tag = "chat",
+    request_body = ChatImportRequest,
+    responses(
+        (status = 200, description = "Import summary for the file or inline payload"),
+        (status = 400, description = "Inline content matched no parser; the body lists the parser errors"),
+        (status = 413, description = "Request body exceeds the inline import limit")
+    )
+)]
+#[instrument(skip(req))]
+async fn import(Json(req): Json<ChatImportRequest>) -> Result<Json<serde_json::Value>, ApiError> {
+    let platform = req
+        .platform
+        .as_deref()
+        .map(|p| p.trim().to_ascii_lowercase())
+        .filter(|p| !p.is_empty());
+    let chat = match (req.chat, req.content) {
+        (Some(chat), _) => chat,
+        (None, Some(content)) => parse_inline_export_text(content, platform.as_deref()).await?,
+        (None, None) => {
+            return Ok(Json(
+                run_import_with_chat_index(req.file_path.as_deref().unwrap_or_default(), None)
+                    .await?,
+            ));
+        }
+    };
     Ok(Json(
-        run_import_with_chat_index(&req.file_path, None).await?,
+        run_inline_import(chat, platform, req.chat_name).await?,
     ))
 }
 
+/// Parse raw export text in memory with the analysis parsers. A platform
+/// hint that names a parser uses only that parser; otherwise every
+/// registered parser's `parse_str` is tried and the chat with the most
+/// messages wins. Failures become a 400 carrying the parser errors.
+async fn parse_inline_export_text(
+    content: String,
+    platform: Option<&str>,
+) -> Result<AnalysisParsedChat, ApiError> {
+    let platform = platform.map(str::to_string);
+    tokio::task::spawn_blocking(move || {
+        let registry = ParserRegistry::new();
+        let names = registry.parser_names();
+        let candidates: Vec<&String> = match platform.as_deref() {
+            Some(hint) if names.iter().any(|name| name == hint) => {
+                names.iter().filter(|name| *name == hint).collect()
+            }
+            _ => names.iter().collect(),
+        };
+        let mut best: Option<AnalysisParsedChat> = None;
+        let mut errors = Vec::with_capacity(candidates.len());
+        for name in candidates {
+            match registry.parse_str_with(name, &content, "chat") {
+                Ok(chat) if chat.messages.is_empty() => {
+                    errors.push(format!("{}: no messages", name))
+                }
+                Ok(chat) => {
+                    let better = best
+                        .as_ref()
+                        .is_none_or(|current| chat.messages.len() > current.messages.len());
+                    if better {
+                        best = Some(chat);
+                    }
+                }
+                Err(e) => errors.push(format!("{}: {}", name, e)),
+            }
+        }
+        best.ok_or_else(|| errors.join("; "))
+    })
+    .await
+    .map_err(|e| ApiError::Internal(e.to_string()))?
+    .map_err(|errors| ApiError::InvalidRequest(format!("error.unrecognized_format ({})", errors)))
+}
+
+/// Import a chat that arrived in the request body rather than from a file.
+async fn run_inline_import(
+    mut chat: AnalysisParsedChat,
+    platform: Option<String>,
+    chat_name: Option<String>,
+) -> Result<serde_json::Value, ApiError> {
+    if let Some(name) = chat_name.filter(|name| !name.trim().is_empty()) {
+        chat.chat_name = name;
+    }
+    let fallback_platform = platform.unwrap_or_else(|| "unknown".to_string());
+    let source_label = format!("inline:{}", fallback_platform);
+    let Some((detected, payload, stats)) = analysis_chat_to_payload(chat, || fallback_platform)
+    else {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": "error.no_messages"
+        }));
+    };
+
+    let pool = crate::database::get_pool()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let repo = crate::database::Repository::new(pool.clone());
+    let started_at = now_ts();
+    let progress_id = repo
+        .create_import_progress(&ImportProgress {
+            id: 0,
+            file_path: source_label,
+            total_messages: Some(0),
+            processed_messages: Some(0),
+            status: Some("saving".to_string()),
+            started_at: Some(started_at),
+            completed_at: None,
+            error_message: None,
+        })
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    persist_import_payload(
+        &repo,
+        &pool,
+        progress_id,
+        started_at,
+        None,
+        detected,
+        payload,
+        stats,
+    )
+    .await
+}
+
 #[instrument]
 async fn import_batch(
     Json(req): Json<ImportBatchRequest>,
@@ -2662,8 +2957,16 @@ async fn run_merged_import_batch(
     let mut imported_files = 0usize;
     let mut total_inserted = 0usize;
     let mut total_duplicates = 0usize;
+    let mut progress_events = ImportProgressReporter::new(
+        None,
+        parsed_sources
+            .iter()
+            .map(|source| source.messages.len() as u64)
+            .sum(),
+    );
 
     for source in parsed_sources {
+        progress_events.start_file(Some(source.source_path.clone()));
         let mut source_inserted = 0usize;
         let mut source_duplicates = 0usize;
         let mut source_failed = false;
@@ -2692,6 +2995,11 @@ async fn run_merged_import_batch(
             if merged_seen.contains(&signature) {
                 source_duplicates = source_duplicates.saturating_add(1);
                 total_duplicates = total_duplicates.saturating_add(1);
+                progress_events.report(
+                    (total_inserted + total_duplicates) as u64,
+                    total_inserted as u64,
+                    total_duplicates as u64,
+                );
                 continue;
             }
 
@@ -2699,22 +3007,48 @@ async fn run_merged_import_batch(
                 id: 0,
                 sender_id,
                 sender_account_name: msg.sender_name.clone(),
-                sender_group_nickname: msg.sender_name.clone(),
+                sender_group_nickname: msg
+                    .sender_group_nickname
+                    .clone()
+                    .or_else(|| msg.sender_name.clone()),
                 ts: msg.ts,
                 msg_type: msg.msg_type,
                 content: msg.content.clone(),
                 reply_to_message_id: None,
                 platform_message_id: msg.platform_message_id.clone(),
+                edited_at: msg.edited_at,
                 meta_id,
             };
-            if let Err(e) = repo.create_message(&row).await {
-                source_failed = true;
-                source_error = Some(e.to_string());
-                break;
+            let inserted_message_id = match repo.create_message(&row).await {
+                Ok(id) => id,
+                Err(e) => {
+                    source_failed = true;
+                    source_error = Some(e.to_string());
+                    break;
+                }
+            };
+            if message_created_subscribed() {
+                publish_message_created(&WebhookMessageCreatedEvent {
+                    event_type: "message.created".to_string(),
+                    platform: merged_platform.clone(),
+                    chat_name: merged_name.clone(),
+                    meta_id,
+                    message_id: inserted_message_id,
+                    sender_id,
+                    sender_name: row.sender_account_name.clone(),
+                    ts: row.ts,
+                    msg_type: row.msg_type,
+                    content: row.content.clone(),
+                });
             }
             merged_seen.insert(signature);
             source_inserted = source_inserted.saturating_add(1);
             total_inserted = total_inserted.saturating_add(1);
+            progress_events.report(
+                (total_inserted + total_duplicates) as u64,
+                total_inserted as u64,
+                total_duplicates as u64,
+            );
         }
 
         let checkpoint_status = if source_failed { "failed" } else { "completed" };
@@ -2751,6 +3085,14 @@ async fn run_merged_import_batch(
         }
     }
 
+    progress_events.finish(
+        if imported_files > 0 {
+            "complete"
+        } else {
+            "error"
+        },
+        None,
+    );
     Ok(serde_json::json!({
         "success": imported_files > 0 || skipped_files > 0,
         "mode": "merged",
@@ -2876,8 +3218,17 @@ pub async fn get_available_years(
     Ok(Json(years))
 }
 
-#[instrument]
-async fn get_sessions() -> Result<Json<Vec<AnalysisSession>>, ApiError> {
+#[utoipa::path(
+    get,
+    path = "/chat/sessions",
+    tag = "chat",
+    responses(
+        (status = 200, description = "Imported chat sessions", body = [AnalysisSession]),
+        (status = 304, description = "The If-None-Match ETag is still current")
+    )
+)]
+#[instrument(skip(headers))]
+async fn get_sessions(headers: HeaderMap) -> Result<Response, ApiError> {
     let pool = crate::database::get_pool()
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
@@ -2899,11 +3250,15 @@ async fn get_sessions() -> Result<Json<Vec<AnalysisSession>>, ApiError> {
         })
         .collect();
 
-    Ok(Json(sessions))
+    let last_modified = sessions.iter().map(|s| s.imported_at).max();
+    json_with_validators(&headers, &sessions, last_modified)
 }
 
-#[instrument]
-async fn get_session(Path(session_id): Path<String>) -> Result<Json<AnalysisSession>, ApiError> {
+#[instrument(skip(headers))]
+async fn get_session(
+    Path(session_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
     let id = session_id
         .parse::<i64>()
         .map_err(|_| ApiError::InvalidRequest("Invalid session ID".to_string()))?;
@@ -2919,13 +3274,47 @@ async fn get_session(Path(session_id): Path<String>) -> Result<Json<AnalysisSess
         .map_err(|e| ApiError::Database(e.to_string()))?
         .ok_or_else(|| ApiError::NotFound("Session not found".to_string()))?;
 
-    Ok(Json(AnalysisSession {
+    let session = AnalysisSession {
         id: chat.id,
         name: chat.name,
         platform: chat.platform,
         chat_type: chat.chat_type,
         imported_at: chat.imported_at,
-    }))
+    };
+    let last_modified = Some(session.imported_at);
+    json_with_validators(&headers, &session, last_modified)
+}
+
+#[instrument]
+async fn get_message_thread(
+    Path(message_id): Path<String>,
+    Query(query): Query<MessageThreadQuery>,
+) -> Result<Json<crate::database::MessageThread>, ApiError> {
+    let id = message_id
+        .parse::<i64>()
+        .map_err(|_| ApiError::InvalidRequest("Invalid message ID".to_string()))?;
+    let depth = query
+        .depth
+        .unwrap_or(MESSAGE_THREAD_DEFAULT_DEPTH)
+        .min(MESSAGE_THREAD_MAX_DEPTH);
+    let max_replies = if query.include_replies.unwrap_or(false) {
+        MESSAGE_THREAD_MAX_REPLIES
+    } else {
+        0
+    };
+
+    let pool = crate::database::get_pool()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let repo = crate::database::Repository::new(pool);
+    let thread = repo
+        .get_message_thread(id, depth, max_replies)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound("Message not found".to_string()))?;
+
+    Ok(Json(thread))
 }
 
 #[instrument]
@@ -2940,13 +3329,66 @@ async fn delete_session(Path(session_id): Path<String>) -> Result<Json<bool>, Ap
 
     let repo = crate::database::Repository::new(pool);
 
-    repo.delete_chat(id)
+    let deleted = repo
+        .delete_chat(id)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
+    if !deleted {
+        return Err(ApiError::NotFound("Session not found".to_string()));
+    }
 
     Ok(Json(true))
 }
 
+#[derive(Debug, Deserialize)]
+struct UpdateSessionRequest {
+    #[serde(alias = "newName", alias = "new_name")]
+    name: String,
+}
+
+#[instrument]
+async fn update_session(
+    Path(session_id): Path<String>,
+    Json(req): Json<UpdateSessionRequest>,
+) -> Result<Json<AnalysisSession>, ApiError> {
+    let id = session_id
+        .parse::<i64>()
+        .map_err(|_| ApiError::InvalidRequest("Invalid session ID".to_string()))?;
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::InvalidRequest(
+            "Session name must not be empty".to_string(),
+        ));
+    }
+
+    let pool = crate::database::get_pool()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+
+    let repo = crate::database::Repository::new(pool);
+    let renamed = repo
+        .rename_chat(id, name)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    if !renamed {
+        return Err(ApiError::NotFound("Session not found".to_string()));
+    }
+
+    let chat = repo
+        .get_chat(id)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound("Session not found".to_string()))?;
+
+    Ok(Json(AnalysisSession {
+        id: chat.id,
+        name: chat.name,
+        platform: chat.platform,
+        chat_type: chat.chat_type,
+        imported_at: chat.imported_at,
+    }))
+}
+
 #[derive(Debug, Deserialize)]
 struct RenameSessionRequest {
     new_name: String,
@@ -4795,42 +5237,17 @@ async fn get_members(
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    // English engineering note.
-    let query = r#"
-        SELECT DISTINCT
-            m.id,
-            m.platform_id,
-            m.account_name,
-            m.group_nickname,
-            m.aliases,
-            m.avatar,
-            m.roles
-        FROM member m
-        INNER JOIN message msg ON m.id = msg.sender_id
-        WHERE msg.meta_id = ?1
-          AND msg.msg_type != 7
-          AND COALESCE(m.account_name, '') != '系统消息'
-        ORDER BY m.id
-    "#;
-
-    #[derive(Debug, sqlx::FromRow)]
-    struct MemberRow {
-        id: i64,
-        platform_id: String,
-        account_name: Option<String>,
-        group_nickname: Option<String>,
-        aliases: Option<String>,
-        avatar: Option<String>,
-        roles: Option<String>,
-    }
+    let repo = crate::database::Repository::new(pool);
+    repo.get_chat(meta_id)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound("Session not found".to_string()))?;
 
-    let rows: Vec<MemberRow> = sqlx::query_as(query)
-        .bind(meta_id)
-        .fetch_all(&*pool)
+    let rows = repo
+        .list_chat_members(meta_id)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    // English engineering note.
     let members: Vec<MemberResponse> = rows
         .into_iter()
         .map(|row| {
@@ -4845,6 +5262,7 @@ async fn get_members(
                 account_name: row.account_name,
                 group_nickname: row.group_nickname,
                 aliases,
+                message_count: row.message_count,
             }
         })
         .collect();
@@ -6571,7 +6989,8 @@ async fn incremental_import(
         None
     } else {
         Some(
-            reqwest::Client::builder()
+            xenobot_core::http_client::outbound_client_builder()
+                .map_err(|e| ApiError::Http(e.to_string()))?
                 .timeout(Duration::from_millis(webhook_dispatch.request_timeout_ms))
                 .build()
                 .map_err(|e| ApiError::Http(e.to_string()))?,
@@ -6584,6 +7003,8 @@ async fn incremental_import(
     let mut processed = 0i32;
     let mut duplicate_count = 0usize;
     let mut new_count = 0usize;
+    let mut progress_events =
+        ImportProgressReporter::new(Some(req.file_path.clone()), payload_messages.len() as u64);
     let write_result = async {
         let mut member_cache: HashMap<String, i64> = HashMap::new();
         for member in &payload_members {
@@ -6598,7 +7019,10 @@ async fn incremental_import(
                 let profile = ParsedMemberProfile {
                     platform_id: msg.sender_platform_id.clone(),
                     account_name: msg.sender_name.clone(),
-                    group_nickname: msg.sender_name.clone(),
+                    group_nickname: msg
+                        .sender_group_nickname
+                        .clone()
+                        .or_else(|| msg.sender_name.clone()),
                 };
                 let created = ensure_member_profile_and_history(&repo, &profile, msg.ts).await?;
                 member_cache.insert(profile.platform_id, created);
@@ -6615,6 +7039,7 @@ async fn incremental_import(
             if existing_signatures.contains(&signature) {
                 duplicate_count += 1;
                 processed += 1;
+                progress_events.report(processed as u64, new_count as u64, duplicate_count as u64);
                 continue;
             }
 
@@ -6623,19 +7048,23 @@ async fn incremental_import(
                     id: 0,
                     sender_id,
                     sender_account_name: msg.sender_name.clone(),
-                    sender_group_nickname: msg.sender_name.clone(),
+                    sender_group_nickname: msg
+                        .sender_group_nickname
+                        .clone()
+                        .or_else(|| msg.sender_name.clone()),
                     ts: msg.ts,
                     msg_type: msg.msg_type,
                     content: msg.content.clone(),
                     reply_to_message_id: None,
                     platform_message_id: msg.platform_message_id.clone(),
+                    edited_at: msg.edited_at,
                     meta_id,
                 })
                 .await
                 .map_err(|e| ApiError::Database(e.to_string()))?;
             existing_signatures.insert(signature);
 
-            if let Some(client) = webhook_client.as_ref() {
+            if webhook_client.is_some() || message_created_subscribed() {
                 let event = WebhookMessageCreatedEvent {
                     event_type: "message.created".to_string(),
                     platform: payload_platform.clone(),
@@ -6648,26 +7077,31 @@ async fn incremental_import(
                     msg_type: msg.msg_type,
                     content: msg.content.clone(),
                 };
-                if webhook_queue.is_empty() {
-                    webhook_queue_first_enqueued_at = Some(Instant::now());
-                }
-                webhook_queue.push(event);
-                let queue_age = webhook_queue_first_enqueued_at.map(|t| t.elapsed());
-                if should_flush_webhook_queue(webhook_queue.len(), queue_age, &webhook_dispatch) {
-                    let stats = dispatch_api_webhook_batch(
-                        client,
-                        &webhook_items,
-                        &mut webhook_queue,
-                        &webhook_dispatch,
-                    )
-                    .await;
-                    merge_webhook_dispatch_stats(&mut webhook_stats, &stats);
-                    webhook_queue_first_enqueued_at = None;
+                publish_message_created(&event);
+                if let Some(client) = webhook_client.as_ref() {
+                    if webhook_queue.is_empty() {
+                        webhook_queue_first_enqueued_at = Some(Instant::now());
+                    }
+                    webhook_queue.push(event);
+                    let queue_age = webhook_queue_first_enqueued_at.map(|t| t.elapsed());
+                    if should_flush_webhook_queue(webhook_queue.len(), queue_age, &webhook_dispatch)
+                    {
+                        let stats = dispatch_api_webhook_batch(
+                            client,
+                            &webhook_items,
+                            &mut webhook_queue,
+                            &webhook_dispatch,
+                        )
+                        .await;
+                        merge_webhook_dispatch_stats(&mut webhook_stats, &stats);
+                        webhook_queue_first_enqueued_at = None;
+                    }
                 }
             }
 
             new_count += 1;
             processed += 1;
+            progress_events.report(processed as u64, new_count as u64, duplicate_count as u64);
             if processed % 200 == 0 {
                 let _ = repo.update_progress(progress_id, processed, "saving").await;
             }
@@ -6690,6 +7124,7 @@ async fn incremental_import(
     .await;
 
     if let Err(err) = write_result {
+        progress_events.finish("error", Some(err.to_string()));
         let _ = repo.update_progress(progress_id, processed, "failed").await;
         let _ = repo.fail_import(progress_id, &err.to_string()).await;
         let _ = upsert_source_checkpoint(
@@ -6709,6 +7144,7 @@ async fn incremental_import(
         return Err(err);
     }
 
+    progress_events.finish("complete", None);
     let _ = repo.complete_import(progress_id, now_ts()).await;
     let _ = upsert_source_checkpoint(
         &repo,
@@ -6891,6 +7327,169 @@ async fn export_sessions_to_temp_files(
     })))
 }
 
+/// Rows fetched per page while streaming an export.
+const EXPORT_STREAM_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ExportStreamQuery {
+    format: Option<String>,
+    meta_id: Option<i64>,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    member_id: Option<i64>,
+    since_id: Option<i64>,
+    since_ts: Option<i64>,
+}
+
+/// Streams matching messages as NDJSON, one page of rows at a time, so large
+/// chats never need to be held in memory.
+#[utoipa::path(
+    get,
+    path = "/chat/export",
+    tag = "chat",
+    params(ExportStreamQuery),
+    responses(
+        (status = 200, description = "One JSON message per line", content_type = "application/x-ndjson"),
+        (status = 304, description = "The If-None-Match ETag is still current"),
+        (status = 400, description = "Unsupported export format")
+    )
+)]
+#[instrument(skip(headers))]
+async fn export_messages_stream(
+    Query(query): Query<ExportStreamQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let format = query.format.as_deref().unwrap_or("ndjson");
+    if !format.eq_ignore_ascii_case("ndjson") {
+        return Err(ApiError::InvalidRequest(format!(
+            "unsupported export format: {}",
+            format
+        )));
+    }
+
+    let pool = crate::database::get_pool()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let repo = crate::database::Repository::new(pool);
+    let filter = MessageExportFilter {
+        meta_id: query.meta_id,
+        start_ts: query.start_ts,
+        end_ts: query.end_ts,
+        member_id: query.member_id,
+        since_id: query.since_id,
+        since_ts: query.since_ts,
+    };
+
+    // The filter is part of the validator so each query gets its own ETag.
+    let freshness = repo
+        .export_freshness(&filter)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let validator_input = serde_json::to_vec(&(&filter, &freshness))
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let etag = weak_etag(&validator_input);
+    // No Last-Modified: message `ts` is send time, and renames of chats or
+    // members change the body without leaving a modification time behind.
+    if if_none_match_matches(&headers, &etag) {
+        return Ok(with_validators(
+            StatusCode::NOT_MODIFIED.into_response(),
+            &etag,
+            None,
+        ));
+    }
+
+    let pages = stream::try_unfold(
+        (repo, filter, None::<(i64, i64)>, false),
+        |(repo, filter, after, exhausted)| async move {
+            if exhausted {
+                return Ok(None);
+            }
+            let rows = repo
+                .export_messages_page(&filter, after, EXPORT_STREAM_PAGE_SIZE)
+                .await
+                .map_err(std::io::Error::other)?;
+            let Some(last) = rows.last() else {
+                return Ok(None);
+            };
+            let next = Some((last.ts, last.message_id));
+            let exhausted = (rows.len() as i64) < EXPORT_STREAM_PAGE_SIZE;
+
+            let mut chunk = String::new();
+            for row in &rows {
+                chunk.push_str(&serde_json::to_string(row).map_err(std::io::Error::other)?);
+                chunk.push('\n');
+            }
+            Ok::<_, std::io::Error>(Some((chunk, (repo, filter, next, exhausted))))
+        },
+    );
+
+    let response = Response::builder()
+        .header(header::CONTENT_TYPE, "application/x-ndjson")
+        .body(Body::from_stream(pages))
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    Ok(with_validators(response, &etag, None))
+}
+
+// ==================== HTTP Caching ====================
+
+/// Weak ETag (`W/"<fnv1a>"`) over the bytes that identify a response.
+fn weak_etag(bytes: &[u8]) -> String {
+    let mut hash_state: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash_state ^= u64::from(*byte);
+        hash_state = hash_state.wrapping_mul(0x100000001b3);
+    }
+    format!("W/\"{:016x}\"", hash_state)
+}
+
+/// Whether `If-None-Match` lists `etag`, using the weak comparison HTTP
+/// prescribes for this header.
+fn if_none_match_matches(headers: &HeaderMap, etag: &str) -> bool {
+    fn opaque(tag: &str) -> &str {
+        let tag = tag.trim();
+        tag.strip_prefix("W/").unwrap_or(tag)
+    }
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
+}
+
+fn with_validators(mut response: Response, etag: &str, last_modified: Option<i64>) -> Response {
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        headers.insert(header::ETAG, value);
+    }
+    let http_date = last_modified
+        .filter(|ts| *ts > 0)
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map(|dt| dt.format("%a, %d %b %Y %H:%M:%S GMT").to_string());
+    if let Some(value) = http_date.and_then(|date| HeaderValue::from_str(&date).ok()) {
+        headers.insert(header::LAST_MODIFIED, value);
+    }
+    response
+}
+
+/// Serialize `body` as JSON, or answer `304 Not Modified` when the client
+/// already holds the same representation.
+fn json_with_validators<T: Serialize>(
+    headers: &HeaderMap,
+    body: &T,
+    last_modified: Option<i64>,
+) -> Result<Response, ApiError> {
+    let bytes = serde_json::to_vec(body).map_err(|e| ApiError::Internal(e.to_string()))?;
+    let etag = weak_etag(&bytes);
+    let response = if if_none_match_matches(headers, &etag) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        ([(header::CONTENT_TYPE, "application/json")], bytes).into_response()
+    };
+    Ok(with_validators(response, &etag, last_modified))
+}
+
 #[derive(Debug, Deserialize)]
 struct CleanupTempFilesRequest {
     file_paths: Vec<String>,
@@ -7043,6 +7642,7 @@ mod tests {
         assert_eq!(cfg.flush_interval_ms, WEBHOOK_FLUSH_INTERVAL_MS_DEFAULT);
         assert_eq!(cfg.retry_attempts, WEBHOOK_RETRY_ATTEMPTS_DEFAULT);
         assert_eq!(cfg.retry_base_delay_ms, WEBHOOK_RETRY_BASE_DELAY_MS_DEFAULT);
+        assert!(!cfg.retry_jitter);
     }
 
     #[test]
@@ -7054,6 +7654,7 @@ mod tests {
             flush_interval_ms: Some(50_000),
             retry_attempts: Some(100),
             retry_base_delay_ms: Some(10_000),
+            retry_jitter: None,
         });
         assert_eq!(cfg.batch_size, 1);
         assert_eq!(cfg.max_concurrency, 64);
@@ -7086,7 +7687,8 @@ mod tests {
             "requestTimeoutMs": 5000,
             "flushIntervalMs": 250,
             "retryAttempts": 2,
-            "retryBaseDelayMs": 10
+            "retryBaseDelayMs": 10,
+            "retryJitter": true
           }
         }
         "#;
@@ -7105,6 +7707,14 @@ mod tests {
         assert_eq!(cfg.flush_interval_ms, 250);
         assert_eq!(cfg.retry_attempts, 2);
         assert_eq!(cfg.retry_base_delay_ms, 10);
+        assert_eq!(
+            cfg.retry_policy(),
+            WebhookRetryPolicy {
+                attempts: 2,
+                base_delay_ms: 10,
+                jitter: true,
+            }
+        );
     }
 
     #[test]
@@ -7116,6 +7726,7 @@ mod tests {
             flush_interval_ms: Some(1_000),
             retry_attempts: None,
             retry_base_delay_ms: None,
+            retry_jitter: None,
         });
         assert!(!should_flush_webhook_queue(0, None, &cfg));
         assert!(!should_flush_webhook_queue(
@@ -7130,4 +7741,56 @@ mod tests {
             &cfg
         ));
     }
+
+    #[tokio::test]
+    async fn merged_import_publishes_progress_events_for_each_file() {
+        let dir = std::env::temp_dir().join(format!(
+            "xenobot-import-progress-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("system clock should be after unix epoch")
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let config = xenobot_core::config::DatabaseConfig {
+            sqlite_path: dir.join("xenobot.db"),
+            ..Default::default()
+        };
+        crate::database::init_database_with_config(&config)
+            .await
+            .expect("init database");
+
+        let mut paths = Vec::new();
+        for (idx, name) in ["first.txt", "second.txt"].iter().enumerate() {
+            let path = dir.join(name);
+            let body: String = (0..5).map(|n| format!("file {idx} line {n}\n")).collect();
+            std::fs::write(&path, body).expect("write source file");
+            paths.push(path.to_string_lossy().into_owned());
+        }
+
+        let mut rx = crate::events::subscribe_import_progress();
+        let result = super::run_merged_import_batch(&paths, Some("progress"))
+            .await
+            .expect("merged import");
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(result["importedFiles"], 2);
+        for path in &paths {
+            assert!(
+                events
+                    .iter()
+                    .any(|e| e.current_file.as_deref() == Some(path.as_str())),
+                "no progress event for {path}: {events:?}"
+            );
+        }
+        let last = events.last().expect("progress events");
+        assert_eq!(last.status, "complete");
+        assert_eq!(last.total, 10);
+        assert_eq!(last.inserted, 10);
+        assert_eq!(last.processed, 10);
+    }
 }
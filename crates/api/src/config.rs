@@ -28,6 +28,17 @@ pub struct ApiConfig {
     /// Allowed CORS origins.
     pub cors_allowed_origins: Vec<String>,
 
+    /// Methods advertised in `Access-Control-Allow-Methods` for preflight requests.
+    pub cors_allowed_methods: Vec<String>,
+
+    /// Headers advertised in `Access-Control-Allow-Headers` for preflight requests.
+    pub cors_allowed_headers: Vec<String>,
+
+    /// Allow credentialed CORS requests.
+    ///
+    /// Only honored with an explicit origin list; wildcard origins never allow credentials.
+    pub cors_allow_credentials: bool,
+
     /// Request timeout in seconds.
     pub request_timeout_seconds: u64,
 
@@ -159,6 +170,15 @@ impl Default for ApiConfig {
                 "http://localhost:3000".to_string(),
                 "http://localhost:5173".to_string(),
             ],
+            cors_allowed_methods: ["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"]
+                .iter()
+                .map(|method| method.to_string())
+                .collect(),
+            cors_allowed_headers: ["content-type", "authorization", "accept", "x-api-token"]
+                .iter()
+                .map(|header| header.to_string())
+                .collect(),
+            cors_allow_credentials: true,
             request_timeout_seconds: 30,
             enable_request_logging: true,
             enable_compression: true,
@@ -30,6 +30,10 @@ pub fn router() -> Router {
         // Theme
         .route("/theme", post(set_theme_source))
         .route("/platform-capabilities", get(get_platform_capabilities))
+        // Build info
+        .route("/version", get(get_build_info))
+        // Maintenance
+        .route("/maintenance", post(run_maintenance))
         // Dialog
         .route("/dialog/open", post(show_open_dialog))
         // Clipboard
@@ -134,6 +138,78 @@ async fn get_version() -> Result<Json<String>, ApiError> {
     }
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildInfoFeatures {
+    api: bool,
+    analysis: bool,
+    gpu: bool,
+    wechat: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildInfo {
+    version: &'static str,
+    git_commit: Option<&'static str>,
+    build_timestamp: Option<&'static str>,
+    schema_version: i64,
+    features: BuildInfoFeatures,
+}
+
+fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_commit: option_env!("API_GIT_COMMIT")
+            .map(str::trim)
+            .filter(|commit| !commit.is_empty()),
+        build_timestamp: option_env!("API_BUILD_TIMESTAMP"),
+        schema_version: crate::database::supported_schema_version(),
+        features: BuildInfoFeatures {
+            api: true,
+            // The API crate always links the analysis crate.
+            analysis: true,
+            // Metal acceleration is only available on Apple Silicon builds.
+            gpu: option_env!("API_APPLE_SILICON").is_some(),
+            wechat: cfg!(feature = "wechat"),
+        },
+    }
+}
+
+/// Build and compatibility info for frontend/backend version checks.
+#[instrument]
+async fn get_build_info() -> Json<BuildInfo> {
+    Json(build_info())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MaintenanceResponse {
+    steps: Vec<crate::database::MaintenanceStep>,
+    total_duration_ms: u64,
+}
+
+/// Rebuild FTS indexes, refresh planner statistics and optionally truncate the WAL.
+#[instrument]
+async fn run_maintenance(
+    body: Option<Json<crate::database::MaintenanceOptions>>,
+) -> Result<Json<MaintenanceResponse>, ApiError> {
+    let options = body.map(|Json(options)| options).unwrap_or_default();
+    let pool = crate::database::get_pool()
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let repo = crate::database::Repository::new(pool);
+    let steps = repo
+        .run_maintenance(&options)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    let total_duration_ms = steps.iter().map(|step| step.duration_ms).sum();
+    Ok(Json(MaintenanceResponse {
+        steps,
+        total_duration_ms,
+    }))
+}
+
 #[instrument]
 async fn check_update() -> Result<Json<serde_json::Value>, ApiError> {
     Ok(Json(serde_json::json!({
@@ -262,4 +338,39 @@ mod tests {
         assert_eq!(wechat["atWechatDepth"], true);
         assert_eq!(wechat["ingest"]["nativeRuntimeDetector"], true);
     }
+
+    #[tokio::test]
+    async fn version_route_reports_crate_and_schema_version() {
+        let app = router();
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("/version")
+            .body(Body::empty())
+            .expect("build request");
+
+        let response = app.oneshot(request).await.expect("route response");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let json: serde_json::Value = serde_json::from_slice(&bytes).expect("json body");
+
+        let version = json["version"].as_str().expect("version string");
+        assert!(!version.is_empty());
+        assert_eq!(version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(json["features"]["api"], true);
+
+        let latest_on_disk =
+            std::fs::read_dir(std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations"))
+                .expect("read migrations dir")
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    name.split('_').next()?.parse::<i64>().ok()
+                })
+                .max()
+                .expect("at least one migration");
+        assert_eq!(json["schemaVersion"], latest_on_disk);
+    }
 }
@@ -12,6 +12,7 @@ use xenobot_core::config::DatabaseConfig;
 
 static DB_POOL: Lazy<Mutex<Option<SqlitePool>>> = Lazy::new(|| Mutex::new(None));
 static DB_PATH: Lazy<RwLock<Option<PathBuf>>> = Lazy::new(|| RwLock::new(None));
+static MEDIA_ROOT: Lazy<RwLock<Option<PathBuf>>> = Lazy::new(|| RwLock::new(None));
 // Migrator is created dynamically in run_migrations()
 
 fn migrations_dir() -> PathBuf {
@@ -37,14 +38,75 @@ pub fn get_db_path() -> PathBuf {
     default_db_path()
 }
 
+/// Root directory that relative media paths are resolved against.
+pub fn get_media_root() -> PathBuf {
+    if let Some(path) = MEDIA_ROOT
+        .read()
+        .ok()
+        .and_then(|guard| guard.as_ref().cloned())
+    {
+        return path;
+    }
+
+    if let Ok(path) = std::env::var("XENOBOT_MEDIA_ROOT") {
+        let trimmed = path.trim();
+        if !trimmed.is_empty() {
+            return PathBuf::from(trimmed);
+        }
+    }
+
+    default_media_root(&get_db_path())
+}
+
+fn default_media_root(db_path: &Path) -> PathBuf {
+    db_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("media")
+}
+
+fn resolve_media_root(config: &DatabaseConfig, db_path: &Path) -> PathBuf {
+    if let Ok(path) = std::env::var("XENOBOT_MEDIA_ROOT") {
+        let trimmed = path.trim();
+        if !trimmed.is_empty() {
+            return PathBuf::from(trimmed);
+        }
+    }
+
+    match config.media_root.as_ref() {
+        Some(root) if root.is_absolute() => root.clone(),
+        Some(root) => db_path
+            .parent()
+            .map(|parent| parent.join(root))
+            .unwrap_or_else(|| root.clone()),
+        None => default_media_root(db_path),
+    }
+}
+
 fn default_db_path() -> PathBuf {
     let data_dir = dirs::data_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join("xenobot");
-    std::fs::create_dir_all(&data_dir).ok();
+    create_private_dir(&data_dir).ok();
     data_dir.join("xenobot.db")
 }
 
+/// Create `dir` (and missing parents) readable by the owner only; the data
+/// directory holds chat history.
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)
+    }
+    #[cfg(not(unix))]
+    fs::create_dir_all(dir)
+}
+
 fn resolve_db_path(config: &DatabaseConfig) -> PathBuf {
     if let Ok(path) = std::env::var("XENOBOT_DB_PATH") {
         let trimmed = path.trim();
@@ -61,7 +123,12 @@ fn resolve_db_path(config: &DatabaseConfig) -> PathBuf {
 }
 
 pub async fn init_database() -> Result<(), Box<dyn std::error::Error>> {
-    let config = DatabaseConfig::default();
+    let config = DatabaseConfig {
+        wal_autocheckpoint: std::env::var("XENOBOT_WAL_AUTOCHECKPOINT")
+            .ok()
+            .and_then(|value| value.trim().parse::<u32>().ok()),
+        ..DatabaseConfig::default()
+    };
     init_database_with_config(&config).await
 }
 
@@ -78,12 +145,15 @@ pub async fn init_database_with_config(
         fs::create_dir_all(parent)?;
     }
 
-    let connect_options = SqliteConnectOptions::new()
+    let mut connect_options = SqliteConnectOptions::new()
         .filename(&db_path)
         .create_if_missing(true)
         .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
         .foreign_keys(true)
         .synchronous(sqlx::sqlite::SqliteSynchronous::Normal);
+    if let Some(pages) = config.wal_autocheckpoint {
+        connect_options = connect_options.pragma("wal_autocheckpoint", pages.to_string());
+    }
 
     let pool = SqlitePoolOptions::new()
         .max_connections(config.max_connections as u32)
@@ -96,6 +166,10 @@ pub async fn init_database_with_config(
     let mut pool_guard = DB_POOL.lock().await;
     *pool_guard = Some(pool);
 
+    if let Ok(mut media_guard) = MEDIA_ROOT.write() {
+        *media_guard = Some(resolve_media_root(config, &db_path));
+    }
+
     if let Ok(mut path_guard) = DB_PATH.write() {
         *path_guard = Some(db_path);
     }
@@ -104,6 +178,44 @@ pub async fn init_database_with_config(
     Ok(())
 }
 
+/// Pool for previews that must not write. The configured database is opened
+/// read-only and its migrations are not run; a database that does not exist
+/// yet is not created, and an empty in-memory database with the current
+/// schema stands in for it.
+pub async fn open_preview_pool(
+    config: &DatabaseConfig,
+) -> Result<Arc<SqlitePool>, Box<dyn std::error::Error>> {
+    let db_path = resolve_db_path(config);
+    if db_path.is_file() {
+        info!("Opening database read-only at: {:?}", db_path);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(config.max_connections as u32)
+            .acquire_timeout(std::time::Duration::from_secs(config.connection_timeout))
+            .connect_with(
+                SqliteConnectOptions::new()
+                    .filename(&db_path)
+                    .read_only(true),
+            )
+            .await?;
+        return Ok(Arc::new(pool));
+    }
+
+    info!(
+        "No database at {:?}; previewing against an empty in-memory database",
+        db_path
+    );
+    // Every in-memory connection is its own database, so the pool keeps
+    // exactly one open for its whole lifetime.
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect_with("sqlite::memory:".parse::<SqliteConnectOptions>()?)
+        .await?;
+    run_migrations(&pool).await?;
+    Ok(Arc::new(pool))
+}
+
 pub async fn get_pool() -> Result<Arc<SqlitePool>, Box<dyn std::error::Error>> {
     let pool_guard = DB_POOL.lock().await;
     if let Some(pool) = pool_guard.as_ref() {
@@ -126,11 +238,94 @@ pub async fn run_migrations(pool: &SqlitePool) -> Result<(), Box<dyn std::error:
     info!("Running SQLx database migrations...");
     let migrations_dir = migrations_dir();
     let migrator = Migrator::new(Path::new(&migrations_dir)).await?;
+    let reconciled = reconcile_legacy_migrations(pool, &migrator).await?;
+    if reconciled > 0 {
+        info!(
+            "Adopted {} migration(s) recorded in legacy schema_migrations",
+            reconciled
+        );
+    }
     migrator.run(pool).await?;
     info!("Database migrations completed");
     Ok(())
 }
 
+/// Copies versions recorded in the legacy `schema_migrations` table (written by
+/// older CLI builds) into `_sqlx_migrations`, the single tracking table both the
+/// CLI and the API now use, so schema that is already present is treated as
+/// applied instead of being re-run. Returns how many versions were adopted.
+pub async fn reconcile_legacy_migrations(
+    pool: &SqlitePool,
+    migrator: &Migrator,
+) -> Result<u64, sqlx::Error> {
+    let legacy_exists = sqlx::query_scalar::<_, i64>(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations'",
+    )
+    .fetch_optional(pool)
+    .await?
+    .is_some();
+    if !legacy_exists {
+        return Ok(0);
+    }
+
+    // Same layout sqlx creates, so `Migrator::run` accepts the adopted rows.
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS _sqlx_migrations (
+            version BIGINT PRIMARY KEY,
+            description TEXT NOT NULL,
+            installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            success BOOLEAN NOT NULL,
+            checksum BLOB NOT NULL,
+            execution_time BIGINT NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    let legacy_versions: Vec<i64> = sqlx::query_scalar("SELECT version FROM schema_migrations")
+        .fetch_all(pool)
+        .await?;
+    let mut adopted = 0u64;
+    for migration in migrator.iter() {
+        if !legacy_versions.contains(&migration.version) {
+            continue;
+        }
+        adopted += sqlx::query(
+            "INSERT OR IGNORE INTO _sqlx_migrations \
+             (version, description, success, checksum, execution_time) \
+             VALUES (?1, ?2, TRUE, ?3, 0)",
+        )
+        .bind(migration.version)
+        .bind(&*migration.description)
+        .bind(&*migration.checksum)
+        .execute(pool)
+        .await?
+        .rows_affected();
+    }
+    Ok(adopted)
+}
+
+/// Highest migration version shipped with this build (the `NNN` prefix of
+/// the newest `migrations/NNN_*.sql` file), or 0 when none are present.
+pub fn supported_schema_version() -> i64 {
+    let Ok(entries) = fs::read_dir(migrations_dir()) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.ends_with(".sql") {
+                return None;
+            }
+            name.split('_').next()?.parse::<i64>().ok()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
 pub fn ensure_migrations_dir() -> Result<(), std::io::Error> {
     let migrations_dir = migrations_dir();
     if !migrations_dir.exists() {
@@ -47,6 +47,7 @@ pub struct TimeRange {
 
 use serde::{Deserialize, Serialize};
 use sqlx::{sqlite::SqlitePool, FromRow, Result as SqlxResult};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -74,6 +75,18 @@ pub struct Member {
     pub roles: Option<String>,
 }
 
+/// A member who has sent messages in a chat, with their message count.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ChatMemberSummary {
+    pub id: i64,
+    pub platform_id: String,
+    pub account_name: Option<String>,
+    pub group_nickname: Option<String>,
+    pub aliases: Option<String>,
+    pub avatar: Option<String>,
+    pub message_count: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Message {
     pub id: i64,
@@ -85,7 +98,64 @@ pub struct Message {
     pub content: Option<String>,
     pub reply_to_message_id: Option<String>,
     pub platform_message_id: Option<String>,
+    /// When the message was last edited, if the source recorded it.
+    pub edited_at: Option<i64>,
+    pub meta_id: i64,
+}
+
+/// A message with the messages it replies to and, optionally, its replies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageThread {
+    /// Reply ancestors, from the thread root down to the direct parent.
+    pub ancestors: Vec<Message>,
+    pub message: Message,
+    /// Direct replies, oldest first.
+    pub replies: Vec<Message>,
+    /// The ancestor walk hit its depth limit before reaching the root.
+    pub truncated: bool,
+}
+
+/// Filters shared by the message export paths; `None` fields are not applied.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MessageExportFilter {
+    pub meta_id: Option<i64>,
+    pub start_ts: Option<i64>,
+    /// Exclusive upper bound.
+    pub end_ts: Option<i64>,
+    pub member_id: Option<i64>,
+    pub since_id: Option<i64>,
+    pub since_ts: Option<i64>,
+}
+
+/// Change markers for the rows an export filter selects, used to build HTTP
+/// cache validators without reading the rows themselves.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ExportFreshness {
+    pub message_count: i64,
+    pub max_message_id: i64,
+    pub max_ts: i64,
+    pub max_edited_at: i64,
+    pub last_imported_at: i64,
+    /// `id`, `name`, and `platform` of the chats in scope, so renames count as changes.
+    pub chats_signature: String,
+    /// `id`, `account_name`, and `group_nickname` of the senders in scope, so
+    /// member renames count as changes.
+    pub members_signature: String,
+}
+
+/// One exported message, flattened with its chat and sender names.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ExportMessageRow {
+    pub message_id: i64,
     pub meta_id: i64,
+    pub platform: String,
+    pub chat_name: String,
+    pub sender_id: i64,
+    pub sender_name: String,
+    pub ts: i64,
+    pub msg_type: i64,
+    pub content: Option<String>,
+    pub edited_at: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -196,6 +266,19 @@ pub struct ImportSourceCheckpoint {
     pub last_duplicate_messages: i64,
     pub status: String,
     pub error_message: Option<String>,
+    /// Encoding a text export was decoded with; `None` for structured formats.
+    pub source_encoding: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MessageMedia {
+    pub id: i64,
+    pub message_id: i64,
+    pub media_kind: String,
+    pub local_path: String,
+    pub mime_type: Option<String>,
+    pub file_size: Option<i64>,
+    pub created_at: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -384,13 +467,240 @@ pub struct LaughAnalysis {
     pub group_laugh_rate: f64,
 }
 
+/// Toggles for [`Repository::run_maintenance`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceOptions {
+    #[serde(default = "default_true")]
+    pub rebuild_fts: bool,
+    #[serde(default = "default_true")]
+    pub analyze: bool,
+    #[serde(default)]
+    pub wal_checkpoint: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for MaintenanceOptions {
+    fn default() -> Self {
+        Self {
+            rebuild_fts: true,
+            analyze: true,
+            wal_checkpoint: false,
+        }
+    }
+}
+
+/// Outcome of one maintenance step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceStep {
+    pub name: String,
+    pub duration_ms: u64,
+    pub detail: Option<String>,
+}
+
+/// Columns the write methods bind, per table. Checked before the first write
+/// so a database migrated by a different build is refused up front instead of
+/// failing (or silently misbehaving) inside an INSERT.
+const WRITE_SCHEMA_REQUIREMENTS: &[(&str, &[&str])] = &[
+    (
+        "meta",
+        &[
+            "name",
+            "platform",
+            "chat_type",
+            "imported_at",
+            "group_id",
+            "group_avatar",
+            "owner_id",
+            "schema_version",
+            "session_gap_threshold",
+        ],
+    ),
+    (
+        "member",
+        &[
+            "platform_id",
+            "account_name",
+            "group_nickname",
+            "aliases",
+            "avatar",
+            "roles",
+        ],
+    ),
+    (
+        "message",
+        &[
+            "sender_id",
+            "sender_account_name",
+            "sender_group_nickname",
+            "ts",
+            "msg_type",
+            "content",
+            "reply_to_message_id",
+            "platform_message_id",
+            "meta_id",
+            "edited_at",
+        ],
+    ),
+    (
+        "message_media",
+        &[
+            "message_id",
+            "media_kind",
+            "local_path",
+            "mime_type",
+            "file_size",
+            "created_at",
+        ],
+    ),
+];
+
+async fn check_write_schema(pool: &SqlitePool) -> SqlxResult<()> {
+    let mut problems = Vec::new();
+    for (table, expected) in WRITE_SCHEMA_REQUIREMENTS {
+        let columns: Vec<(String, i64, Option<String>, i64)> =
+            sqlx::query_as(r#"SELECT name, "notnull", dflt_value, pk FROM pragma_table_info(?1)"#)
+                .bind(table)
+                .fetch_all(pool)
+                .await?;
+        if columns.is_empty() {
+            problems.push(format!("table `{table}` is missing"));
+            continue;
+        }
+
+        let missing: Vec<&str> = expected
+            .iter()
+            .copied()
+            .filter(|name| !columns.iter().any(|(column, ..)| column == name))
+            .collect();
+        if !missing.is_empty() {
+            problems.push(format!("`{table}` lacks {}", missing.join(", ")));
+        }
+
+        let unset_required: Vec<&str> = columns
+            .iter()
+            .filter(|(name, not_null, default, pk)| {
+                *not_null != 0
+                    && default.is_none()
+                    && *pk == 0
+                    && !expected.contains(&name.as_str())
+            })
+            .map(|(name, ..)| name.as_str())
+            .collect();
+        if !unset_required.is_empty() {
+            problems.push(format!(
+                "`{table}` requires {} which this build does not write",
+                unset_required.join(", ")
+            ));
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(sqlx::Error::Configuration(
+        format!(
+            "database schema is incompatible with this build ({}); migrate it with a matching xenobot version before writing",
+            problems.join("; ")
+        )
+        .into(),
+    ))
+}
+
+#[derive(Clone)]
 pub struct Repository {
     pool: Arc<SqlitePool>,
+    media_root: Option<PathBuf>,
+    write_schema_checked: Arc<tokio::sync::OnceCell<()>>,
 }
 
 impl Repository {
     pub fn new(pool: Arc<SqlitePool>) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            media_root: None,
+            write_schema_checked: Arc::new(tokio::sync::OnceCell::new()),
+        }
+    }
+
+    /// Verify that the tables written by this repository have the columns
+    /// this build binds, and no `NOT NULL` column without a default that it
+    /// would leave unset. Succeeds at most once per repository (and its
+    /// clones); a failed check is retried on the next write.
+    pub async fn ensure_write_schema(&self) -> SqlxResult<()> {
+        self.write_schema_checked
+            .get_or_try_init(|| check_write_schema(&self.pool))
+            .await
+            .map(|_| ())
+    }
+
+    /// Pool for statements that modify the database. Every write goes
+    /// through here, so the schema is checked before the first one.
+    async fn writer(&self) -> SqlxResult<&SqlitePool> {
+        self.ensure_write_schema().await?;
+        Ok(&*self.pool)
+    }
+
+    /// Override the media root used to store and resolve media paths.
+    pub fn with_media_root(mut self, media_root: impl Into<PathBuf>) -> Self {
+        self.media_root = Some(media_root.into());
+        self
+    }
+
+    /// Media root for this repository, falling back to the process-wide root.
+    pub fn media_root(&self) -> PathBuf {
+        self.media_root
+            .clone()
+            .unwrap_or_else(super::get_media_root)
+    }
+
+    /// Convert a media file path into the form persisted in `message_media`.
+    ///
+    /// Paths under the media root are stored relative with `/` separators;
+    /// anything outside the root is kept absolute.
+    pub fn relativize_media_path(&self, path: &Path) -> String {
+        let root = self.media_root();
+        match path.strip_prefix(&root) {
+            Ok(relative) => relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/"),
+            Err(_) => path.to_string_lossy().to_string(),
+        }
+    }
+
+    /// Resolve a stored media path to an absolute path under the current media root.
+    ///
+    /// Legacy absolute paths are returned unchanged when they still exist; when
+    /// they have gone missing (e.g. the data dir moved) the file name is looked
+    /// up directly under the media root instead.
+    pub fn resolve_media_path(&self, stored: &str) -> PathBuf {
+        let root = self.media_root();
+        let stored_path = Path::new(stored);
+        if !stored_path.is_absolute() {
+            return stored
+                .split('/')
+                .filter(|part| !part.is_empty())
+                .fold(root, |acc, part| acc.join(part));
+        }
+
+        if stored_path.exists() {
+            return stored_path.to_path_buf();
+        }
+
+        if let Some(file_name) = stored_path.file_name() {
+            let relocated = root.join(file_name);
+            if relocated.exists() {
+                return relocated;
+            }
+        }
+
+        stored_path.to_path_buf()
     }
 
     // Meta (ChatMeta) methods
@@ -410,7 +720,7 @@ impl Repository {
             meta.schema_version,
             meta.session_gap_threshold
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
 
         Ok(result.last_insert_rowid())
@@ -446,7 +756,7 @@ impl Repository {
             meta.schema_version,
             meta.session_gap_threshold
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
@@ -483,11 +793,45 @@ impl Repository {
         }
     }
 
-    pub async fn delete_chat(&self, id: i64) -> SqlxResult<()> {
-        sqlx::query!("DELETE FROM meta WHERE id = ?1", id)
-            .execute(&*self.pool)
+    /// Renames a chat. Returns `false` when no chat has this id.
+    pub async fn rename_chat(&self, meta_id: i64, new_name: &str) -> SqlxResult<bool> {
+        let result = sqlx::query("UPDATE meta SET name = ?2 WHERE id = ?1")
+            .bind(meta_id)
+            .bind(new_name)
+            .execute(self.writer().await?)
             .await?;
-        Ok(())
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Deletes a chat together with its messages, media, sessions and caches.
+    ///
+    /// Dependent rows are removed explicitly inside one transaction so the
+    /// delete is complete even on connections without `foreign_keys` enabled.
+    /// Returns `false` when no chat has this id.
+    pub async fn delete_chat(&self, id: i64) -> SqlxResult<bool> {
+        let mut tx = self.writer().await?.begin().await?;
+        for sql in [
+            "DELETE FROM message_media WHERE message_id IN (SELECT id FROM message WHERE meta_id = ?1)",
+            "DELETE FROM embedding_cache WHERE message_id IN (SELECT id FROM message WHERE meta_id = ?1)",
+            "DELETE FROM message_context WHERE message_id IN (SELECT id FROM message WHERE meta_id = ?1)",
+            "DELETE FROM message_context WHERE session_id IN (SELECT id FROM chat_session WHERE meta_id = ?1)",
+            "DELETE FROM session_messages WHERE message_id IN (SELECT id FROM message WHERE meta_id = ?1)",
+            "DELETE FROM session_messages WHERE session_id IN (SELECT id FROM sessions WHERE meta_id = ?1)",
+            "DELETE FROM memory_entry WHERE meta_id = ?1",
+            "DELETE FROM message WHERE meta_id = ?1",
+            "DELETE FROM chat_session WHERE meta_id = ?1",
+            "DELETE FROM sessions WHERE meta_id = ?1",
+            "DELETE FROM analysis_cache WHERE meta_id = ?1",
+            "UPDATE import_source_checkpoint SET meta_id = NULL WHERE meta_id = ?1",
+        ] {
+            sqlx::query(sql).bind(id).execute(&mut *tx).await?;
+        }
+        let result = sqlx::query("DELETE FROM meta WHERE id = ?1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
     }
 
     // Member methods
@@ -504,7 +848,7 @@ impl Repository {
             member.avatar,
             member.roles
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
 
         Ok(result.last_insert_rowid())
@@ -549,14 +893,14 @@ impl Repository {
             member.avatar,
             member.roles
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
 
     pub async fn delete_member(&self, id: i64) -> SqlxResult<()> {
         sqlx::query!("DELETE FROM member WHERE id = ?1", id)
-            .execute(&*self.pool)
+            .execute(self.writer().await?)
             .await?;
         Ok(())
     }
@@ -575,6 +919,27 @@ impl Repository {
         .await
     }
 
+    /// Members who sent at least one non-system message in the chat, ordered
+    /// by member id. Returns an empty list for chats without messages.
+    pub async fn list_chat_members(&self, meta_id: i64) -> SqlxResult<Vec<ChatMemberSummary>> {
+        sqlx::query_as(
+            r#"
+            SELECT m.id, m.platform_id, m.account_name, m.group_nickname, m.aliases, m.avatar,
+                   COUNT(msg.id) AS message_count
+            FROM member m
+            INNER JOIN message msg ON m.id = msg.sender_id
+            WHERE msg.meta_id = ?1
+              AND msg.msg_type != 7
+              AND COALESCE(m.account_name, '') != '系统消息'
+            GROUP BY m.id
+            ORDER BY m.id
+            "#,
+        )
+        .bind(meta_id)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
     pub async fn get_or_create_member(
         &self,
         platform_id: &str,
@@ -594,7 +959,7 @@ impl Repository {
             platform_id,
             account_name
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
 
         Ok(result.last_insert_rowid())
@@ -646,7 +1011,7 @@ impl Repository {
             normalized_account_name,
             normalized_group_nickname
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
 
         Ok(result.last_insert_rowid())
@@ -709,8 +1074,8 @@ impl Repository {
     pub async fn create_message(&self, msg: &Message) -> SqlxResult<i64> {
         let result = sqlx::query!(
             r#"
-            INSERT INTO message (sender_id, sender_account_name, sender_group_nickname, ts, msg_type, content, reply_to_message_id, platform_message_id, meta_id)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            INSERT INTO message (sender_id, sender_account_name, sender_group_nickname, ts, msg_type, content, reply_to_message_id, platform_message_id, edited_at, meta_id)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
             "#,
             msg.sender_id,
             msg.sender_account_name,
@@ -720,9 +1085,10 @@ impl Repository {
             msg.content,
             msg.reply_to_message_id,
             msg.platform_message_id,
+            msg.edited_at,
             msg.meta_id
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
 
         Ok(result.last_insert_rowid())
@@ -732,7 +1098,7 @@ impl Repository {
         sqlx::query_as!(
             Message,
             r#"
-            SELECT id, sender_id, sender_account_name, sender_group_nickname, ts, msg_type, content, reply_to_message_id, platform_message_id, meta_id
+            SELECT id, sender_id, sender_account_name, sender_group_nickname, ts, msg_type, content, reply_to_message_id, platform_message_id, edited_at, meta_id
             FROM message WHERE id = ?1
             "#,
             id
@@ -746,7 +1112,7 @@ impl Repository {
             r#"
             UPDATE message SET sender_id = ?2, sender_account_name = ?3, sender_group_nickname = ?4,
             ts = ?5, msg_type = ?6, content = ?7, reply_to_message_id = ?8, platform_message_id = ?9,
-            meta_id = ?10 WHERE id = ?1
+            edited_at = ?10, meta_id = ?11 WHERE id = ?1
             "#,
             msg.id,
             msg.sender_id,
@@ -757,16 +1123,17 @@ impl Repository {
             msg.content,
             msg.reply_to_message_id,
             msg.platform_message_id,
+            msg.edited_at,
             msg.meta_id
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
 
     pub async fn delete_message(&self, id: i64) -> SqlxResult<()> {
         sqlx::query!("DELETE FROM message WHERE id = ?1", id)
-            .execute(&*self.pool)
+            .execute(self.writer().await?)
             .await?;
         Ok(())
     }
@@ -779,8 +1146,8 @@ impl Repository {
     ) -> SqlxResult<Vec<Message>> {
         sqlx::query_as::<_, Message>(
             r#"
-            SELECT id, sender_id, sender_account_name, sender_group_nickname, ts, msg_type, content, reply_to_message_id, platform_message_id, meta_id
-            FROM message WHERE meta_id = ?1 ORDER BY ts DESC LIMIT ?2 OFFSET ?3
+            SELECT id, sender_id, sender_account_name, sender_group_nickname, ts, msg_type, content, reply_to_message_id, platform_message_id, edited_at, meta_id
+            FROM message WHERE meta_id = ?1 ORDER BY ts DESC, id DESC LIMIT ?2 OFFSET ?3
             "#,
         )
         .bind(meta_id)
@@ -790,6 +1157,100 @@ impl Repository {
         .await
     }
 
+    /// Load message `id` with up to `max_depth` reply ancestors and up to
+    /// `max_replies` direct replies, all within the message's chat.
+    ///
+    /// `reply_to_message_id` names the parent's `platform_message_id`; only a
+    /// value that is no native id in the chat is read as the parent's row id.
+    /// The ancestor walk stops at a message it has already visited, so a reply
+    /// cycle ends the chain instead of looping.
+    pub async fn get_message_thread(
+        &self,
+        id: i64,
+        max_depth: usize,
+        max_replies: i64,
+    ) -> SqlxResult<Option<MessageThread>> {
+        let Some(message) = self.get_message(id).await? else {
+            return Ok(None);
+        };
+
+        let mut visited = std::collections::HashSet::from([message.id]);
+        let mut ancestors = Vec::new();
+        let mut truncated = false;
+        let mut reference = message.reply_to_message_id.clone();
+        while let Some(parent_ref) = reference.take() {
+            let parent_ref = parent_ref.trim();
+            if parent_ref.is_empty() {
+                break;
+            }
+            if ancestors.len() >= max_depth {
+                truncated = true;
+                break;
+            }
+            let mut parent = sqlx::query_as::<_, Message>(
+                r#"
+                SELECT id, sender_id, sender_account_name, sender_group_nickname, ts, msg_type, content, reply_to_message_id, platform_message_id, edited_at, meta_id
+                FROM message
+                WHERE meta_id = ?1 AND platform_message_id = ?2
+                "#,
+            )
+            .bind(message.meta_id)
+            .bind(parent_ref)
+            .fetch_optional(&*self.pool)
+            .await?;
+            if parent.is_none() {
+                if let Ok(row_id) = parent_ref.parse::<i64>() {
+                    parent = self
+                        .get_message(row_id)
+                        .await?
+                        .filter(|row| row.meta_id == message.meta_id);
+                }
+            }
+            let Some(parent) = parent else {
+                break;
+            };
+            if !visited.insert(parent.id) {
+                break;
+            }
+            reference = parent.reply_to_message_id.clone();
+            ancestors.push(parent);
+        }
+        ancestors.reverse();
+
+        let replies = if max_replies > 0 {
+            sqlx::query_as::<_, Message>(
+                r#"
+                SELECT id, sender_id, sender_account_name, sender_group_nickname, ts, msg_type, content, reply_to_message_id, platform_message_id, edited_at, meta_id
+                FROM message
+                WHERE meta_id = ?1 AND id != ?2
+                  AND ((?3 IS NOT NULL AND reply_to_message_id = ?3)
+                       OR (reply_to_message_id = CAST(?2 AS TEXT)
+                           AND NOT EXISTS (
+                               SELECT 1 FROM message native
+                               WHERE native.meta_id = ?1
+                                 AND native.platform_message_id = CAST(?2 AS TEXT))))
+                ORDER BY ts ASC, id ASC
+                LIMIT ?4
+                "#,
+            )
+            .bind(message.meta_id)
+            .bind(message.id)
+            .bind(message.platform_message_id.as_deref())
+            .bind(max_replies)
+            .fetch_all(&*self.pool)
+            .await?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Some(MessageThread {
+            ancestors,
+            message,
+            replies,
+            truncated,
+        }))
+    }
+
     pub async fn search_messages(
         &self,
         meta_id: i64,
@@ -799,8 +1260,8 @@ impl Repository {
         let pattern = format!("%{}%", keyword);
         sqlx::query_as::<_, Message>(
             r#"
-            SELECT id, sender_id, sender_account_name, sender_group_nickname, ts, msg_type, content, reply_to_message_id, platform_message_id, meta_id
-            FROM message WHERE meta_id = ?1 AND content LIKE ?2 ORDER BY ts DESC LIMIT ?3
+            SELECT id, sender_id, sender_account_name, sender_group_nickname, ts, msg_type, content, reply_to_message_id, platform_message_id, edited_at, meta_id
+            FROM message WHERE meta_id = ?1 AND content LIKE ?2 ORDER BY ts DESC, id DESC LIMIT ?3
             "#,
         )
         .bind(meta_id)
@@ -839,6 +1300,245 @@ impl Repository {
         Ok(count > 0)
     }
 
+    /// Like [`Self::message_exists`], but checks every chat on `platform`,
+    /// or every chat at all when `platform` is `None`.
+    pub async fn message_exists_across_chats(
+        &self,
+        platform: Option<&str>,
+        sender_id: i64,
+        ts: i64,
+        msg_type: i64,
+        content: Option<&str>,
+    ) -> SqlxResult<bool> {
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) as count
+            FROM message msg
+            JOIN meta ON meta.id = msg.meta_id
+            WHERE (?1 IS NULL OR meta.platform = ?1)
+              AND msg.sender_id = ?2
+              AND msg.ts = ?3
+              AND msg.msg_type = ?4
+              AND COALESCE(msg.content, '') = COALESCE(?5, '')
+            "#,
+        )
+        .bind(platform)
+        .bind(sender_id)
+        .bind(ts)
+        .bind(msg_type)
+        .bind(content)
+        .fetch_one(&*self.pool)
+        .await?;
+        Ok(count > 0)
+    }
+
+    /// Edit time of the message holding this native id in `meta_id`: `None`
+    /// when there is no such message, `Some(None)` when it was never edited.
+    pub async fn platform_message_edited_at(
+        &self,
+        meta_id: i64,
+        platform_message_id: &str,
+    ) -> SqlxResult<Option<Option<i64>>> {
+        sqlx::query_scalar(
+            r#"
+            SELECT edited_at
+            FROM message
+            WHERE meta_id = ?1
+              AND platform_message_id = ?2
+            "#,
+        )
+        .bind(meta_id)
+        .bind(platform_message_id)
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    /// Replace the content of the message holding this native id when
+    /// `edited_at` is newer than its stored edit. Returns whether a row
+    /// changed.
+    pub async fn apply_platform_message_edit(
+        &self,
+        meta_id: i64,
+        platform_message_id: &str,
+        content: Option<&str>,
+        edited_at: i64,
+    ) -> SqlxResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE message
+            SET content = ?3, edited_at = ?4
+            WHERE meta_id = ?1
+              AND platform_message_id = ?2
+              AND (edited_at IS NULL OR edited_at < ?4)
+            "#,
+        )
+        .bind(meta_id)
+        .bind(platform_message_id)
+        .bind(content)
+        .bind(edited_at)
+        .execute(self.writer().await?)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Fetch one page of export rows ordered by `(ts, id)`.
+    ///
+    /// `after` is the `(ts, id)` of the last row of the previous page, so callers
+    /// can walk the whole result set with bounded memory.
+    pub async fn export_messages_page(
+        &self,
+        filter: &MessageExportFilter,
+        after: Option<(i64, i64)>,
+        limit: i64,
+    ) -> SqlxResult<Vec<ExportMessageRow>> {
+        let mut builder = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+            r#"
+            SELECT
+                msg.id AS message_id,
+                msg.meta_id,
+                meta.platform,
+                meta.name AS chat_name,
+                msg.sender_id,
+                COALESCE(msg.sender_account_name, member.account_name, '') AS sender_name,
+                msg.ts,
+                msg.msg_type,
+                msg.content,
+                msg.edited_at
+            FROM message msg
+            JOIN meta ON meta.id = msg.meta_id
+            LEFT JOIN member ON member.id = msg.sender_id
+            WHERE 1 = 1
+            "#,
+        );
+        push_export_filter(&mut builder, filter);
+        if let Some((ts, id)) = after {
+            builder
+                .push(" AND (msg.ts > ")
+                .push_bind(ts)
+                .push(" OR (msg.ts = ")
+                .push_bind(ts)
+                .push(" AND msg.id > ")
+                .push_bind(id)
+                .push("))");
+        }
+        builder
+            .push(" ORDER BY msg.ts ASC, msg.id ASC LIMIT ")
+            .push_bind(limit);
+
+        builder
+            .build_query_as::<ExportMessageRow>()
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Summarize what `filter` selects so callers can tell whether an export
+    /// changed since a previous read.
+    pub async fn export_freshness(
+        &self,
+        filter: &MessageExportFilter,
+    ) -> SqlxResult<ExportFreshness> {
+        let mut builder = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+            r#"
+            SELECT
+                COUNT(*) AS message_count,
+                COALESCE(MAX(msg.id), 0) AS max_message_id,
+                COALESCE(MAX(msg.ts), 0) AS max_ts,
+                COALESCE(MAX(msg.edited_at), 0) AS max_edited_at
+            FROM message msg
+            WHERE 1 = 1
+            "#,
+        );
+        push_export_filter(&mut builder, filter);
+        let (message_count, max_message_id, max_ts, max_edited_at) = builder
+            .build_query_as::<(i64, i64, i64, i64)>()
+            .fetch_one(&*self.pool)
+            .await?;
+
+        let mut chats = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+            r#"
+            SELECT
+                COALESCE(MAX(imported_at), 0),
+                COALESCE(GROUP_CONCAT(id || ':' || platform || ':' || name, char(30)), '')
+            FROM (SELECT id, platform, name, imported_at FROM meta WHERE 1 = 1
+            "#,
+        );
+        if let Some(meta_id) = filter.meta_id {
+            chats.push(" AND id = ").push_bind(meta_id);
+        }
+        chats.push(" ORDER BY id)");
+        let (last_imported_at, chats_signature) = chats
+            .build_query_as::<(i64, String)>()
+            .fetch_one(&*self.pool)
+            .await?;
+
+        let mut members = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+            r#"
+            SELECT COALESCE(GROUP_CONCAT(
+                id || ':' || COALESCE(account_name, '') || ':' || COALESCE(group_nickname, ''),
+                char(30)
+            ), '')
+            FROM (SELECT id, account_name, group_nickname FROM member
+                  WHERE id IN (SELECT msg.sender_id FROM message msg WHERE 1 = 1
+            "#,
+        );
+        push_export_filter(&mut members, filter);
+        members.push(") ORDER BY id)");
+        let members_signature = members
+            .build_query_scalar::<String>()
+            .fetch_one(&*self.pool)
+            .await?;
+
+        Ok(ExportFreshness {
+            message_count,
+            max_message_id,
+            max_ts,
+            max_edited_at,
+            last_imported_at,
+            chats_signature,
+            members_signature,
+        })
+    }
+
+    // MessageMedia methods
+    pub async fn create_message_media(
+        &self,
+        message_id: i64,
+        media_kind: &str,
+        path: &Path,
+        mime_type: Option<&str>,
+        file_size: Option<i64>,
+        created_at: i64,
+    ) -> SqlxResult<i64> {
+        let local_path = self.relativize_media_path(path);
+        let result = sqlx::query(
+            r#"
+            INSERT INTO message_media (message_id, media_kind, local_path, mime_type, file_size, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+        )
+        .bind(message_id)
+        .bind(media_kind)
+        .bind(local_path)
+        .bind(mime_type)
+        .bind(file_size)
+        .bind(created_at)
+        .execute(self.writer().await?)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn list_message_media(&self, message_id: i64) -> SqlxResult<Vec<MessageMedia>> {
+        sqlx::query_as::<_, MessageMedia>(
+            r#"
+            SELECT id, message_id, media_kind, local_path, mime_type, file_size, created_at
+            FROM message_media WHERE message_id = ?1 ORDER BY id
+            "#,
+        )
+        .bind(message_id)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
     pub async fn get_member_stats(&self, meta_id: i64) -> SqlxResult<Vec<MemberStats>> {
         #[derive(Debug, FromRow)]
         struct StatsRow {
@@ -1068,7 +1768,7 @@ impl Repository {
             session.is_manual,
             session.summary
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
 
         Ok(result.last_insert_rowid())
@@ -1099,14 +1799,14 @@ impl Repository {
             session.is_manual,
             session.summary
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
 
     pub async fn delete_chat_session(&self, id: i64) -> SqlxResult<()> {
         sqlx::query!("DELETE FROM chat_session WHERE id = ?1", id)
-            .execute(&*self.pool)
+            .execute(self.writer().await?)
             .await?;
         Ok(())
     }
@@ -1176,7 +1876,7 @@ impl Repository {
         .bind(importance)
         .bind(now_ts)
         .bind(now_ts)
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
@@ -1231,7 +1931,7 @@ impl Repository {
             history.start_ts,
             history.end_ts
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
 
         Ok(result.last_insert_rowid())
@@ -1276,14 +1976,14 @@ impl Repository {
             end_ts,
             id
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
 
     pub async fn delete_member_name_history(&self, id: i64) -> SqlxResult<()> {
         sqlx::query!("DELETE FROM member_name_history WHERE id = ?1", id)
-            .execute(&*self.pool)
+            .execute(self.writer().await?)
             .await?;
         Ok(())
     }
@@ -1299,7 +1999,7 @@ impl Repository {
             context.session_id,
             context.topic_id
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
@@ -1340,7 +2040,7 @@ impl Repository {
             "DELETE FROM message_context WHERE message_id = ?1",
             message_id
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
@@ -1358,7 +2058,7 @@ impl Repository {
             cache.model,
             cache.created_at
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(result.last_insert_rowid())
     }
@@ -1409,7 +2109,7 @@ impl Repository {
 
     pub async fn delete_embedding_cache(&self, id: i64) -> SqlxResult<()> {
         sqlx::query!("DELETE FROM embedding_cache WHERE id = ?1", id)
-            .execute(&*self.pool)
+            .execute(self.writer().await?)
             .await?;
         Ok(())
     }
@@ -1419,7 +2119,7 @@ impl Repository {
             "DELETE FROM embedding_cache WHERE message_id = ?1",
             message_id
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
@@ -1437,7 +2137,7 @@ impl Repository {
             cache.params,
             cache.created_at
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(result.last_insert_rowid())
     }
@@ -1489,14 +2189,14 @@ impl Repository {
 
     pub async fn delete_analysis_cache(&self, id: i64) -> SqlxResult<()> {
         sqlx::query!("DELETE FROM analysis_cache WHERE id = ?1", id)
-            .execute(&*self.pool)
+            .execute(self.writer().await?)
             .await?;
         Ok(())
     }
 
     pub async fn delete_analysis_cache_by_meta_id(&self, meta_id: i64) -> SqlxResult<()> {
         sqlx::query!("DELETE FROM analysis_cache WHERE meta_id = ?1", meta_id)
-            .execute(&*self.pool)
+            .execute(self.writer().await?)
             .await?;
         Ok(())
     }
@@ -2111,7 +2811,7 @@ impl Repository {
             session.created_at,
             session.updated_at
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(result.last_insert_rowid())
     }
@@ -2156,14 +2856,14 @@ impl Repository {
             updated_at,
             id
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
 
     pub async fn delete_session(&self, id: i64) -> SqlxResult<()> {
         sqlx::query!("DELETE FROM sessions WHERE id = ?1", id)
-            .execute(&*self.pool)
+            .execute(self.writer().await?)
             .await?;
         Ok(())
     }
@@ -2182,7 +2882,7 @@ impl Repository {
             session_message.message_id,
             session_message.order_index
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(result.last_insert_rowid())
     }
@@ -2210,14 +2910,14 @@ impl Repository {
             order_index,
             id
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
 
     pub async fn delete_session_message(&self, id: i64) -> SqlxResult<()> {
         sqlx::query!("DELETE FROM session_messages WHERE id = ?1", id)
-            .execute(&*self.pool)
+            .execute(self.writer().await?)
             .await?;
         Ok(())
     }
@@ -2227,7 +2927,7 @@ impl Repository {
             "DELETE FROM session_messages WHERE session_id = ?1",
             session_id
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
@@ -2247,7 +2947,7 @@ impl Repository {
             progress.completed_at,
             progress.error_message
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(result.last_insert_rowid())
     }
@@ -2278,7 +2978,7 @@ impl Repository {
             processed_messages,
             status
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
@@ -2291,7 +2991,7 @@ impl Repository {
             id,
             completed_at
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
@@ -2304,14 +3004,14 @@ impl Repository {
             id,
             error_message
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
 
     pub async fn delete_import_progress(&self, id: i64) -> SqlxResult<()> {
         sqlx::query!("DELETE FROM import_progress WHERE id = ?1", id)
-            .execute(&*self.pool)
+            .execute(self.writer().await?)
             .await?;
         Ok(())
     }
@@ -2352,7 +3052,8 @@ impl Repository {
                 last_inserted_messages,
                 last_duplicate_messages,
                 status,
-                error_message
+                error_message,
+                source_encoding
             FROM import_source_checkpoint
             WHERE source_kind = ?1 AND source_path = ?2
             LIMIT 1
@@ -2407,9 +3108,10 @@ impl Repository {
                 last_inserted_messages,
                 last_duplicate_messages,
                 status,
-                error_message
+                error_message,
+                source_encoding
             )
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
             ON CONFLICT(source_kind, source_path) DO UPDATE SET
                 fingerprint = excluded.fingerprint,
                 file_size = excluded.file_size,
@@ -2421,7 +3123,8 @@ impl Repository {
                 last_inserted_messages = excluded.last_inserted_messages,
                 last_duplicate_messages = excluded.last_duplicate_messages,
                 status = excluded.status,
-                error_message = excluded.error_message
+                error_message = excluded.error_message,
+                source_encoding = excluded.source_encoding
             "#,
         )
         .bind(&checkpoint.source_kind)
@@ -2437,7 +3140,8 @@ impl Repository {
         .bind(checkpoint.last_duplicate_messages)
         .bind(&checkpoint.status)
         .bind(&checkpoint.error_message)
-        .execute(&*self.pool)
+        .bind(&checkpoint.source_encoding)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
@@ -2462,7 +3166,7 @@ impl Repository {
         .bind(source_path)
         .bind(error_message)
         .bind(processed_at)
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
@@ -2479,7 +3183,7 @@ impl Repository {
             conversation.created_at,
             conversation.updated_at
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(result.last_insert_rowid())
     }
@@ -2507,14 +3211,14 @@ impl Repository {
             conversation.messages,
             conversation.updated_at
         )
-        .execute(&*self.pool)
+        .execute(self.writer().await?)
         .await?;
         Ok(())
     }
 
     pub async fn delete_conversation(&self, id: i64) -> SqlxResult<()> {
         sqlx::query!("DELETE FROM conversations WHERE id = ?1", id)
-            .execute(&*self.pool)
+            .execute(self.writer().await?)
             .await?;
         Ok(())
     }
@@ -3083,4 +3787,99 @@ impl Repository {
 
         Ok(rows.into_iter().map(|r| r.year).collect())
     }
+
+    /// Rebuilds FTS indexes, refreshes planner statistics and optionally
+    /// truncates the WAL. Disabled steps are omitted from the result.
+    pub async fn run_maintenance(
+        &self,
+        options: &MaintenanceOptions,
+    ) -> SqlxResult<Vec<MaintenanceStep>> {
+        let mut steps = Vec::new();
+
+        if options.rebuild_fts {
+            let started = std::time::Instant::now();
+            let tables: Vec<String> = sqlx::query_scalar(
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND sql LIKE '%USING fts%' ORDER BY name",
+            )
+            .fetch_all(&*self.pool)
+            .await?;
+            for table in &tables {
+                let quoted = table.replace('"', "\"\"");
+                let sql = format!("INSERT INTO \"{quoted}\"(\"{quoted}\") VALUES('rebuild')");
+                sqlx::query(&sql).execute(&*self.pool).await?;
+            }
+            steps.push(MaintenanceStep {
+                name: "rebuild_fts".to_string(),
+                duration_ms: started.elapsed().as_millis() as u64,
+                detail: Some(if tables.is_empty() {
+                    "no fts tables".to_string()
+                } else {
+                    tables.join(",")
+                }),
+            });
+        }
+
+        if options.analyze {
+            let started = std::time::Instant::now();
+            sqlx::query("ANALYZE").execute(&*self.pool).await?;
+            steps.push(MaintenanceStep {
+                name: "analyze".to_string(),
+                duration_ms: started.elapsed().as_millis() as u64,
+                detail: None,
+            });
+        }
+
+        if options.wal_checkpoint {
+            let started = std::time::Instant::now();
+            let (busy, log_frames, checkpointed): (i64, i64, i64) =
+                sqlx::query_as("PRAGMA wal_checkpoint(TRUNCATE)")
+                    .fetch_one(&*self.pool)
+                    .await?;
+            steps.push(MaintenanceStep {
+                name: "wal_checkpoint".to_string(),
+                duration_ms: started.elapsed().as_millis() as u64,
+                detail: Some(format!(
+                    "busy={busy} log={log_frames} checkpointed={checkpointed}"
+                )),
+            });
+        }
+
+        Ok(steps)
+    }
+}
+
+/// Append the `MessageExportFilter` conditions to a query over `message msg`.
+fn push_export_filter(
+    builder: &mut sqlx::QueryBuilder<'_, sqlx::Sqlite>,
+    filter: &MessageExportFilter,
+) {
+    if let Some(meta_id) = filter.meta_id {
+        builder.push(" AND msg.meta_id = ").push_bind(meta_id);
+    }
+    if let Some(start) = filter.start_ts {
+        builder.push(" AND msg.ts >= ").push_bind(start);
+    }
+    if let Some(end) = filter.end_ts {
+        builder.push(" AND msg.ts < ").push_bind(end);
+    }
+    if let Some(member_id) = filter.member_id {
+        builder.push(" AND msg.sender_id = ").push_bind(member_id);
+    }
+    match (filter.since_id, filter.since_ts) {
+        (Some(id), Some(ts)) => {
+            builder
+                .push(" AND (msg.id > ")
+                .push_bind(id)
+                .push(" OR msg.ts > ")
+                .push_bind(ts)
+                .push(")");
+        }
+        (Some(id), None) => {
+            builder.push(" AND msg.id > ").push_bind(id);
+        }
+        (None, Some(ts)) => {
+            builder.push(" AND msg.ts > ").push_bind(ts);
+        }
+        (None, None) => {}
+    }
 }
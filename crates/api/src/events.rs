@@ -12,8 +12,21 @@ use axum::{
 use futures::stream;
 use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
-use std::time::Duration;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 use tracing::instrument;
+use xenobot_core::webhook::WebhookMessageCreatedEvent;
+
+/// Event name used for import progress on SSE and WebSocket channels.
+pub const IMPORT_PROGRESS_EVENT: &str = "import.progress";
+
+/// Minimum spacing between throttled import progress events (~2 per second).
+pub const IMPORT_PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+const IMPORT_PROGRESS_CAPACITY: usize = 64;
+const MESSAGE_CREATED_CAPACITY: usize = 256;
+const MONITOR_FILE_PROCESSED_CAPACITY: usize = 64;
 
 /// Events API router.
 pub fn router() -> Router {
@@ -28,16 +41,157 @@ pub fn router() -> Router {
 
 // Request/Response types
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ImportProgressEvent {
     pub total: u64,
     pub processed: u64,
+    #[serde(default)]
+    pub inserted: u64,
+    #[serde(default)]
+    pub duplicates: u64,
     pub current_file: Option<String>,
     pub status: String, // "parsing", "importing", "complete", "error"
     pub error: Option<String>,
 }
 
+fn import_progress_bus() -> &'static broadcast::Sender<ImportProgressEvent> {
+    static BUS: OnceLock<broadcast::Sender<ImportProgressEvent>> = OnceLock::new();
+    BUS.get_or_init(|| broadcast::channel(IMPORT_PROGRESS_CAPACITY).0)
+}
+
+/// Subscribe to import progress events published by running imports.
+pub fn subscribe_import_progress() -> broadcast::Receiver<ImportProgressEvent> {
+    import_progress_bus().subscribe()
+}
+
+/// Publish an import progress event; dropped when nobody is listening.
+pub fn publish_import_progress(event: ImportProgressEvent) {
+    let _ = import_progress_bus().send(event);
+}
+
+fn message_created_bus() -> &'static broadcast::Sender<WebhookMessageCreatedEvent> {
+    static BUS: OnceLock<broadcast::Sender<WebhookMessageCreatedEvent>> = OnceLock::new();
+    BUS.get_or_init(|| broadcast::channel(MESSAGE_CREATED_CAPACITY).0)
+}
+
+/// Subscribe to `message.created` events for messages written to the database.
+pub fn subscribe_message_created() -> broadcast::Receiver<WebhookMessageCreatedEvent> {
+    message_created_bus().subscribe()
+}
+
+/// Whether anyone is listening for `message.created`, so writers can skip
+/// building events nobody receives.
+pub fn message_created_subscribed() -> bool {
+    message_created_bus().receiver_count() > 0
+}
+
+/// Publish a `message.created` event; dropped when nobody is listening.
+pub fn publish_message_created(event: &WebhookMessageCreatedEvent) {
+    if message_created_subscribed() {
+        let _ = message_created_bus().send(event.clone());
+    }
+}
+
+/// Payload of a `monitor.file_processed` event.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorFileProcessedEvent {
+    /// Path of the processed export file.
+    pub path: String,
+    /// Detected platform, when parsing succeeded.
+    pub platform: Option<String>,
+    /// Chat the messages were written to.
+    pub chat_name: Option<String>,
+    /// Messages inserted from this file.
+    pub inserted: u64,
+    /// Messages skipped as duplicates.
+    pub duplicates: u64,
+    /// "completed" or "failed".
+    pub status: String,
+    /// Failure reason when `status` is "failed".
+    pub error: Option<String>,
+}
+
+fn monitor_file_processed_bus() -> &'static broadcast::Sender<MonitorFileProcessedEvent> {
+    static BUS: OnceLock<broadcast::Sender<MonitorFileProcessedEvent>> = OnceLock::new();
+    BUS.get_or_init(|| broadcast::channel(MONITOR_FILE_PROCESSED_CAPACITY).0)
+}
+
+/// Subscribe to `monitor.file_processed` events published by the file monitor.
+pub fn subscribe_monitor_file_processed() -> broadcast::Receiver<MonitorFileProcessedEvent> {
+    monitor_file_processed_bus().subscribe()
+}
+
+/// Publish a `monitor.file_processed` event; dropped when nobody is listening.
+pub fn publish_monitor_file_processed(event: MonitorFileProcessedEvent) {
+    let _ = monitor_file_processed_bus().send(event);
+}
+
+/// Tracks one import run and publishes throttled `import.progress` events.
+///
+/// `report` is cheap enough to call per message: it only clones and sends
+/// when a subscriber exists and the interval has elapsed.
+#[derive(Debug)]
+pub struct ImportProgressReporter {
+    event: ImportProgressEvent,
+    last_emit: Option<Instant>,
+}
+
+impl ImportProgressReporter {
+    pub fn new(current_file: Option<String>, total: u64) -> Self {
+        Self {
+            event: ImportProgressEvent {
+                total,
+                processed: 0,
+                inserted: 0,
+                duplicates: 0,
+                current_file,
+                status: "importing".to_string(),
+                error: None,
+            },
+            last_emit: None,
+        }
+    }
+
+    /// Switch to the next file of a multi-file import, publishing immediately.
+    pub fn start_file(&mut self, current_file: Option<String>) {
+        self.event.current_file = current_file;
+        self.publish();
+    }
+
+    /// Record running counts, publishing at most once per [`IMPORT_PROGRESS_INTERVAL`].
+    pub fn report(&mut self, processed: u64, inserted: u64, duplicates: u64) {
+        self.event.processed = processed;
+        self.event.inserted = inserted;
+        self.event.duplicates = duplicates;
+        if import_progress_bus().receiver_count() == 0 {
+            return;
+        }
+        let now = Instant::now();
+        if self
+            .last_emit
+            .is_some_and(|last| now.duration_since(last) < IMPORT_PROGRESS_INTERVAL)
+        {
+            return;
+        }
+        self.last_emit = Some(now);
+        publish_import_progress(self.event.clone());
+    }
+
+    /// Publish the final state ("complete" or "error") regardless of throttling.
+    pub fn finish(&mut self, status: &str, error: Option<String>) {
+        self.event.status = status.to_string();
+        self.event.error = error;
+        self.publish();
+    }
+
+    fn publish(&mut self) {
+        self.last_emit = Some(Instant::now());
+        publish_import_progress(self.event.clone());
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportProgressEvent {
@@ -87,8 +241,22 @@ pub struct MergeParseProgressEvent {
 #[axum::debug_handler]
 #[instrument]
 pub async fn import_progress_sse() -> Sse<impl stream::Stream<Item = Result<Event, Infallible>>> {
-    // Placeholder: return empty stream that never emits
-    let stream = stream::empty::<Result<Event, Infallible>>();
+    let stream = stream::unfold(subscribe_import_progress(), |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(progress) => {
+                    let event = Event::default()
+                        .event(IMPORT_PROGRESS_EVENT)
+                        .json_data(&progress)
+                        .unwrap_or_else(|_| Event::default().event(IMPORT_PROGRESS_EVENT));
+                    return Some((Ok(event), rx));
+                }
+                // A slow client just misses intermediate counts.
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
     Sse::new(stream).keep_alive(
         axum::response::sse::KeepAlive::new()
             .interval(Duration::from_secs(15))
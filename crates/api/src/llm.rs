@@ -18,9 +18,15 @@ use std::{
     time::{SystemTime, UNIX_EPOCH},
 };
 use tracing::{instrument, warn};
+use utoipa::{OpenApi, ToSchema};
 
 use crate::{secrets, ApiError};
 
+/// OpenAPI description of the documented LLM routes.
+#[derive(OpenApi)]
+#[openapi(paths(get_providers, has_config, chat))]
+pub(crate) struct LlmApiDoc;
+
 /// LLM API router.
 pub fn router() -> Router {
     Router::new()
@@ -39,7 +45,7 @@ pub fn router() -> Router {
 
 // ==================== Request/Response Types ====================
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct ProviderModel {
     id: String,
@@ -47,7 +53,7 @@ struct ProviderModel {
     description: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct LLMProvider {
     id: String,
@@ -90,14 +96,14 @@ struct AIServiceConfig {
     updated_at: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct ChatMessage {
     role: String,
     content: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct ChatOptions {
     temperature: Option<f32>,
@@ -120,7 +126,7 @@ struct ConfigOperationResponse {
     error: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct ChatResponse {
     success: bool,
@@ -611,7 +617,8 @@ async fn try_openai_compatible_chat_completion(
     let payload = build_openai_chat_payload(&model, messages, options, config);
     let timeout_ms = llm_request_timeout_ms();
 
-    let client = reqwest::Client::builder()
+    let client = xenobot_core::http_client::outbound_client_builder()
+        .map_err(|err| ApiError::Http(format!("failed to build llm client: {err}")))?
         .timeout(Duration::from_millis(timeout_ms))
         .build()
         .map_err(|err| ApiError::Http(format!("failed to build llm client: {err}")))?;
@@ -671,7 +678,8 @@ async fn try_gemini_chat_completion(
     let payload = build_gemini_payload(messages, options, config);
     let timeout_ms = llm_request_timeout_ms();
 
-    let client = reqwest::Client::builder()
+    let client = xenobot_core::http_client::outbound_client_builder()
+        .map_err(|err| ApiError::Http(format!("failed to build llm client: {err}")))?
         .timeout(Duration::from_millis(timeout_ms))
         .build()
         .map_err(|err| ApiError::Http(format!("failed to build llm client: {err}")))?;
@@ -750,6 +758,12 @@ fn build_stub_answer(messages: &[ChatMessage], active: Option<&AIServiceConfig>)
 
 // ==================== Handler Implementations ====================
 
+#[utoipa::path(
+    get,
+    path = "/llm/providers",
+    tag = "llm",
+    responses((status = 200, description = "Supported LLM providers and models", body = [LLMProvider]))
+)]
 #[instrument]
 async fn get_providers() -> Result<Json<Vec<LLMProvider>>, ApiError> {
     Ok(Json(provider_catalog()))
@@ -1066,6 +1080,12 @@ async fn validate_api_key(
     })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/llm/has-config",
+    tag = "llm",
+    responses((status = 200, description = "Whether an LLM config is available", body = bool))
+)]
 #[instrument]
 async fn has_config() -> Result<Json<bool>, ApiError> {
     let state = read_store()?;
@@ -1077,13 +1097,20 @@ async fn has_config() -> Result<Json<bool>, ApiError> {
     Ok(Json(has))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct ChatRequest {
     messages: Vec<ChatMessage>,
     options: Option<ChatOptions>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/llm/chat",
+    tag = "llm",
+    request_body = ChatRequest,
+    responses((status = 200, description = "Completion from the active LLM config", body = ChatResponse))
+)]
 #[instrument]
 async fn chat(Json(req): Json<ChatRequest>) -> Result<Json<ChatResponse>, ApiError> {
     let state = read_store()?;
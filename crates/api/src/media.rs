@@ -107,6 +107,17 @@ async fn stream_message_media(
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
+    let repo = crate::database::Repository::new(pool.clone());
+    let attachments = repo
+        .list_message_media(message_id)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
+    if let Some(attachment) = attachments.first() {
+        let resolved = repo.resolve_media_path(&attachment.local_path);
+        let safe_path = resolve_allowed_path(resolved.to_string_lossy().as_ref())?;
+        return media_response_from_path(safe_path, req.download.unwrap_or(false)).await;
+    }
+
     let row = sqlx::query("SELECT content FROM message WHERE id = ?1")
         .bind(message_id)
         .fetch_optional(pool.as_ref())
@@ -327,6 +338,7 @@ fn ensure_path_allowed(path: &PathBuf) -> Result<(), ApiError> {
     if let Some(data_dir) = dirs::data_dir() {
         allowed_roots.push(data_dir.join("xenobot"));
     }
+    allowed_roots.push(crate::database::get_media_root());
     if let Some(home) = dirs::home_dir() {
         allowed_roots.push(
             home.join("Library")
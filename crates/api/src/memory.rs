@@ -394,6 +394,7 @@ mod tests {
                     content: Some((*content).to_string()),
                     reply_to_message_id: None,
                     platform_message_id: None,
+                    edited_at: None,
                     meta_id,
                 })
                 .await?;
@@ -1041,6 +1041,7 @@ async fn merge_into_database(
             content: msg.content.clone(),
             reply_to_message_id: None,
             platform_message_id: None,
+            edited_at: None,
             meta_id,
         })
         .await
@@ -1,19 +1,16 @@
 //! Axum router configuration for Xenobot HTTP API.
 
 use crate::config::ApiConfig;
+use axum::http::{HeaderName, HeaderValue, Method};
 use axum::{Json, Router};
 use tower_http::cors::CorsLayer;
+use utoipa::OpenApi;
 
 /// Build the main API router with all enabled modules.
 pub fn build_router(config: &ApiConfig) -> Router {
     let mut router = Router::new();
     let status_payload = build_status_payload(config);
 
-    // Add CORS layer if enabled
-    if config.enable_cors {
-        router = router.layer(create_cors_layer(config));
-    }
-
     // Add API modules based on feature flags
     if config.features.enable_chat {
         router = router.nest("/chat", crate::chat::router());
@@ -70,6 +67,7 @@ pub fn build_router(config: &ApiConfig) -> Router {
     // Add service index and health check endpoints
     router = router.route("/", axum::routing::get(api_index));
     router = router.route("/health", axum::routing::get(health_check));
+    router = router.route("/openapi.json", axum::routing::get(openapi_json));
     router = router.route(
         "/status",
         axum::routing::get({
@@ -81,6 +79,12 @@ pub fn build_router(config: &ApiConfig) -> Router {
         }),
     );
 
+    // CORS must wrap the fully assembled router so preflight requests are
+    // answered for every nested (and parameterized) route.
+    if config.enable_cors {
+        router = router.layer(create_cors_layer(config));
+    }
+
     router
 }
 
@@ -91,6 +95,7 @@ async fn api_index() -> Json<serde_json::Value> {
         "status": "running",
         "health": "/health",
         "statusEndpoint": "/status",
+        "openapi": "/openapi.json",
         "endpoints": [
             "/chat",
             "/media",
@@ -111,10 +116,40 @@ async fn api_index() -> Json<serde_json::Value> {
 }
 
 /// Health check endpoint.
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses((status = 200, description = "Service is up", body = String, content_type = "text/plain"))
+)]
 async fn health_check() -> &'static str {
     "OK"
 }
 
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Xenobot API",
+        description = "HTTP API mirroring Xenobot's IPC surface."
+    ),
+    paths(health_check)
+)]
+struct ApiDoc;
+
+/// OpenAPI 3 document for the health, chat, search, import, and LLM routes.
+pub fn openapi_document() -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+    doc.merge(crate::chat::ChatApiDoc::openapi());
+    doc.merge(crate::ai::SearchApiDoc::openapi());
+    doc.merge(crate::llm::LlmApiDoc::openapi());
+    doc
+}
+
+/// Serves the generated OpenAPI document.
+async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(openapi_document())
+}
+
 fn build_status_payload(config: &ApiConfig) -> serde_json::Value {
     serde_json::json!({
         "service": "xenobot-api",
@@ -153,31 +188,46 @@ fn build_status_payload(config: &ApiConfig) -> serde_json::Value {
 fn create_cors_layer(config: &ApiConfig) -> CorsLayer {
     let mut cors = tower_http::cors::CorsLayer::new();
 
-    if config.cors_allowed_origins.is_empty() {
+    // Credentials are only valid with an explicit origin list; browsers reject
+    // `Access-Control-Allow-Origin: *` together with credentials.
+    let wildcard_origin = config.cors_allowed_origins.is_empty();
+    if wildcard_origin {
         cors = cors.allow_origin(tower_http::cors::Any);
     } else {
-        let origins: Vec<_> = config
+        // Malformed entries are dropped, never widened to `Any`: a list with
+        // no valid origin allows none.
+        let origins: Vec<HeaderValue> = config
             .cors_allowed_origins
             .iter()
-            .filter_map(|origin| origin.parse().ok())
+            .filter_map(|origin| match origin.parse() {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    tracing::warn!("ignoring malformed CORS origin {:?}", origin);
+                    None
+                }
+            })
             .collect();
-
         cors = cors.allow_origin(origins);
     }
 
-    cors.allow_methods([
-        axum::http::Method::GET,
-        axum::http::Method::POST,
-        axum::http::Method::PUT,
-        axum::http::Method::DELETE,
-        axum::http::Method::OPTIONS,
-    ])
-    .allow_headers([
-        axum::http::header::CONTENT_TYPE,
-        axum::http::header::AUTHORIZATION,
-        axum::http::header::ACCEPT,
-    ])
-    .allow_credentials(config.enable_cors)
+    let mut methods: Vec<Method> = config
+        .cors_allowed_methods
+        .iter()
+        .filter_map(|method| method.trim().to_ascii_uppercase().parse().ok())
+        .collect();
+    if !methods.contains(&Method::OPTIONS) {
+        methods.push(Method::OPTIONS);
+    }
+
+    let headers: Vec<HeaderName> = config
+        .cors_allowed_headers
+        .iter()
+        .filter_map(|header| header.trim().parse().ok())
+        .collect();
+
+    cors.allow_methods(methods)
+        .allow_headers(headers)
+        .allow_credentials(config.cors_allow_credentials && !wildcard_origin)
 }
 
 /// API route path builder.
@@ -249,6 +299,41 @@ mod tests {
         assert_eq!(std::str::from_utf8(&bytes).expect("utf8"), "OK");
     }
 
+    #[tokio::test]
+    async fn openapi_route_lists_documented_paths() {
+        let app = build_router(&ApiConfig::default());
+        let request = Request::builder()
+            .method(Method::GET)
+            .uri("/openapi.json")
+            .body(Body::empty())
+            .expect("build request");
+        let response = app.oneshot(request).await.expect("route response");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let json: serde_json::Value = serde_json::from_slice(&bytes).expect("json body");
+        assert!(json["openapi"]
+            .as_str()
+            .is_some_and(|version| version.starts_with("3.")));
+        let paths = json["paths"].as_object().expect("paths object");
+        for path in [
+            "/health",
+            "/chat/import",
+            "/chat/sessions",
+            "/chat/export",
+            "/ai/search-messages",
+            "/ai/semantic-search-messages",
+            "/llm/providers",
+            "/llm/chat",
+        ] {
+            assert!(paths.contains_key(path), "missing {path}");
+        }
+        assert!(paths["/chat/import"]["post"]["requestBody"].is_object());
+        assert!(json["components"]["schemas"]["AnalysisSession"].is_object());
+    }
+
     #[tokio::test]
     async fn status_route_returns_runtime_payload_and_feature_flags() {
         let app = build_router(&ApiConfig::default());
@@ -275,4 +360,94 @@ mod tests {
         assert_eq!(json["features"]["session"], true);
         assert_eq!(json["runtime"]["arch"], std::env::consts::ARCH);
     }
+
+    #[tokio::test]
+    async fn cors_preflight_is_answered_for_parameterized_routes() {
+        let app = build_router(&ApiConfig::default());
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/chat/sessions/42")
+            .header("origin", "http://localhost:5173")
+            .header("access-control-request-method", "DELETE")
+            .header("access-control-request-headers", "content-type")
+            .body(Body::empty())
+            .expect("build request");
+        let response = app.oneshot(request).await.expect("route response");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let headers = response.headers();
+        assert_eq!(
+            headers
+                .get("access-control-allow-origin")
+                .and_then(|v| v.to_str().ok()),
+            Some("http://localhost:5173")
+        );
+        let allow_methods = headers
+            .get("access-control-allow-methods")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        assert!(allow_methods.contains("DELETE"));
+        assert!(allow_methods.contains("OPTIONS"));
+        let allow_headers = headers
+            .get("access-control-allow-headers")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        assert!(allow_headers.contains("content-type"));
+        assert_eq!(
+            headers
+                .get("access-control-allow-credentials")
+                .and_then(|v| v.to_str().ok()),
+            Some("true")
+        );
+    }
+
+    #[tokio::test]
+    async fn cors_wildcard_origin_never_allows_credentials() {
+        let config = ApiConfig {
+            cors_allowed_origins: Vec::new(),
+            ..ApiConfig::default()
+        };
+        let app = build_router(&config);
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/health")
+            .header("origin", "http://example.test")
+            .header("access-control-request-method", "GET")
+            .body(Body::empty())
+            .expect("build request");
+        let response = app.oneshot(request).await.expect("route response");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .headers()
+                .get("access-control-allow-origin")
+                .and_then(|v| v.to_str().ok()),
+            Some("*")
+        );
+        assert!(response
+            .headers()
+            .get("access-control-allow-credentials")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn cors_malformed_origins_are_not_widened_to_wildcard() {
+        let config = ApiConfig {
+            cors_allowed_origins: vec!["http://bad\norigin".to_string()],
+            ..ApiConfig::default()
+        };
+        let app = build_router(&config);
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/health")
+            .header("origin", "http://example.test")
+            .header("access-control-request-method", "GET")
+            .body(Body::empty())
+            .expect("build request");
+        let response = app.oneshot(request).await.expect("route response");
+        assert!(response
+            .headers()
+            .get("access-control-allow-origin")
+            .is_none());
+    }
 }
@@ -23,6 +23,18 @@ impl ApiServer {
 
     /// Run the server until shutdown signal.
     pub async fn run(self) -> Result<(), ApiError> {
+        self.run_with_bound_hook(|_| {}).await
+    }
+
+    /// Run the server until shutdown signal, calling `on_bound` with the
+    /// actual TCP address once the listener is bound.
+    ///
+    /// With port `0` the OS picks a free port; this is the only way to learn
+    /// which one. The hook is not called for unix socket transport.
+    pub async fn run_with_bound_hook<F>(self, on_bound: F) -> Result<(), ApiError>
+    where
+        F: FnOnce(SocketAddr) + Send,
+    {
         let addr = self.config.bind_addr;
         let unix_socket_path = self.config.unix_socket_path.clone();
         let unix_socket_mode = self.config.unix_socket_mode;
@@ -111,14 +123,10 @@ On macOS sandbox, use a short writable path such as $TMPDIR/xenobot.sock or run
                 ));
             }
         } else {
-            let listener = tokio::net::TcpListener::bind(addr).await.map_err(|e| {
-                ApiError::Io(std::io::Error::new(
-                    std::io::ErrorKind::AddrNotAvailable,
-                    format!("Failed to bind to {}: {}", addr, e),
-                ))
-            })?;
+            let (listener, local_addr) = bind_tcp_listener(addr).await?;
 
-            info!("Server listening on {}", addr);
+            info!("Server listening on {}", local_addr);
+            on_bound(local_addr);
 
             axum::serve(listener, router)
                 .with_graceful_shutdown(shutdown)
@@ -175,6 +183,21 @@ On macOS sandbox, use a short writable path such as $TMPDIR/xenobot.sock or run
     }
 }
 
+/// Binds a TCP listener and returns it with the address actually bound,
+/// which differs from `addr` when `addr` uses port `0`.
+pub async fn bind_tcp_listener(
+    addr: SocketAddr,
+) -> Result<(tokio::net::TcpListener, SocketAddr), ApiError> {
+    let listener = tokio::net::TcpListener::bind(addr).await.map_err(|e| {
+        ApiError::Io(std::io::Error::new(
+            std::io::ErrorKind::AddrNotAvailable,
+            format!("Failed to bind to {}: {}", addr, e),
+        ))
+    })?;
+    let local_addr = listener.local_addr().map_err(ApiError::Io)?;
+    Ok((listener, local_addr))
+}
+
 #[cfg(unix)]
 async fn serve_unix_listener(
     listener: tokio::net::UnixListener,
@@ -307,3 +330,26 @@ pub async fn start_server(config: ApiConfig) -> Result<(), ApiError> {
     let server = ApiServer::new(config);
     server.run().await
 }
+
+/// Like [`start_server`], reporting the bound TCP address through `on_bound`.
+pub async fn start_server_with_bound_hook<F>(config: ApiConfig, on_bound: F) -> Result<(), ApiError>
+where
+    F: FnOnce(SocketAddr) + Send,
+{
+    let server = ApiServer::new(config);
+    server.run_with_bound_hook(on_bound).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn bind_tcp_listener_reports_ephemeral_port() {
+        let requested: SocketAddr = "127.0.0.1:0".parse().expect("socket addr");
+        let (listener, local_addr) = bind_tcp_listener(requested).await.expect("bind");
+        assert_ne!(local_addr.port(), 0);
+        assert_eq!(local_addr.ip(), requested.ip());
+        assert_eq!(listener.local_addr().expect("local addr"), local_addr);
+    }
+}
@@ -2,12 +2,13 @@
 
 use crate::config::{ApiConfig, WebhookReplayConfig};
 use futures::stream::{self, StreamExt};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 use xenobot_core::webhook::{
-    now_unix_ts, overwrite_dead_letter_entries, read_dead_letter_entries, WebhookDeadLetterEntry,
+    now_unix_ts, overwrite_dead_letter_entries, read_dead_letter_entries, send_webhook_event,
+    WebhookDeadLetterEntry,
 };
 
 /// Spawn a background task that replays webhook dead-letter entries on an interval.
@@ -21,12 +22,15 @@ pub fn spawn_webhook_dead_letter_replayer(config: &ApiConfig) -> Option<JoinHand
 
     let replay_config = config.webhook_replay.clone();
     Some(tokio::spawn(async move {
-        let client = match reqwest::Client::builder()
-            .timeout(Duration::from_secs(
-                replay_config.request_timeout_seconds.max(1),
-            ))
-            .build()
-        {
+        let built = xenobot_core::http_client::outbound_client_builder().and_then(|builder| {
+            builder
+                .timeout(Duration::from_secs(
+                    replay_config.request_timeout_seconds.max(1),
+                ))
+                .build()
+                .map_err(|e| xenobot_core::Error::Network(e.to_string()))
+        });
+        let client = match built {
             Ok(client) => client,
             Err(err) => {
                 warn!(
@@ -91,9 +95,13 @@ async fn replay_dead_letters_once(
         return Ok(());
     }
 
+    // Entries do not store secrets; sign with the webhook's current one.
+    let secrets = load_webhook_secrets().await?;
+    let secrets = &secrets;
     let max_concurrency = config.max_concurrency.max(1);
     let delivery_results = stream::iter(selected.into_iter().map(|entry| async move {
-        let result = deliver_dead_letter_entry(client, &entry).await;
+        let secret = secrets.get(&entry.webhook_id).map(String::as_str);
+        let result = deliver_dead_letter_entry(client, &entry, secret).await;
         (entry, result)
     }))
     .buffer_unordered(max_concurrency)
@@ -147,15 +155,17 @@ async fn replay_dead_letters_once(
 async fn deliver_dead_letter_entry(
     client: &reqwest::Client,
     entry: &WebhookDeadLetterEntry,
+    secret: Option<&str>,
 ) -> Result<(), String> {
-    let response = client
-        .post(&entry.webhook_url)
-        .header("X-Xenobot-Event", &entry.event.event_type)
-        .header("X-Xenobot-Webhook-Id", &entry.webhook_id)
-        .json(&entry.event)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+    let response = send_webhook_event(
+        client,
+        &entry.webhook_url,
+        &entry.webhook_id,
+        &entry.event,
+        secret,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
 
     if response.status().is_success() {
         return Ok(());
@@ -171,6 +181,12 @@ async fn load_dead_letter_entries() -> Result<Vec<WebhookDeadLetterEntry>, Strin
         .map_err(|e| format!("read dead-letter entries failed: {}", e))
 }
 
+async fn load_webhook_secrets() -> Result<HashMap<String, String>, String> {
+    tokio::task::spawn_blocking(crate::chat::read_api_webhook_secrets)
+        .await
+        .map_err(|e| format!("join webhook config read task failed: {}", e))
+}
+
 async fn persist_dead_letter_entries(entries: Vec<WebhookDeadLetterEntry>) -> Result<(), String> {
     tokio::task::spawn_blocking(move || overwrite_dead_letter_entries(&entries))
         .await
@@ -124,6 +124,7 @@ async fn test_advanced_analysis_endpoints_return_expected_shapes(
             content: Some(content.to_string()),
             reply_to_message_id: None,
             platform_message_id: None,
+            edited_at: None,
             meta_id,
         })
         .await?;
@@ -273,6 +273,7 @@ async fn test_agent_run_stream_executes_real_tools() -> Result<(), Box<dyn std::
             content: Some("Data migration checkpoint completed".to_string()),
             reply_to_message_id: None,
             platform_message_id: None,
+            edited_at: None,
             meta_id,
         })
         .await?;
@@ -287,6 +288,7 @@ async fn test_agent_run_stream_executes_real_tools() -> Result<(), Box<dyn std::
             content: Some("Incremental import semantic query passed".to_string()),
             reply_to_message_id: None,
             platform_message_id: None,
+            edited_at: None,
             meta_id,
         })
         .await?;
@@ -418,6 +420,7 @@ async fn test_agent_run_stream_accepts_snake_case_and_forced_tools(
         content: Some("semantic checkpoint signal".to_string()),
         reply_to_message_id: None,
         platform_message_id: None,
+        edited_at: None,
         meta_id,
     })
     .await?;
@@ -519,6 +522,7 @@ async fn test_agent_run_stream_resolves_owner_platform_id_for_member_tools(
         content: Some("owner context seed".to_string()),
         reply_to_message_id: None,
         platform_message_id: None,
+        edited_at: None,
         meta_id,
     })
     .await?;
@@ -646,6 +650,7 @@ async fn test_agent_run_stream_normalizes_forced_tool_aliases_to_canonical_names
             content: Some("semantic alias test message".to_string()),
             reply_to_message_id: None,
             platform_message_id: None,
+            edited_at: None,
             meta_id,
         })
         .await?;
@@ -773,6 +778,7 @@ async fn test_agent_forced_single_tool_parameter_contract_matrix(
             content: Some("matrix semantic content".to_string()),
             reply_to_message_id: None,
             platform_message_id: None,
+            edited_at: None,
             meta_id,
         })
         .await?;
@@ -786,6 +792,7 @@ async fn test_agent_forced_single_tool_parameter_contract_matrix(
         content: Some("matrix second line".to_string()),
         reply_to_message_id: None,
         platform_message_id: None,
+        edited_at: None,
         meta_id,
     })
     .await?;
@@ -961,6 +968,7 @@ async fn test_agent_run_stream_normalizes_calling_layer_alias_matrix(
             content: Some("alias matrix seed content".to_string()),
             reply_to_message_id: None,
             platform_message_id: None,
+            edited_at: None,
             meta_id,
         })
         .await?;
@@ -974,6 +982,7 @@ async fn test_agent_run_stream_normalizes_calling_layer_alias_matrix(
         content: Some("alias matrix secondary content".to_string()),
         reply_to_message_id: None,
         platform_message_id: None,
+        edited_at: None,
         meta_id,
     })
     .await?;
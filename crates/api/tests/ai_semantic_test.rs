@@ -113,6 +113,7 @@ async fn test_semantic_search_endpoint_returns_ranked_messages_and_rewrite(
             content: Some((*content).to_string()),
             reply_to_message_id: None,
             platform_message_id: None,
+            edited_at: None,
             meta_id,
         })
         .await?;
@@ -171,3 +172,127 @@ async fn test_semantic_search_endpoint_returns_ranked_messages_and_rewrite(
     let _ = fs::remove_dir_all(&test_root);
     Ok(())
 }
+
+#[tokio::test]
+async fn test_hybrid_search_returns_exact_term_and_paraphrase_hits(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let _guard = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+    let workspace_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .canonicalize()?;
+    let _cwd_guard = WorkingDirGuard::change_to(&workspace_root)?;
+
+    let test_root = unique_test_root();
+    fs::create_dir_all(&test_root)?;
+    let mut db_config = DatabaseConfig::default();
+    db_config.sqlite_path = test_root.join("xenobot_api_hybrid.db");
+    xenobot_api::database::init_database_with_config(&db_config).await?;
+
+    let pool = xenobot_api::database::get_pool().await?;
+    let repo = Repository::new(pool.clone());
+    let meta_id = repo
+        .create_chat(&ChatMeta {
+            id: 0,
+            name: "Hybrid Session".to_string(),
+            platform: "telegram".to_string(),
+            chat_type: "group".to_string(),
+            imported_at: 1_700_000_000,
+            group_id: None,
+            group_avatar: None,
+            owner_id: None,
+            schema_version: 3,
+            session_gap_threshold: 1800,
+        })
+        .await?;
+    let sender_id = repo.get_or_create_member("u_alice", Some("Alice")).await?;
+
+    let rows = [
+        "deploy the release today",
+        "shipping the new release to customers",
+        "lunch plans for friday",
+    ];
+    for (idx, content) in rows.iter().enumerate() {
+        repo.create_message(&Message {
+            id: 0,
+            sender_id,
+            sender_account_name: Some("Alice".to_string()),
+            sender_group_nickname: None,
+            ts: 1_700_000_100 + idx as i64,
+            msg_type: 0,
+            content: Some((*content).to_string()),
+            reply_to_message_id: None,
+            platform_message_id: None,
+            edited_at: None,
+            meta_id,
+        })
+        .await?;
+    }
+
+    let app = ai::router();
+    let (status, resp) = post_json(
+        &app,
+        "/hybrid-search",
+        serde_json::json!({
+            "sessionId": meta_id.to_string(),
+            "query": "deploy",
+            "limit": 10
+        }),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK, "unexpected response: {resp}");
+    let messages = resp["messages"].as_array().expect("messages array");
+    let contents: Vec<&str> = messages
+        .iter()
+        .map(|m| m["content"].as_str().unwrap_or_default())
+        .collect();
+    assert_eq!(contents[0], "deploy the release today");
+    assert_eq!(messages[0]["lexicalScore"].as_f64(), Some(1.0));
+
+    let (status, resp) = post_json(
+        &app,
+        "/hybrid-search",
+        serde_json::json!({
+            "sessionId": meta_id.to_string(),
+            "query": "deploy release",
+            "lexicalWeight": 1.0,
+            "semanticWeight": 1.0,
+            "limit": 10
+        }),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK, "unexpected response: {resp}");
+    let messages = resp["messages"].as_array().expect("messages array");
+    let exact = messages
+        .iter()
+        .find(|m| m["content"] == "deploy the release today")
+        .expect("exact-term hit");
+    let paraphrase = messages
+        .iter()
+        .find(|m| m["content"] == "shipping the new release to customers")
+        .expect("paraphrase hit");
+    assert_eq!(exact["lexicalScore"].as_f64(), Some(1.0));
+    assert!(exact["semanticScore"].as_f64().is_some());
+    assert_eq!(paraphrase["lexicalScore"].as_f64(), Some(0.5));
+    assert!(paraphrase["semanticScore"].as_f64().unwrap_or(0.0) >= 0.2);
+    assert!(exact["score"].as_f64() > paraphrase["score"].as_f64());
+    assert!(!messages
+        .iter()
+        .any(|m| m["content"] == "lunch plans for friday"));
+
+    let (status, _) = post_json(
+        &app,
+        "/hybrid-search",
+        serde_json::json!({
+            "sessionId": meta_id.to_string(),
+            "query": "deploy release",
+            "lexicalWeight": 0.0,
+            "semanticWeight": 0.0
+        }),
+    )
+    .await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let _ = fs::remove_dir_all(&test_root);
+    Ok(())
+}
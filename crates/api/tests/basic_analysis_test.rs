@@ -130,6 +130,7 @@ async fn test_basic_analysis_endpoints_with_time_filter_contract(
             content: Some((*content).to_string()),
             reply_to_message_id: None,
             platform_message_id: None,
+            edited_at: None,
             meta_id,
         })
         .await?;
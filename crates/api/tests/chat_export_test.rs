@@ -0,0 +1,377 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use tower::util::ServiceExt;
+use xenobot_api::chat;
+use xenobot_api::database::repository::{ChatMeta, Member, Message, MessageExportFilter};
+use xenobot_api::database::Repository;
+use xenobot_core::config::DatabaseConfig;
+
+static TEST_COUNTER: AtomicU64 = AtomicU64::new(0);
+static TEST_MUTEX: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+struct WorkingDirGuard {
+    previous: PathBuf,
+}
+
+impl WorkingDirGuard {
+    fn change_to(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let previous = std::env::current_dir()?;
+        std::env::set_current_dir(path)?;
+        Ok(Self { previous })
+    }
+}
+
+impl Drop for WorkingDirGuard {
+    fn drop(&mut self) {
+        let _ = std::env::set_current_dir(&self.previous);
+    }
+}
+
+fn unique_test_root() -> PathBuf {
+    let epoch_nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let seq = TEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("xenobot_api_export_{}_{}", epoch_nanos, seq))
+}
+
+async fn seed_chat(
+    repo: &Repository,
+    name: &str,
+    sender_id: i64,
+    first_ts: i64,
+    count: i64,
+) -> Result<i64, Box<dyn std::error::Error>> {
+    let meta_id = repo
+        .create_chat(&ChatMeta {
+            id: 0,
+            name: name.to_string(),
+            platform: "telegram".to_string(),
+            chat_type: "group".to_string(),
+            imported_at: 1_700_000_000,
+            group_id: None,
+            group_avatar: None,
+            owner_id: None,
+            schema_version: 3,
+            session_gap_threshold: 1800,
+        })
+        .await?;
+    for idx in 0..count {
+        repo.create_message(&Message {
+            id: 0,
+            sender_id,
+            sender_account_name: Some("Alice".to_string()),
+            sender_group_nickname: None,
+            ts: first_ts + idx,
+            msg_type: 0,
+            content: Some(format!("{} message {}", name, idx)),
+            reply_to_message_id: None,
+            platform_message_id: None,
+            edited_at: None,
+            meta_id,
+        })
+        .await?;
+    }
+    Ok(meta_id)
+}
+
+async fn get_ndjson(
+    app: &axum::Router,
+    path: &str,
+) -> Result<(StatusCode, Vec<serde_json::Value>), Box<dyn std::error::Error>> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(path)
+        .body(Body::empty())?;
+    let response = app.clone().oneshot(request).await?;
+    let status = response.status();
+    assert_eq!(
+        response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok()),
+        Some("application/x-ndjson")
+    );
+    let body_bytes = to_bytes(response.into_body(), usize::MAX).await?;
+    let mut rows = Vec::new();
+    for line in std::str::from_utf8(&body_bytes)?.lines() {
+        rows.push(serde_json::from_str::<serde_json::Value>(line)?);
+    }
+    Ok((status, rows))
+}
+
+#[tokio::test]
+async fn test_export_endpoint_streams_ndjson_across_pages() -> Result<(), Box<dyn std::error::Error>>
+{
+    let _test_guard = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+    let workspace_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .canonicalize()?;
+    let _cwd_guard = WorkingDirGuard::change_to(&workspace_root)?;
+
+    let test_root = unique_test_root();
+    fs::create_dir_all(&test_root)?;
+    let mut db_config = DatabaseConfig::default();
+    db_config.sqlite_path = test_root.join("xenobot_api_export_stream.db");
+    xenobot_api::database::init_database_with_config(&db_config).await?;
+
+    let pool = xenobot_api::database::get_pool().await?;
+    let repo = Repository::new(pool.clone());
+    let sender_id = repo
+        .create_member(&Member {
+            id: 0,
+            platform_id: "alice".to_string(),
+            account_name: Some("Alice".to_string()),
+            group_nickname: None,
+            aliases: None,
+            avatar: None,
+            roles: None,
+        })
+        .await?;
+    // More rows than one page so the stream has to follow its cursor.
+    let seeded = 1_203;
+    let meta_id = seed_chat(&repo, "Launch Room", sender_id, 1_735_000_000, seeded).await?;
+    seed_chat(&repo, "Other Room", sender_id, 1_735_000_000, 5).await?;
+
+    let app = chat::router();
+    let (status, rows) =
+        get_ndjson(&app, &format!("/export?format=ndjson&meta_id={meta_id}")).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(rows.len() as i64, seeded);
+    assert!(rows.iter().all(|row| row["meta_id"] == meta_id));
+    assert_eq!(rows[0]["content"], "Launch Room message 0");
+    assert_eq!(rows[0]["sender_name"], "Alice");
+    let timestamps: Vec<i64> = rows.iter().filter_map(|row| row["ts"].as_i64()).collect();
+    assert!(timestamps.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let since_ts = 1_735_000_000 + seeded - 10;
+    let (_, tail) = get_ndjson(
+        &app,
+        &format!("/export?meta_id={meta_id}&since_ts={since_ts}"),
+    )
+    .await?;
+    assert_eq!(tail.len(), 9);
+
+    let (_, everything) = get_ndjson(&app, "/export?format=ndjson").await?;
+    assert_eq!(everything.len() as i64, seeded + 5);
+
+    let _ = fs::remove_dir_all(&test_root);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_export_keyset_pages_do_not_skip_same_second_messages(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let _test_guard = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+    let workspace_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .canonicalize()?;
+    let _cwd_guard = WorkingDirGuard::change_to(&workspace_root)?;
+
+    let test_root = unique_test_root();
+    fs::create_dir_all(&test_root)?;
+    let mut db_config = DatabaseConfig::default();
+    db_config.sqlite_path = test_root.join("xenobot_api_export_same_second.db");
+    xenobot_api::database::init_database_with_config(&db_config).await?;
+
+    let pool = xenobot_api::database::get_pool().await?;
+    let repo = Repository::new(pool.clone());
+    let sender_id = repo
+        .create_member(&Member {
+            id: 0,
+            platform_id: "alice".to_string(),
+            account_name: Some("Alice".to_string()),
+            group_nickname: None,
+            aliases: None,
+            avatar: None,
+            roles: None,
+        })
+        .await?;
+    let meta_id = seed_chat(&repo, "Burst Room", sender_id, 1_735_000_000, 0).await?;
+    for idx in 0..100 {
+        repo.create_message(&Message {
+            id: 0,
+            sender_id,
+            sender_account_name: Some("Alice".to_string()),
+            sender_group_nickname: None,
+            ts: 1_735_000_000 + idx / 40,
+            msg_type: 0,
+            content: Some(format!("burst {}", idx)),
+            reply_to_message_id: None,
+            platform_message_id: None,
+            edited_at: None,
+            meta_id,
+        })
+        .await?;
+    }
+
+    let filter = MessageExportFilter {
+        meta_id: Some(meta_id),
+        ..Default::default()
+    };
+    let mut seen = Vec::new();
+    let mut after = None;
+    loop {
+        let page = repo.export_messages_page(&filter, after, 7).await?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        after = Some((last.ts, last.message_id));
+        seen.extend(
+            page.iter()
+                .map(|row| row.content.clone().unwrap_or_default()),
+        );
+    }
+    let expected: Vec<String> = (0..100).map(|idx| format!("burst {}", idx)).collect();
+    assert_eq!(seen, expected);
+
+    let _ = fs::remove_dir_all(&test_root);
+    Ok(())
+}
+
+async fn conditional_get(
+    app: &axum::Router,
+    path: &str,
+    if_none_match: Option<&str>,
+) -> Result<(StatusCode, Option<String>, bool), Box<dyn std::error::Error>> {
+    let mut builder = Request::builder().method("GET").uri(path);
+    if let Some(etag) = if_none_match {
+        builder = builder.header(header::IF_NONE_MATCH, etag);
+    }
+    let response = app.clone().oneshot(builder.body(Body::empty())?).await?;
+    let status = response.status();
+    let etag = response
+        .headers()
+        .get(header::ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let has_last_modified = response.headers().contains_key(header::LAST_MODIFIED);
+    let body = to_bytes(response.into_body(), usize::MAX).await?;
+    if status == StatusCode::NOT_MODIFIED {
+        assert!(body.is_empty());
+    }
+    Ok((status, etag, has_last_modified))
+}
+
+#[tokio::test]
+async fn test_sessions_and_export_answer_conditional_requests_with_304(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let _test_guard = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+    let workspace_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .canonicalize()?;
+    let _cwd_guard = WorkingDirGuard::change_to(&workspace_root)?;
+
+    let test_root = unique_test_root();
+    fs::create_dir_all(&test_root)?;
+    let mut db_config = DatabaseConfig::default();
+    db_config.sqlite_path = test_root.join("xenobot_api_export_etag.db");
+    xenobot_api::database::init_database_with_config(&db_config).await?;
+
+    let pool = xenobot_api::database::get_pool().await?;
+    let repo = Repository::new(pool.clone());
+    let sender_id = repo
+        .create_member(&Member {
+            id: 0,
+            platform_id: "alice".to_string(),
+            account_name: Some("Alice".to_string()),
+            group_nickname: None,
+            aliases: None,
+            avatar: None,
+            roles: None,
+        })
+        .await?;
+    let meta_id = seed_chat(&repo, "Launch Room", sender_id, 1_735_000_000, 3).await?;
+    let app = chat::router();
+
+    let (status, etag, has_last_modified) = conditional_get(&app, "/sessions", None).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert!(has_last_modified);
+    let sessions_etag = etag.expect("sessions etag");
+    assert!(sessions_etag.starts_with("W/\""));
+    let (status, etag, _) = conditional_get(&app, "/sessions", Some(&sessions_etag)).await?;
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+    assert_eq!(etag.as_deref(), Some(sessions_etag.as_str()));
+
+    repo.rename_chat(meta_id, "Launch Room (renamed)").await?;
+    let (status, etag, _) = conditional_get(&app, "/sessions", Some(&sessions_etag)).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(etag.as_deref(), Some(sessions_etag.as_str()));
+
+    let export_path = format!("/export?meta_id={meta_id}");
+    let (status, etag, _) = conditional_get(&app, &export_path, None).await?;
+    assert_eq!(status, StatusCode::OK);
+    let export_etag = etag.expect("export etag");
+    let (status, _, _) = conditional_get(&app, &export_path, Some(&export_etag)).await?;
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+
+    seed_chat(&repo, "Other Room", sender_id, 1_735_000_100, 1).await?;
+    let (status, _, _) = conditional_get(&app, &export_path, Some(&export_etag)).await?;
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+    repo.create_message(&Message {
+        id: 0,
+        sender_id,
+        sender_account_name: Some("Alice".to_string()),
+        sender_group_nickname: None,
+        ts: 1_735_000_200,
+        msg_type: 0,
+        content: Some("late arrival".to_string()),
+        reply_to_message_id: None,
+        platform_message_id: None,
+        edited_at: None,
+        meta_id,
+    })
+    .await?;
+    let (status, etag, has_last_modified) =
+        conditional_get(&app, &export_path, Some(&export_etag)).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!has_last_modified);
+    assert_ne!(etag.as_deref(), Some(export_etag.as_str()));
+
+    // Rows without a stored sender name take it from `member`, so renaming
+    // the sender changes the export.
+    let mut bob = Member {
+        id: 0,
+        platform_id: "bob".to_string(),
+        account_name: Some("Bob".to_string()),
+        group_nickname: None,
+        aliases: None,
+        avatar: None,
+        roles: None,
+    };
+    bob.id = repo.create_member(&bob).await?;
+    repo.create_message(&Message {
+        id: 0,
+        sender_id: bob.id,
+        sender_account_name: None,
+        sender_group_nickname: None,
+        ts: 1_735_000_300,
+        msg_type: 0,
+        content: Some("from bob".to_string()),
+        reply_to_message_id: None,
+        platform_message_id: None,
+        edited_at: None,
+        meta_id,
+    })
+    .await?;
+    let (status, etag, _) = conditional_get(&app, &export_path, None).await?;
+    assert_eq!(status, StatusCode::OK);
+    let export_etag = etag.expect("export etag");
+    bob.account_name = Some("Robert".to_string());
+    repo.update_member(&bob).await?;
+    let (status, etag, _) = conditional_get(&app, &export_path, Some(&export_etag)).await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(etag.as_deref(), Some(export_etag.as_str()));
+
+    let _ = fs::remove_dir_all(&test_root);
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use serde_json::json;
+use tower::util::ServiceExt;
+use xenobot_analysis::parsers::{ChatMember, ChatType, MessageType, ParsedChat, ParsedMessage};
+use xenobot_api::chat;
+use xenobot_core::config::DatabaseConfig;
+
+static TEST_COUNTER: AtomicU64 = AtomicU64::new(0);
+static TEST_MUTEX: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+struct WorkingDirGuard {
+    previous: PathBuf,
+}
+
+impl WorkingDirGuard {
+    fn change_to(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let previous = std::env::current_dir()?;
+        std::env::set_current_dir(path)?;
+        Ok(Self { previous })
+    }
+}
+
+impl Drop for WorkingDirGuard {
+    fn drop(&mut self) {
+        let _ = std::env::set_current_dir(&self.previous);
+    }
+}
+
+fn unique_test_root() -> PathBuf {
+    let epoch_nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let seq = TEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("xenobot_api_inline_import_{}_{}", epoch_nanos, seq))
+}
+
+async fn post_json(
+    app: &axum::Router,
+    path: &str,
+    body: Vec<u8>,
+) -> Result<(StatusCode, serde_json::Value), Box<dyn std::error::Error>> {
+    let request = Request::builder()
+        .method("POST")
+        .uri(path)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body))?;
+    let response = app.clone().oneshot(request).await?;
+    let status = response.status();
+    let body_bytes = to_bytes(response.into_body(), usize::MAX).await?;
+    let value = serde_json::from_slice(&body_bytes).unwrap_or(serde_json::Value::Null);
+    Ok((status, value))
+}
+
+async fn message_count(meta_id: i64) -> Result<i64, Box<dyn std::error::Error>> {
+    let pool = xenobot_api::database::get_pool().await?;
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM message WHERE meta_id = ?1")
+        .bind(meta_id)
+        .fetch_one(&*pool)
+        .await?;
+    Ok(count)
+}
+
+#[tokio::test]
+async fn test_import_accepts_inline_whatsapp_text_and_parsed_chat(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let _test_guard = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+    let workspace_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .canonicalize()?;
+    let _cwd_guard = WorkingDirGuard::change_to(&workspace_root)?;
+
+    let test_root = unique_test_root();
+    fs::create_dir_all(&test_root)?;
+    let mut db_config = DatabaseConfig::default();
+    db_config.sqlite_path = test_root.join("xenobot_api_inline_import.db");
+    xenobot_api::database::init_database_with_config(&db_config).await?;
+
+    let app = chat::router();
+    let whatsapp_export = "[01/02/2025, 10:00:00] Alice: Launch checklist is green.\n\
+                           [01/02/2025, 10:01:30] Bob: Payment webhook still blocks.\n\
+                           [01/02/2025, 10:03:00] Alice: I will chase it today.\n";
+    let (status, resp) = post_json(
+        &app,
+        "/import",
+        serde_json::to_vec(&json!({
+            "content": whatsapp_export,
+            "platform": "whatsapp",
+            "chat_name": "Launch Room"
+        }))?,
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(resp["success"], true, "unexpected response: {resp}");
+    assert_eq!(resp["payloadPlatform"], "whatsapp");
+    assert_eq!(resp["sessionName"], "Launch Room");
+    assert_eq!(resp["diagnostics"]["messagesWritten"], 3);
+    let meta_id: i64 = resp["sessionId"].as_str().expect("session id").parse()?;
+    assert_eq!(message_count(meta_id).await?, 3);
+
+    let parsed = ParsedChat {
+        platform: "telegram".to_string(),
+        chat_name: "Ops".to_string(),
+        chat_type: ChatType::Group,
+        messages: vec![
+            ParsedMessage {
+                sender: "1001".to_string(),
+                sender_name: Some("Carol".to_string()),
+                sender_group_nickname: None,
+                timestamp: 1_735_813_230,
+                content: "Deploy window confirmed.".to_string(),
+                msg_type: MessageType::Text,
+                edited_at: Some(1_735_813_500),
+                platform_message_id: None,
+            },
+            ParsedMessage {
+                sender: "1002".to_string(),
+                sender_name: Some("Dan".to_string()),
+                sender_group_nickname: None,
+                timestamp: 1_735_813_290,
+                content: "   ".to_string(),
+                msg_type: MessageType::Text,
+                edited_at: None,
+                platform_message_id: None,
+            },
+        ],
+        members: vec![ChatMember {
+            id: "1001".to_string(),
+            name: Some("Carol".to_string()),
+            display_name: None,
+        }],
+        encoding: None,
+        salvage: None,
+        chat_type_known: false,
+    };
+    let (status, resp) = post_json(
+        &app,
+        "/import",
+        serde_json::to_vec(&json!({ "chat": parsed }))?,
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(resp["success"], true, "unexpected response: {resp}");
+    assert_eq!(resp["payloadPlatform"], "telegram");
+    assert_eq!(resp["diagnostics"]["messagesReceived"], 2);
+    assert_eq!(resp["diagnostics"]["messagesWritten"], 1);
+    let meta_id: i64 = resp["sessionId"].as_str().expect("session id").parse()?;
+    assert_eq!(message_count(meta_id).await?, 1);
+    let pool = xenobot_api::database::get_pool().await?;
+    let edited_at: Option<i64> =
+        sqlx::query_scalar("SELECT edited_at FROM message WHERE meta_id = ?1")
+            .bind(meta_id)
+            .fetch_one(&*pool)
+            .await?;
+    assert_eq!(edited_at, Some(1_735_813_500));
+
+    let (status, resp) = post_json(
+        &app,
+        "/import",
+        serde_json::to_vec(&json!({ "content": whatsapp_export, "chat_name": "Unhinted" }))?,
+    )
+    .await?;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(resp["success"], true, "unexpected response: {resp}");
+    assert_eq!(resp["diagnostics"]["messagesWritten"], 3);
+
+    let (status, resp) = post_json(
+        &app,
+        "/import",
+        serde_json::to_vec(&json!({ "content": "not a chat export" }))?,
+    )
+    .await?;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error = resp["error"].as_str().unwrap_or_default();
+    assert!(error.contains("error.unrecognized_format"), "{error}");
+    assert!(error.contains("whatsapp: "), "{error}");
+
+    let oversized = "x".repeat(9 * 1024 * 1024);
+    let (status, _) = post_json(
+        &app,
+        "/import",
+        serde_json::to_vec(&json!({ "content": oversized, "platform": "whatsapp" }))?,
+    )
+    .await?;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+    let _ = fs::remove_dir_all(&test_root);
+    Ok(())
+}
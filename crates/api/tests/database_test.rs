@@ -1,7 +1,9 @@
 use std::sync::Arc;
 
 use sqlx::{migrate::Migrator, sqlite::SqlitePoolOptions};
-use xenobot_api::database::repository::{ChatMeta, ImportSourceCheckpoint, Member, Message};
+use xenobot_api::database::repository::{
+    ChatMeta, ImportSourceCheckpoint, MaintenanceOptions, Member, Message,
+};
 use xenobot_api::database::Repository;
 
 static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
@@ -110,6 +112,161 @@ async fn test_repository_basic_crud() -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
+#[tokio::test]
+async fn test_rename_and_cascading_delete_chat() -> Result<(), Box<dyn std::error::Error>> {
+    let repo = setup_test_repo().await?;
+
+    let meta_id = repo
+        .create_chat(&ChatMeta {
+            id: 0,
+            name: "Before".to_string(),
+            platform: "telegram".to_string(),
+            chat_type: "group".to_string(),
+            imported_at: 1_700_000_000,
+            group_id: None,
+            group_avatar: None,
+            owner_id: None,
+            schema_version: 3,
+            session_gap_threshold: 1800,
+        })
+        .await?;
+
+    assert!(repo.rename_chat(meta_id, "After").await?);
+    assert_eq!(repo.get_chat(meta_id).await?.expect("chat").name, "After");
+    assert!(!repo.rename_chat(meta_id + 1000, "Missing").await?);
+
+    let sender_id = repo
+        .get_or_create_member("telegram:user_1", Some("Alice"))
+        .await?;
+    let message_id = repo
+        .create_message(&Message {
+            id: 0,
+            sender_id,
+            sender_account_name: Some("Alice".to_string()),
+            sender_group_nickname: None,
+            ts: 1_700_000_123,
+            msg_type: 1,
+            content: Some("[photo]".to_string()),
+            reply_to_message_id: None,
+            platform_message_id: None,
+            edited_at: None,
+            meta_id,
+        })
+        .await?;
+    repo.create_message_media(
+        message_id,
+        "image",
+        std::path::Path::new("/tmp/xenobot-missing-photo.jpg"),
+        Some("image/jpeg"),
+        Some(3),
+        1_700_000_200,
+    )
+    .await?;
+    assert_eq!(repo.list_message_media(message_id).await?.len(), 1);
+
+    assert!(repo.delete_chat(meta_id).await?);
+    assert!(repo.get_chat(meta_id).await?.is_none());
+    assert!(repo.list_message_media(message_id).await?.is_empty());
+    assert!(repo.get_message(message_id).await?.is_none());
+    assert!(!repo.delete_chat(meta_id).await?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_maintenance_rebuilds_fts_and_analyzes() -> Result<(), Box<dyn std::error::Error>> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+    MIGRATOR.run(&pool).await?;
+    sqlx::query("CREATE VIRTUAL TABLE message_fts USING fts5(content)")
+        .execute(&pool)
+        .await?;
+    sqlx::query("INSERT INTO message_fts(content) VALUES ('hello world'), ('stale index')")
+        .execute(&pool)
+        .await?;
+    let repo = Repository::new(Arc::new(pool));
+
+    let steps = repo
+        .run_maintenance(&MaintenanceOptions {
+            rebuild_fts: true,
+            analyze: true,
+            wal_checkpoint: true,
+        })
+        .await?;
+    let names: Vec<&str> = steps.iter().map(|step| step.name.as_str()).collect();
+    assert_eq!(names, vec!["rebuild_fts", "analyze", "wal_checkpoint"]);
+    assert_eq!(steps[0].detail.as_deref(), Some("message_fts"));
+
+    let only_analyze = repo
+        .run_maintenance(&MaintenanceOptions {
+            rebuild_fts: false,
+            analyze: true,
+            wal_checkpoint: false,
+        })
+        .await?;
+    assert_eq!(only_analyze.len(), 1);
+    assert_eq!(only_analyze[0].name, "analyze");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_chat_members_counts_messages_per_sender(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = setup_test_repo().await?;
+    let new_chat = |name: &str| ChatMeta {
+        id: 0,
+        name: name.to_string(),
+        platform: "telegram".to_string(),
+        chat_type: "group".to_string(),
+        imported_at: 1_700_000_000,
+        group_id: None,
+        group_avatar: None,
+        owner_id: None,
+        schema_version: 3,
+        session_gap_threshold: 1800,
+    };
+    let meta_id = repo.create_chat(&new_chat("Team")).await?;
+    let empty_id = repo.create_chat(&new_chat("Empty")).await?;
+
+    let alice = repo
+        .get_or_create_member("telegram:alice", Some("Alice"))
+        .await?;
+    let bob = repo
+        .get_or_create_member("telegram:bob", Some("Bob"))
+        .await?;
+    for (sender_id, ts) in [(alice, 1), (bob, 2), (alice, 3)] {
+        repo.create_message(&Message {
+            id: 0,
+            sender_id,
+            sender_account_name: None,
+            sender_group_nickname: None,
+            ts: 1_700_000_000 + ts,
+            msg_type: 0,
+            content: Some(format!("message {ts}")),
+            reply_to_message_id: None,
+            platform_message_id: None,
+            edited_at: None,
+            meta_id,
+        })
+        .await?;
+    }
+
+    let members = repo.list_chat_members(meta_id).await?;
+    let counts: Vec<(&str, i64)> = members
+        .iter()
+        .map(|member| (member.platform_id.as_str(), member.message_count))
+        .collect();
+    assert_eq!(counts, vec![("telegram:alice", 2), ("telegram:bob", 1)]);
+    assert_eq!(members[0].account_name.as_deref(), Some("Alice"));
+
+    assert!(repo.list_chat_members(empty_id).await?.is_empty());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_member_crud() -> Result<(), Box<dyn std::error::Error>> {
     let repo = setup_test_repo().await?;
@@ -172,6 +329,7 @@ async fn test_import_source_checkpoint_upsert_and_unchanged(
         last_duplicate_messages: 5,
         status: "completed".to_string(),
         error_message: None,
+        source_encoding: Some("GBK".to_string()),
     };
     repo.upsert_import_source_checkpoint(&checkpoint).await?;
 
@@ -184,6 +342,7 @@ async fn test_import_source_checkpoint_upsert_and_unchanged(
     assert_eq!(stored.fingerprint, "123:1700000000:42");
     assert_eq!(stored.last_inserted_messages, 20);
     assert_eq!(stored.last_duplicate_messages, 5);
+    assert_eq!(stored.source_encoding.as_deref(), Some("GBK"));
 
     let unchanged = repo
         .source_checkpoint_is_unchanged(
@@ -222,7 +381,206 @@ async fn test_import_source_checkpoint_upsert_and_unchanged(
     assert_eq!(stored_after_update.file_size, 999);
     assert_eq!(stored_after_update.last_inserted_messages, 3);
     assert_eq!(stored_after_update.last_duplicate_messages, 7);
+    assert_eq!(stored_after_update.source_encoding.as_deref(), Some("GBK"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_message_exists_across_chats_respects_platform_scope(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = setup_test_repo().await?;
+    let chat = |name: &str| ChatMeta {
+        id: 0,
+        name: name.to_string(),
+        platform: "telegram".to_string(),
+        chat_type: "group".to_string(),
+        imported_at: 1_700_000_000,
+        group_id: None,
+        group_avatar: None,
+        owner_id: None,
+        schema_version: 3,
+        session_gap_threshold: 1800,
+    };
+    let backup_a = repo.create_chat(&chat("Backup A")).await?;
+    let backup_b = repo.create_chat(&chat("Backup B")).await?;
+    let sender_id = repo
+        .get_or_create_member("telegram:user_1", Some("Alice"))
+        .await?;
+    repo.create_message(&Message {
+        id: 0,
+        sender_id,
+        sender_account_name: Some("Alice".to_string()),
+        sender_group_nickname: None,
+        ts: 1_700_000_123,
+        msg_type: 0,
+        content: Some("hello".to_string()),
+        reply_to_message_id: None,
+        platform_message_id: None,
+        edited_at: None,
+        meta_id: backup_a,
+    })
+    .await?;
+
+    // Session scope only sees the chat being imported into.
+    assert!(
+        !repo
+            .message_exists(backup_b, sender_id, 1_700_000_123, 0, Some("hello"))
+            .await?
+    );
+    // Platform scope finds the copy captured in the other backup.
+    assert!(
+        repo.message_exists_across_chats(
+            Some("telegram"),
+            sender_id,
+            1_700_000_123,
+            0,
+            Some("hello")
+        )
+        .await?
+    );
+    assert!(
+        !repo
+            .message_exists_across_chats(
+                Some("discord"),
+                sender_id,
+                1_700_000_123,
+                0,
+                Some("hello")
+            )
+            .await?
+    );
+    assert!(
+        repo.message_exists_across_chats(None, sender_id, 1_700_000_123, 0, Some("hello"))
+            .await?
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_message_thread_follows_reply_chain_in_order(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = setup_test_repo().await?;
+    let chat = |name: &str| ChatMeta {
+        id: 0,
+        name: name.to_string(),
+        platform: "telegram".to_string(),
+        chat_type: "group".to_string(),
+        imported_at: 1_700_000_000,
+        group_id: None,
+        group_avatar: None,
+        owner_id: None,
+        schema_version: 3,
+        session_gap_threshold: 1800,
+    };
+    let meta_id = repo.create_chat(&chat("Thread")).await?;
+    let other_meta_id = repo.create_chat(&chat("Other")).await?;
+    let sender_id = repo
+        .get_or_create_member("telegram:user_1", Some("Alice"))
+        .await?;
+    let message = |meta_id: i64, ts: i64, reply_to: Option<String>, platform_id: &str| Message {
+        id: 0,
+        sender_id,
+        sender_account_name: Some("Alice".to_string()),
+        sender_group_nickname: None,
+        ts,
+        msg_type: 0,
+        content: Some(format!("message at {}", ts)),
+        reply_to_message_id: reply_to,
+        platform_message_id: Some(platform_id.to_string()),
+        edited_at: None,
+        meta_id,
+    };
+
+    // root <- first (by row id) <- second (by platform id) <- leaf, plus a
+    // sibling reply to `second`.
+    let root = repo
+        .create_message(&message(meta_id, 100, None, "m100"))
+        .await?;
+    let first = repo
+        .create_message(&message(meta_id, 200, Some(root.to_string()), "m200"))
+        .await?;
+    let second = repo
+        .create_message(&message(meta_id, 300, Some(first.to_string()), "m300"))
+        .await?;
+    let leaf = repo
+        .create_message(&message(meta_id, 400, Some("m300".to_string()), "m400"))
+        .await?;
+    let sibling = repo
+        .create_message(&message(meta_id, 350, Some("m300".to_string()), "m350"))
+        .await?;
+    // Same platform id in another chat must not be picked up.
+    repo.create_message(&message(
+        other_meta_id,
+        360,
+        Some("m300".to_string()),
+        "m300",
+    ))
+    .await?;
+
+    let thread = repo
+        .get_message_thread(leaf, 50, 0)
+        .await?
+        .expect("thread for leaf");
+    let ancestor_ids: Vec<i64> = thread.ancestors.iter().map(|m| m.id).collect();
+    assert_eq!(ancestor_ids, vec![root, first, second]);
+    assert_eq!(thread.message.id, leaf);
+    assert!(thread.replies.is_empty());
+    assert!(!thread.truncated);
+
+    let thread = repo
+        .get_message_thread(second, 1, 10)
+        .await?
+        .expect("thread for second");
+    let ancestor_ids: Vec<i64> = thread.ancestors.iter().map(|m| m.id).collect();
+    assert_eq!(ancestor_ids, vec![first]);
+    assert!(thread.truncated);
+    let reply_ids: Vec<i64> = thread.replies.iter().map(|m| m.id).collect();
+    assert_eq!(reply_ids, vec![sibling, leaf]);
+
+    // Two messages replying to each other end the walk instead of looping.
+    let a = repo
+        .create_message(&message(meta_id, 500, Some("loop-b".to_string()), "loop-a"))
+        .await?;
+    let b = repo
+        .create_message(&message(meta_id, 600, Some("loop-a".to_string()), "loop-b"))
+        .await?;
+    let thread = repo
+        .get_message_thread(b, 50, 0)
+        .await?
+        .expect("thread for cycle");
+    let ancestor_ids: Vec<i64> = thread.ancestors.iter().map(|m| m.id).collect();
+    assert_eq!(ancestor_ids, vec![a]);
+    assert!(!thread.truncated);
+
+    // A native id that equals another message's row id names the message
+    // carrying that native id, not the row.
+    let native = repo
+        .create_message(&message(meta_id, 700, None, &root.to_string()))
+        .await?;
+    let reply = repo
+        .create_message(&message(meta_id, 800, Some(root.to_string()), "m800"))
+        .await?;
+    let thread = repo
+        .get_message_thread(reply, 50, 0)
+        .await?
+        .expect("thread for colliding reply");
+    let ancestor_ids: Vec<i64> = thread.ancestors.iter().map(|m| m.id).collect();
+    assert_eq!(ancestor_ids, vec![native]);
+    let thread = repo
+        .get_message_thread(native, 0, 10)
+        .await?
+        .expect("thread for native parent");
+    let reply_ids: Vec<i64> = thread.replies.iter().map(|m| m.id).collect();
+    assert_eq!(reply_ids, vec![first, reply]);
+    let thread = repo
+        .get_message_thread(root, 0, 10)
+        .await?
+        .expect("thread for root");
+    assert!(thread.replies.is_empty());
 
+    assert!(repo.get_message_thread(i64::MAX, 50, 0).await?.is_none());
     Ok(())
 }
 
@@ -258,6 +616,7 @@ async fn test_message_exists_incremental_consistency() -> Result<(), Box<dyn std
         content: Some("hello".to_string()),
         reply_to_message_id: None,
         platform_message_id: None,
+        edited_at: None,
         meta_id,
     })
     .await?;
@@ -282,6 +641,7 @@ async fn test_message_exists_incremental_consistency() -> Result<(), Box<dyn std
         content: None,
         reply_to_message_id: None,
         platform_message_id: None,
+        edited_at: None,
         meta_id,
     })
     .await?;
@@ -292,3 +652,200 @@ async fn test_message_exists_incremental_consistency() -> Result<(), Box<dyn std
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_message_media_resolves_after_media_root_relocation(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let test_root = std::env::temp_dir().join(format!(
+        "xenobot_api_media_root_{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    ));
+    let original_root = test_root.join("original").join("media");
+    let relocated_root = test_root.join("relocated").join("media");
+    std::fs::create_dir_all(original_root.join("images"))?;
+    std::fs::create_dir_all(relocated_root.join("images"))?;
+    std::fs::write(original_root.join("images").join("photo.jpg"), b"jpg")?;
+    std::fs::write(relocated_root.join("images").join("photo.jpg"), b"jpg")?;
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+    MIGRATOR.run(&pool).await?;
+    let pool = Arc::new(pool);
+    let repo = Repository::new(pool.clone()).with_media_root(&original_root);
+
+    let meta_id = repo
+        .create_chat(&ChatMeta {
+            id: 0,
+            name: "Media".to_string(),
+            platform: "telegram".to_string(),
+            chat_type: "private".to_string(),
+            imported_at: 1_700_000_000,
+            group_id: None,
+            group_avatar: None,
+            owner_id: None,
+            schema_version: 3,
+            session_gap_threshold: 1800,
+        })
+        .await?;
+    let sender_id = repo
+        .get_or_create_member("telegram:user_1", Some("Alice"))
+        .await?;
+    let message_id = repo
+        .create_message(&Message {
+            id: 0,
+            sender_id,
+            sender_account_name: Some("Alice".to_string()),
+            sender_group_nickname: None,
+            ts: 1_700_000_123,
+            msg_type: 1,
+            content: Some("[photo]".to_string()),
+            reply_to_message_id: None,
+            platform_message_id: None,
+            edited_at: None,
+            meta_id,
+        })
+        .await?;
+
+    repo.create_message_media(
+        message_id,
+        "image",
+        &original_root.join("images").join("photo.jpg"),
+        Some("image/jpeg"),
+        Some(3),
+        1_700_000_200,
+    )
+    .await?;
+
+    let stored = repo.list_message_media(message_id).await?;
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].local_path, "images/photo.jpg");
+
+    let relocated_repo = Repository::new(pool).with_media_root(&relocated_root);
+    let resolved = relocated_repo.resolve_media_path(&stored[0].local_path);
+    assert_eq!(resolved, relocated_root.join("images").join("photo.jpg"));
+    assert!(resolved.exists());
+
+    let legacy_absolute = test_root.join("gone").join("photo.jpg");
+    std::fs::write(relocated_root.join("photo.jpg"), b"jpg")?;
+    let legacy_resolved =
+        relocated_repo.resolve_media_path(legacy_absolute.to_string_lossy().as_ref());
+    assert_eq!(legacy_resolved, relocated_root.join("photo.jpg"));
+
+    let _ = std::fs::remove_dir_all(&test_root);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_legacy_cli_migrated_db_is_adopted_by_sqlx_migrator(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+
+    // Older CLI builds applied the same SQL files but tracked them in `schema_migrations`.
+    sqlx::query("CREATE TABLE schema_migrations (version INTEGER PRIMARY KEY, applied_at TEXT)")
+        .execute(&pool)
+        .await?;
+    for migration in MIGRATOR.iter() {
+        sqlx::raw_sql(&migration.sql).execute(&pool).await?;
+        sqlx::query("INSERT INTO schema_migrations(version) VALUES (?1)")
+            .bind(migration.version)
+            .execute(&pool)
+            .await?;
+    }
+
+    let adopted = xenobot_api::database::reconcile_legacy_migrations(&pool, &MIGRATOR).await?;
+    assert_eq!(adopted as usize, MIGRATOR.iter().count());
+    MIGRATOR.run(&pool).await?;
+
+    let applied_version: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(version), 0) FROM _sqlx_migrations WHERE success = 1",
+    )
+    .fetch_one(&pool)
+    .await?;
+    assert_eq!(applied_version, latest_migration_version_on_disk()?);
+
+    let adopted_again =
+        xenobot_api::database::reconcile_legacy_migrations(&pool, &MIGRATOR).await?;
+    assert_eq!(adopted_again, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_repository_refuses_writes_when_schema_is_incompatible(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+    MIGRATOR.run(&pool).await?;
+
+    // A `message` table as a different build might leave it: one column this
+    // build writes is gone and a required column it doesn't know was added.
+    sqlx::raw_sql(
+        r#"
+        DROP TABLE message;
+        CREATE TABLE message (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            sender_id INTEGER NOT NULL,
+            sender_account_name TEXT,
+            sender_group_nickname TEXT,
+            ts INTEGER NOT NULL,
+            msg_type INTEGER NOT NULL,
+            content TEXT,
+            reply_to_message_id TEXT,
+            meta_id INTEGER NOT NULL,
+            edited_at INTEGER,
+            thread_key TEXT NOT NULL
+        );
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    let pool = Arc::new(pool);
+    let repo = Repository::new(pool.clone());
+    let err = repo
+        .create_chat(&ChatMeta {
+            id: 0,
+            name: "Mismatch".to_string(),
+            platform: "telegram".to_string(),
+            chat_type: "group".to_string(),
+            imported_at: 1_700_000_000,
+            group_id: None,
+            group_avatar: None,
+            owner_id: None,
+            schema_version: 3,
+            session_gap_threshold: 1800,
+        })
+        .await
+        .expect_err("incompatible schema must be refused");
+    let message = err.to_string();
+    assert!(message.contains("platform_message_id"), "{message}");
+    assert!(message.contains("thread_key"), "{message}");
+
+    let meta_rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM meta")
+        .fetch_one(&*pool)
+        .await?;
+    assert_eq!(meta_rows, 0, "nothing may be written before the check");
+
+    // An import's first write is its sender; a fresh repository refuses it too.
+    let repo = Repository::new(pool.clone());
+    repo.get_or_create_member("tg:1", Some("Alice"))
+        .await
+        .expect_err("member write must be refused");
+    repo.rename_chat(1, "Renamed")
+        .await
+        .expect_err("chat rename must be refused");
+    let member_rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM member")
+        .fetch_one(&*pool)
+        .await?;
+    assert_eq!(member_rows, 0);
+    Ok(())
+}
@@ -225,6 +225,7 @@ async fn test_media_message_route_streams_file_from_message_content(
             ),
             reply_to_message_id: None,
             platform_message_id: Some("media-msg-1".to_string()),
+            edited_at: None,
             meta_id,
         })
         .await?;
@@ -3,6 +3,6 @@
 fn main() {
     if let Err(err) = xenobot_cli::app::run() {
         eprintln!("{}", err);
-        std::process::exit(1);
+        std::process::exit(err.exit_code());
     }
 }
@@ -6,6 +6,9 @@
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
+/// Texts sent to the embedding backend per call unless `--embed-batch` says otherwise.
+pub const DEFAULT_EMBED_BATCH: usize = 32;
+
 /// Main CLI application.
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -18,6 +21,11 @@ pub struct Cli {
     #[arg(short, long, env = "XENOBOT_CONFIG")]
     pub config: Option<PathBuf>,
 
+    /// Suppress human-oriented status lines and notes; `--format json` then
+    /// leaves only the JSON document on stdout
+    #[arg(short, long, global = true, default_value_t = false)]
+    pub quiet: bool,
+
     /// Subcommand to execute
     #[command(subcommand)]
     pub command: Commands,
@@ -61,6 +69,21 @@ pub enum Commands {
 
     /// Database operations
     Db(DbArgs),
+
+    /// Manage imported chats
+    Chat(ChatArgs),
+
+    /// Inspect the resolved CLI configuration
+    Config(ConfigArgs),
+
+    /// Benchmark the import pipeline with a synthetic export
+    Bench(BenchArgs),
+
+    /// Check that parse, import, search and export work on this install
+    Selftest(SelftestArgs),
+
+    /// Inspect the chat export parsers built into this binary
+    Parsers(ParsersArgs),
 }
 
 /// Key registration arguments.
@@ -193,6 +216,19 @@ pub struct MonitorArgs {
     /// Database path override when --write-db is enabled
     #[arg(long, env = "XENOBOT_DB_PATH")]
     pub db_path: Option<PathBuf>,
+
+    /// Extra file extensions to monitor, comma separated (e.g. "log,dat")
+    #[arg(long, value_delimiter = ',')]
+    pub include_ext: Vec<String>,
+
+    /// File extensions to ignore, comma separated (e.g. "html,xml")
+    #[arg(long, value_delimiter = ',')]
+    pub exclude_ext: Vec<String>,
+
+    /// Import every file under the platform it parses as instead of skipping
+    /// files that do not match --format
+    #[arg(long, default_value_t = false)]
+    pub any_platform: bool,
 }
 
 /// Source discovery arguments.
@@ -379,6 +415,10 @@ pub enum ApiCommand {
         #[arg(long, alias = "sandbox-coexist", default_value_t = false)]
         force_file_gateway: bool,
 
+        /// Exit file-gateway mode after this many seconds without a processed request
+        #[arg(long)]
+        idle_shutdown_secs: Option<u64>,
+
         /// Database path
         #[arg(long, env = "XENOBOT_DB_PATH")]
         db_path: Option<PathBuf>,
@@ -633,6 +673,10 @@ pub enum AnalysisType {
         #[arg(long)]
         end_date: Option<String>,
 
+        /// Whether `--end-date` itself is part of the range
+        #[arg(long, value_enum, default_value_t = DateBoundary::Inclusive)]
+        date_boundary: DateBoundary,
+
         /// Member ID filter
         #[arg(long)]
         member_id: Option<String>,
@@ -672,6 +716,10 @@ pub struct ImportArgs {
     #[arg(value_enum)]
     pub format: PlatformFormat,
 
+    /// Force this platform's parser instead of auto-detecting each file's format
+    #[arg(long, value_enum)]
+    pub platform: Option<PlatformFormat>,
+
     /// Database path (creates new if not exists)
     #[arg(short, long, env = "XENOBOT_DB_PATH")]
     pub db_path: Option<PathBuf>,
@@ -692,9 +740,46 @@ pub struct ImportArgs {
     #[arg(long, default_value_t = false)]
     pub write_db: bool,
 
+    /// With --write-db, run parsing and dedup against the database and print
+    /// the summary a real import would produce, without writing anything
+    #[arg(long, requires = "write_db")]
+    pub dry_run: bool,
+
     /// Merge multi-file import into a single session name per platform when writing DB
     #[arg(long, default_value_t = false)]
     pub merge: bool,
+
+    /// Number of files parsed in parallel; a single writer inserts each file
+    /// as soon as it and the files before it are parsed
+    #[arg(long, default_value_t = 1)]
+    pub parse_concurrency: usize,
+
+    /// Flag messages dated more than this many seconds after the current time
+    #[arg(long, value_name = "SECONDS", default_value_t = 86_400)]
+    pub future_skew_secs: u64,
+
+    /// Rewrite flagged future timestamps to the current time instead of keeping them
+    #[arg(long, default_value_t = false)]
+    pub clamp_future: bool,
+
+    /// Order in which parsed files are written to the database
+    #[arg(long, value_enum, default_value_t = ImportFileOrder::Name)]
+    pub file_order: ImportFileOrder,
+
+    /// How far to look for an existing copy of each message when writing to the
+    /// DB. Broader scopes cost one extra query per message
+    #[arg(long, value_enum, default_value_t = ImportDedupScope::Session)]
+    pub dedup_scope: ImportDedupScope,
+
+    /// Decode text exports with this encoding (e.g. `gbk`, `shift_jis`,
+    /// `windows-1252`) instead of detecting it per file
+    #[arg(long, value_name = "LABEL")]
+    pub encoding: Option<String>,
+
+    /// Recover the complete messages of JSON exports that end early
+    /// (interrupted download, full disk) instead of failing the file
+    #[arg(long)]
+    pub salvage: bool,
 }
 
 /// Data export arguments.
@@ -720,9 +805,59 @@ pub struct ExportArgs {
     #[arg(long)]
     pub end_date: Option<String>,
 
+    /// Whether `--end-date` itself is part of the range
+    #[arg(long, value_enum, default_value_t = DateBoundary::Inclusive)]
+    pub date_boundary: DateBoundary,
+
     /// Member ID filter
     #[arg(long)]
     pub member_id: Option<String>,
+
+    /// Write `ts` and `edited_at` as RFC3339 UTC strings instead of unix seconds
+    #[arg(long)]
+    pub iso_timestamps: bool,
+
+    /// Only export chats from this platform (exact match, e.g. `telegram`)
+    #[arg(long)]
+    pub platform: Option<String>,
+
+    /// Only export chats whose name contains this text (ASCII case-insensitive)
+    #[arg(long)]
+    pub chat_name: Option<String>,
+
+    /// Comma-separated subset of columns to emit (csv/json/jsonl only), e.g. `ts,sender_name,content`
+    #[arg(long)]
+    pub columns: Option<String>,
+
+    /// CSV field delimiter: a single character such as `;` or `|`, or `tab`
+    #[arg(long, default_value = ",")]
+    pub csv_delimiter: String,
+
+    /// CSV quoting: quote every text field, or only those that need it
+    #[arg(long, value_enum, default_value_t = CsvQuoting::Always)]
+    pub csv_quoting: CsvQuoting,
+
+    /// Only export messages with an id greater than this watermark
+    #[arg(long)]
+    pub since_id: Option<i64>,
+
+    /// Only export messages with a timestamp (unix seconds) greater than this watermark
+    #[arg(long)]
+    pub since_ts: Option<i64>,
+
+    /// Watermark file read as the default `--since-id`/`--since-ts` and updated after export
+    #[arg(long)]
+    pub watermark_file: Option<PathBuf>,
+
+    /// Which stored sender name to show in `sender_name`
+    #[arg(long, value_enum, default_value_t = NamePreference::Account)]
+    pub name_preference: NamePreference,
+
+    /// Strip control characters (except tab and newline) from exported text:
+    /// `on` or `off`. Defaults to on for every format except json/jsonl,
+    /// which escape them instead
+    #[arg(long, value_name = "on|off", value_parser = clap::builder::BoolishValueParser::new())]
+    pub sanitize: Option<bool>,
 }
 
 /// Data query arguments.
@@ -742,7 +877,8 @@ pub struct QueryArgs {
 pub enum QueryType {
     /// Search messages
     Search {
-        /// Search keyword
+        /// Search keyword; space-separated terms must all match, `OR` separates
+        /// alternatives and `"quoted text"` matches a phrase
         #[arg(required = true)]
         keyword: String,
 
@@ -754,6 +890,10 @@ pub enum QueryType {
         #[arg(long)]
         end_date: Option<String>,
 
+        /// Whether `--end-date` itself is part of the range
+        #[arg(long, value_enum, default_value_t = DateBoundary::Inclusive)]
+        date_boundary: DateBoundary,
+
         /// Member ID filter
         #[arg(long)]
         member_id: Option<String>,
@@ -765,6 +905,30 @@ pub enum QueryType {
         /// Output format
         #[arg(short, long, default_value_t = OutputFormat::Text)]
         format: OutputFormat,
+
+        /// Truncate message content to N characters in text output (json/csv stay full)
+        #[arg(long, value_name = "N")]
+        preview_len: Option<usize>,
+
+        /// Show `ts` as an RFC3339 UTC string in text and CSV output instead of unix seconds
+        #[arg(long)]
+        iso_timestamps: bool,
+
+        /// Which stored sender name to display
+        #[arg(long, value_enum, default_value_t = NamePreference::Account)]
+        name_preference: NamePreference,
+
+        /// Also show N messages before and after each hit from the same chat
+        #[arg(long, value_name = "N", default_value_t = 0, conflicts_with = "follow")]
+        context: usize,
+
+        /// Keep running and print newly inserted matches until interrupted
+        #[arg(long, default_value_t = false)]
+        follow: bool,
+
+        /// Poll interval for `--follow`, in milliseconds
+        #[arg(long, default_value_t = 1000, requires = "follow")]
+        poll_interval_ms: u64,
     },
 
     /// Run SQL query
@@ -788,13 +952,131 @@ pub enum QueryType {
         #[arg(long, default_value_t = 0.7)]
         threshold: f32,
 
+        /// Keep the top X% of scored candidates instead of applying --threshold
+        #[arg(long, value_name = "PERCENT", conflicts_with = "threshold")]
+        top_percent: Option<f32>,
+
         /// Limit results
         #[arg(short, long, default_value_t = 10)]
         limit: usize,
 
+        /// Worker threads for scoring candidates (defaults to available cores)
+        #[arg(long)]
+        threads: Option<usize>,
+
+        /// Candidates sent to the embedding backend per call
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_EMBED_BATCH)]
+        embed_batch: usize,
+
+        /// Only score messages from this chat (meta ID)
+        #[arg(long)]
+        meta_id: Option<i64>,
+
+        /// Time range start
+        #[arg(long)]
+        start_date: Option<String>,
+
+        /// Time range end
+        #[arg(long)]
+        end_date: Option<String>,
+
+        /// Whether `--end-date` itself is part of the range
+        #[arg(long, value_enum, default_value_t = DateBoundary::Inclusive)]
+        date_boundary: DateBoundary,
+
+        /// Member ID filter
+        #[arg(long)]
+        member_id: Option<String>,
+
+        /// Output format
+        #[arg(short, long, default_value_t = OutputFormat::Json)]
+        format: OutputFormat,
+
+        /// Truncate message content to N characters in text output (json/csv stay full)
+        #[arg(long, value_name = "N")]
+        preview_len: Option<usize>,
+
+        /// Show `ts` as an RFC3339 UTC string in text and CSV output instead of unix seconds
+        #[arg(long)]
+        iso_timestamps: bool,
+    },
+
+    /// Precompute and store semantic search vectors for messages
+    IndexEmbeddings {
+        /// Messages embedded and written per transaction
+        #[arg(long, default_value_t = 1000)]
+        batch_size: usize,
+
+        /// Messages sent to the embedding backend per call
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_EMBED_BATCH)]
+        embed_batch: usize,
+
+        /// Drop stored vectors and embed every message again
+        #[arg(long, default_value_t = false)]
+        rebuild: bool,
+
+        /// Worker threads for embedding (defaults to available cores)
+        #[arg(long)]
+        threads: Option<usize>,
+    },
+
+    /// Keyword and semantic search with the two rankings fused
+    Hybrid {
+        /// Query text; the keyword side uses `query search` syntax
+        #[arg(required = true)]
+        query: String,
+
+        /// Weight of the keyword ranking in the fused score
+        #[arg(long, default_value_t = 1.0)]
+        lexical_weight: f32,
+
+        /// Weight of the semantic ranking in the fused score
+        #[arg(long, default_value_t = 1.0)]
+        semantic_weight: f32,
+
+        /// Minimum similarity for a semantic match to count
+        #[arg(long, default_value_t = 0.2)]
+        threshold: f32,
+
+        /// Limit results
+        #[arg(short, long, default_value_t = 10)]
+        limit: usize,
+
+        /// Worker threads for semantic scoring (defaults to available cores)
+        #[arg(long)]
+        threads: Option<usize>,
+
+        /// Candidates sent to the embedding backend per call
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_EMBED_BATCH)]
+        embed_batch: usize,
+
+        /// Only search messages from this chat (meta ID)
+        #[arg(long)]
+        meta_id: Option<i64>,
+
+        /// Time range start
+        #[arg(long)]
+        start_date: Option<String>,
+
+        /// Time range end
+        #[arg(long)]
+        end_date: Option<String>,
+
+        /// Whether `--end-date` itself is part of the range
+        #[arg(long, value_enum, default_value_t = DateBoundary::Inclusive)]
+        date_boundary: DateBoundary,
+
+        /// Member ID filter
+        #[arg(long)]
+        member_id: Option<String>,
+
         /// Output format
         #[arg(short, long, default_value_t = OutputFormat::Json)]
         format: OutputFormat,
+
+        /// Truncate message content to N characters in text output (json/csv stay full)
+        #[arg(long, value_name = "N")]
+        preview_len: Option<usize>,
     },
 }
 
@@ -887,6 +1169,16 @@ pub enum WebhookCommand {
         /// Keyword filter
         #[arg(long)]
         keyword: Option<String>,
+
+        /// Message type filter: a name (text, image, audio, video, file,
+        /// sticker, location, system, link) or its numeric code
+        #[arg(long = "msg-type")]
+        msg_type: Option<String>,
+
+        /// Shared secret; deliveries then carry an
+        /// `X-Xenobot-Signature: sha256=<hex>` HMAC of the request body
+        #[arg(long)]
+        secret: Option<String>,
     },
 
     /// List webhooks
@@ -960,6 +1252,10 @@ pub enum WebhookDispatchCommand {
         #[arg(long)]
         flush_interval_ms: Option<u64>,
 
+        /// Max time the oldest buffered event may wait before a flush, in milliseconds
+        #[arg(long)]
+        max_linger_ms: Option<u64>,
+
         /// Retry attempts for failed webhook delivery
         #[arg(long)]
         retry_attempts: Option<u32>,
@@ -968,6 +1264,131 @@ pub enum WebhookDispatchCommand {
         #[arg(long)]
         retry_base_delay_ms: Option<u64>,
 
+        /// Scale each retry delay by a random factor between 0.5x and 1.5x
+        /// (`true`/`false`) so many failing deliveries do not retry in lockstep
+        #[arg(long)]
+        retry_jitter: Option<bool>,
+
+        /// Consecutive failures before an endpoint's circuit breaker opens
+        #[arg(long)]
+        breaker_failure_threshold: Option<u32>,
+
+        /// How long an open breaker skips an endpoint before probing, in milliseconds
+        #[arg(long)]
+        breaker_cooldown_ms: Option<u64>,
+
+        /// Output format
+        #[arg(short, long, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+}
+
+/// Chat management arguments.
+#[derive(Args, Debug)]
+pub struct ChatArgs {
+    /// Database path
+    #[arg(short, long, env = "XENOBOT_DB_PATH")]
+    pub db_path: PathBuf,
+
+    /// Subcommand
+    #[command(subcommand)]
+    pub command: ChatCommand,
+}
+
+/// Chat management subcommands.
+#[derive(Subcommand, Debug)]
+pub enum ChatCommand {
+    /// Rename an imported chat
+    Rename {
+        /// Chat (meta) id
+        #[arg(required = true)]
+        meta_id: i64,
+
+        /// New chat name
+        #[arg(required = true)]
+        name: String,
+    },
+
+    /// Delete a chat with its messages, media and sessions
+    Delete {
+        /// Chat (meta) id
+        #[arg(required = true)]
+        meta_id: i64,
+
+        /// Confirm the deletion
+        #[arg(long, default_value_t = false)]
+        yes: bool,
+    },
+}
+
+/// Configuration inspection arguments.
+#[derive(Args, Debug)]
+pub struct ConfigArgs {
+    /// Subcommand
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+/// Configuration subcommands.
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Print the effective configuration after merging file, env, and flags
+    Show {
+        /// Database path override
+        #[arg(long, env = "XENOBOT_DB_PATH")]
+        db_path: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+}
+
+/// Import benchmark arguments.
+#[derive(Args, Debug)]
+pub struct BenchArgs {
+    /// Write the synthetic WhatsApp-style export to this path and keep it
+    #[arg(long)]
+    pub generate: Option<PathBuf>,
+
+    /// Number of synthetic messages to generate
+    #[arg(long, default_value_t = 10_000)]
+    pub messages: usize,
+
+    /// Time parsing and inserting the synthetic export
+    #[arg(long, default_value_t = false)]
+    pub import: bool,
+
+    /// Database to import into (defaults to a throwaway temp database)
+    #[arg(long)]
+    pub db_path: Option<PathBuf>,
+
+    /// Output format
+    #[arg(short, long, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+/// Self-test arguments.
+#[derive(Args, Debug)]
+pub struct SelftestArgs {
+    /// Output format
+    #[arg(short, long, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+/// Parser inspection arguments.
+#[derive(Args, Debug)]
+pub struct ParsersArgs {
+    /// Subcommand
+    #[command(subcommand)]
+    pub command: ParsersCommand,
+}
+
+/// Parser subcommands.
+#[derive(Subcommand, Debug)]
+pub enum ParsersCommand {
+    /// Run every registered parser against its built-in sample export
+    Test {
         /// Output format
         #[arg(short, long, default_value_t = OutputFormat::Text)]
         format: OutputFormat,
@@ -1018,11 +1439,84 @@ pub enum DbCommand {
         format: OutputFormat,
     },
 
-    /// Optimize database
+    /// Optimize database: `PRAGMA optimize`, ANALYZE and VACUUM
     Optimize {
         /// Database path
         #[arg(required = true)]
         path: PathBuf,
+
+        /// Skip ANALYZE
+        #[arg(long, default_value_t = false)]
+        no_analyze: bool,
+
+        /// Skip VACUUM, which needs free disk space about the size of the database
+        #[arg(long, default_value_t = false)]
+        no_vacuum: bool,
+
+        /// Output format
+        #[arg(short, long, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+
+    /// Rebuild FTS indexes, refresh statistics and optionally truncate the WAL
+    Maintenance {
+        /// Database path
+        #[arg(required = true)]
+        path: PathBuf,
+
+        /// Skip rebuilding FTS indexes
+        #[arg(long, default_value_t = false)]
+        skip_fts: bool,
+
+        /// Skip ANALYZE
+        #[arg(long, default_value_t = false)]
+        skip_analyze: bool,
+
+        /// Run `PRAGMA wal_checkpoint(TRUNCATE)` afterwards
+        #[arg(long, default_value_t = false)]
+        checkpoint: bool,
+
+        /// Output format
+        #[arg(short, long, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+
+    /// Collapse duplicate messages left by older imports
+    Dedup {
+        /// Database path
+        #[arg(required = true)]
+        path: PathBuf,
+
+        /// Only report what would be removed
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+
+        /// Output format
+        #[arg(short, long, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+
+    /// Run one vetted write statement (UPDATE/INSERT/DELETE) in a transaction
+    Exec {
+        /// Database path
+        #[arg(required = true)]
+        path: PathBuf,
+
+        /// A single SQL write statement
+        #[arg(required = true)]
+        sql: String,
+
+        /// Confirm the write; without it the statement is only validated
+        #[arg(long, default_value_t = false)]
+        yes: bool,
+
+        /// Also allow DROP/ATTACH/DETACH/PRAGMA and statements that remove messages
+        #[arg(long, default_value_t = false)]
+        allow_dangerous: bool,
+
+        /// Output format
+        #[arg(short, long, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
     },
 
     /// Verify core schema and hot-path indexes
@@ -1166,6 +1660,17 @@ impl std::fmt::Display for McpCallMode {
     }
 }
 
+/// Order of files within one import run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportFileOrder {
+    /// By path (default)
+    Name,
+    /// By file modification time, oldest first
+    Mtime,
+    /// By each file's earliest message timestamp
+    ContentTs,
+}
+
 /// Platform format for import.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum PlatformFormat {
@@ -1214,6 +1719,19 @@ pub enum PlatformFormat {
     Xenobot,
 }
 
+/// Where `import --write-db` looks for duplicates of an incoming message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportDedupScope {
+    /// Only within the file being imported
+    File,
+    /// Within the target chat, on `--incremental` imports (default)
+    Session,
+    /// Across every chat of the same platform, on every import
+    Platform,
+    /// Across every chat in the database, on every import
+    Global,
+}
+
 /// Export format.
 #[derive(Debug, Clone, ValueEnum)]
 pub enum ExportFormat {
@@ -1227,6 +1745,46 @@ pub enum ExportFormat {
     Json,
     /// HTML
     Html,
+    /// Markdown: one blockquote per message, grouped by sender
+    Markdown,
+    /// SubRip subtitles: one timed cue per message
+    Srt,
+    /// WebVTT subtitles: one timed cue per message
+    Vtt,
+}
+
+/// CSV field quoting mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CsvQuoting {
+    /// Quote every text field (default)
+    Always,
+    /// Quote only fields containing the delimiter, a quote or a line break
+    Minimal,
+}
+
+/// How `--end-date` bounds a date range.
+///
+/// Either way the range ends at a day start and is compared as `ts < end`, so no
+/// trailing second (or sub-second timestamp) of the last day is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DateBoundary {
+    /// Include the whole end date (default; range ends at the next day's start)
+    Inclusive,
+    /// Stop at the start of the end date
+    Exclusive,
+}
+
+/// Which stored sender name query and export output shows.
+///
+/// Messages keep both the sender's account name and, when the source export
+/// has one, the nickname they use in that group; the preferred one is shown
+/// and the other is the fallback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum NamePreference {
+    /// Account name (default)
+    Account,
+    /// Per-group nickname
+    Nickname,
 }
 
 /// Advanced analysis types.
@@ -54,5 +54,46 @@ pub enum CliError {
     Internal(String),
 }
 
+/// Exit code for failures without a more specific class.
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code for invalid arguments; matches clap's usage-error code.
+pub const EXIT_ARGUMENT: i32 = 2;
+/// Exit code for database errors.
+pub const EXIT_DATABASE: i32 = 3;
+/// Exit code for network and API communication errors.
+pub const EXIT_NETWORK: i32 = 4;
+/// Exit code for file system and IO errors.
+pub const EXIT_FILESYSTEM: i32 = 5;
+/// Exit code for configuration errors.
+pub const EXIT_CONFIG: i32 = 6;
+
+impl CliError {
+    /// Process exit code for this error, so scripts can tell user mistakes
+    /// from environment failures:
+    ///
+    /// | Code | Variants |
+    /// |------|----------|
+    /// | 1 | `Command`, `Analysis`, `WeChat`, `Parse`, `Internal` |
+    /// | 2 | `Argument` |
+    /// | 3 | `Database` |
+    /// | 4 | `Network`, `Api` |
+    /// | 5 | `FileSystem`, `Io` |
+    /// | 6 | `Config` |
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Argument(_) => EXIT_ARGUMENT,
+            CliError::Database(_) => EXIT_DATABASE,
+            CliError::Network(_) | CliError::Api(_) => EXIT_NETWORK,
+            CliError::FileSystem(_) | CliError::Io(_) => EXIT_FILESYSTEM,
+            CliError::Config(_) => EXIT_CONFIG,
+            CliError::Command(_)
+            | CliError::Analysis(_)
+            | CliError::WeChat(_)
+            | CliError::Parse(_)
+            | CliError::Internal(_) => EXIT_FAILURE,
+        }
+    }
+}
+
 /// Result alias for CLI operations.
 pub type Result<T> = std::result::Result<T, CliError>;
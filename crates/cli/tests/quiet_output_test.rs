@@ -0,0 +1,74 @@
+//! `--quiet` output contract: machine formats leave stdout parseable.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn unique_test_root(name: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system time")
+        .as_nanos();
+    std::env::temp_dir().join(format!(
+        "xenobot-cli-{}-{}-{}",
+        name,
+        std::process::id(),
+        nanos
+    ))
+}
+
+fn run_cli(root: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_xenobot-cli"))
+        .args(args)
+        .env("HOME", root)
+        .env("XDG_CONFIG_HOME", root.join("config"))
+        .env("XDG_DATA_HOME", root.join("data"))
+        .env("XENOBOT_DB_PATH", root.join("xenobot.db"))
+        .env_remove("XENOBOT_CONFIG")
+        .env_remove("XENOBOT_WORK_DIR")
+        .output()
+        .expect("run xenobot-cli")
+}
+
+fn single_json_document(stdout: &[u8]) -> serde_json::Value {
+    let text = std::str::from_utf8(stdout).expect("utf-8 stdout");
+    let mut documents = serde_json::Deserializer::from_str(text).into_iter::<serde_json::Value>();
+    let first = documents
+        .next()
+        .expect("stdout holds a JSON document")
+        .unwrap_or_else(|err| panic!("stdout is not JSON ({err}): {text}"));
+    assert!(
+        documents.next().is_none(),
+        "stdout holds trailing output after the JSON document: {text}"
+    );
+    first
+}
+
+#[test]
+fn quiet_json_output_is_a_single_parseable_document() {
+    let root = unique_test_root("quiet-json");
+    std::fs::create_dir_all(&root).expect("create test root");
+
+    for format in ["json", "yaml"] {
+        let output = run_cli(&root, &["--quiet", "config", "show", "--format", format]);
+        assert!(
+            output.status.success(),
+            "config show --format {format} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let document = single_json_document(&output.stdout);
+        assert!(document.get("db_path").is_some());
+        assert!(
+            output.stderr.is_empty(),
+            "quiet run wrote to stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    // Without --quiet the yaml fallback note goes to stderr, never stdout.
+    let output = run_cli(&root, &["config", "show", "--format", "yaml"]);
+    assert!(output.status.success());
+    single_json_document(&output.stdout);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("note: yaml renderer"));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
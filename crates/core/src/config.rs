@@ -42,6 +42,7 @@ pub struct XenobotConfig {
 
 /// HTTP server configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct HttpConfig {
     /// Host to bind to.
     pub host: String,
@@ -60,6 +61,16 @@ pub struct HttpConfig {
 
     /// Enable request logging.
     pub enable_request_logging: bool,
+
+    /// Proxy for outbound requests (webhooks, LLM calls). Overrides
+    /// `HTTP_PROXY` / `HTTPS_PROXY`; `XENOBOT_HTTP_PROXY` overrides it.
+    #[serde(default)]
+    pub outbound_proxy: Option<String>,
+
+    /// PEM file with extra trusted root certificates for outbound requests.
+    /// `XENOBOT_CA_BUNDLE` overrides it.
+    #[serde(default)]
+    pub ca_bundle_path: Option<PathBuf>,
 }
 
 /// Database configuration.
@@ -82,6 +93,20 @@ pub struct DatabaseConfig {
 
     /// Auto-vacuum mode.
     pub auto_vacuum: AutoVacuumMode,
+
+    /// Root directory for imported media files.
+    ///
+    /// Media paths are stored relative to this root so a database and its
+    /// media folder can be moved together. Defaults to `media/` next to the
+    /// database file when unset.
+    #[serde(default)]
+    pub media_root: Option<PathBuf>,
+
+    /// WAL auto-checkpoint threshold in pages (`PRAGMA wal_autocheckpoint`).
+    ///
+    /// Uses SQLite's built-in default (1000 pages) when unset.
+    #[serde(default)]
+    pub wal_autocheckpoint: Option<u32>,
 }
 
 /// Auto-vacuum mode for SQLite.
@@ -235,7 +260,7 @@ pub struct WindowsConfig {
 }
 
 /// Linux-specific configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LinuxConfig {
     /// Use Wayland display server.
     pub use_wayland: bool,
@@ -385,6 +410,11 @@ impl XenobotConfig {
         toml::from_str(&content).map_err(|e| Error::Parse(format!("Failed to parse config: {}", e)))
     }
 
+    /// Default config file location: `<config dir>/xenobot/config.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("xenobot").join("config.toml"))
+    }
+
     /// Save configuration to file.
     pub fn save(&self, path: &PathBuf) -> Result<()> {
         let content = toml::to_string_pretty(self)
@@ -404,6 +434,8 @@ impl Default for HttpConfig {
             enable_cors: true,
             request_timeout: 30,
             enable_request_logging: true,
+            outbound_proxy: None,
+            ca_bundle_path: None,
         }
     }
 }
@@ -417,6 +449,8 @@ impl Default for DatabaseConfig {
             enable_wal: true,
             enable_foreign_keys: true,
             auto_vacuum: AutoVacuumMode::Incremental,
+            media_root: None,
+            wal_autocheckpoint: None,
         }
     }
 }
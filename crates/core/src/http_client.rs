@@ -0,0 +1,268 @@
+//! Outbound HTTP client construction.
+//!
+//! Webhook dispatch, dead-letter replay and LLM calls all build their
+//! `reqwest` clients here so proxy and trust settings apply uniformly.
+//! Settings come from the `[http]` section of the config file
+//! (`outbound_proxy`, `ca_bundle_path`) and from the environment, which
+//! takes precedence:
+//!
+//! - `XENOBOT_HTTP_PROXY`: proxy for every outbound request (overrides
+//!   `outbound_proxy` and the standard variables below).
+//! - `HTTP_PROXY` / `HTTPS_PROXY` (or lowercase): per-scheme proxies, used
+//!   when no Xenobot proxy is set.
+//! - `NO_PROXY` (or lowercase): hosts that bypass the proxy.
+//! - `XENOBOT_CA_BUNDLE`: PEM file with extra trusted root certificates
+//!   (overrides `ca_bundle_path`).
+
+use crate::config::{HttpConfig, XenobotConfig};
+use crate::{Error, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Proxy and TLS trust settings for outbound requests.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutboundHttpConfig {
+    /// Proxy URL for plain `http://` requests.
+    pub http_proxy: Option<String>,
+    /// Proxy URL for `https://` requests.
+    pub https_proxy: Option<String>,
+    /// Comma-separated hosts, domains or CIDRs that bypass the proxies.
+    pub no_proxy: Option<String>,
+    /// PEM bundle of additional trusted root certificates.
+    pub ca_bundle_path: Option<PathBuf>,
+}
+
+/// Only the `[http]` table of the config file matters here.
+#[derive(Deserialize, Default)]
+struct HttpSection {
+    #[serde(default)]
+    http: HttpConfig,
+}
+
+/// `[http]` section of the config file at `path`. A missing file or
+/// section yields the defaults.
+fn load_http_config(path: &Path) -> Result<HttpConfig> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HttpConfig::default()),
+        Err(e) => {
+            return Err(Error::FileSystem(format!(
+                "Failed to read config file {}: {e}",
+                path.display()
+            )))
+        }
+    };
+    toml::from_str::<HttpSection>(&content)
+        .map(|section| section.http)
+        .map_err(|e| Error::Parse(format!("Failed to parse config {}: {e}", path.display())))
+}
+
+impl OutboundHttpConfig {
+    /// Read settings from the default config file and the process
+    /// environment.
+    pub fn from_env() -> Result<Self> {
+        let http = match XenobotConfig::default_path() {
+            Some(path) => load_http_config(&path)?,
+            None => HttpConfig::default(),
+        };
+        Ok(Self::from_config(&http, |name| std::env::var(name).ok()))
+    }
+
+    /// Read settings through `lookup`, which returns a variable's value.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        Self::from_config(&HttpConfig::default(), lookup)
+    }
+
+    /// Combine `http` with the variables returned by `lookup`; a variable
+    /// overrides the matching config value.
+    pub fn from_config(http: &HttpConfig, lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let first = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                lookup(name)
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+            })
+        };
+        let configured = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let global = first(&["XENOBOT_HTTP_PROXY"]).or_else(|| configured(&http.outbound_proxy));
+        Self {
+            http_proxy: global
+                .clone()
+                .or_else(|| first(&["HTTP_PROXY", "http_proxy"])),
+            https_proxy: global.or_else(|| first(&["HTTPS_PROXY", "https_proxy"])),
+            no_proxy: first(&["NO_PROXY", "no_proxy"]),
+            ca_bundle_path: first(&["XENOBOT_CA_BUNDLE"])
+                .map(PathBuf::from)
+                .or_else(|| http.ca_bundle_path.clone()),
+        }
+    }
+
+    /// Client builder with these settings applied. Callers add their own
+    /// timeout before `build()`.
+    ///
+    /// Only the proxies resolved here are installed; reqwest's implicit
+    /// system proxy lookup is disabled so both paths cannot disagree.
+    pub fn client_builder(&self) -> Result<reqwest::ClientBuilder> {
+        let mut builder = reqwest::Client::builder().no_proxy();
+        let no_proxy = self
+            .no_proxy
+            .as_deref()
+            .and_then(reqwest::NoProxy::from_string);
+
+        if let Some(url) = &self.http_proxy {
+            let proxy = reqwest::Proxy::http(url)
+                .map_err(|e| Error::Config(format!("invalid http proxy {url}: {e}")))?;
+            builder = builder.proxy(proxy.no_proxy(no_proxy.clone()));
+        }
+        if let Some(url) = &self.https_proxy {
+            let proxy = reqwest::Proxy::https(url)
+                .map_err(|e| Error::Config(format!("invalid https proxy {url}: {e}")))?;
+            builder = builder.proxy(proxy.no_proxy(no_proxy));
+        }
+        if let Some(path) = &self.ca_bundle_path {
+            let pem = std::fs::read(path).map_err(|e| {
+                Error::Config(format!("failed to read CA bundle {}: {e}", path.display()))
+            })?;
+            let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| {
+                Error::Config(format!("invalid CA bundle {}: {e}", path.display()))
+            })?;
+            if certs.is_empty() {
+                return Err(Error::Config(format!(
+                    "CA bundle {} contains no certificates",
+                    path.display()
+                )));
+            }
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        Ok(builder)
+    }
+}
+
+/// Client builder configured from the config file and the environment; see
+/// the module docs.
+pub fn outbound_client_builder() -> Result<reqwest::ClientBuilder> {
+    OutboundHttpConfig::from_env()?.client_builder()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn proxy_env_vars_configure_the_client() {
+        let config = OutboundHttpConfig::from_lookup(lookup(&[
+            ("HTTPS_PROXY", "http://proxy.corp.example:3128"),
+            ("no_proxy", "localhost,127.0.0.1"),
+        ]));
+        assert_eq!(
+            config,
+            OutboundHttpConfig {
+                http_proxy: None,
+                https_proxy: Some("http://proxy.corp.example:3128".to_string()),
+                no_proxy: Some("localhost,127.0.0.1".to_string()),
+                ca_bundle_path: None,
+            }
+        );
+        config
+            .client_builder()
+            .expect("builder")
+            .build()
+            .expect("client");
+
+        assert_eq!(
+            OutboundHttpConfig::from_lookup(lookup(&[])),
+            OutboundHttpConfig::default()
+        );
+    }
+
+    #[test]
+    fn config_file_settings_apply_unless_env_overrides_them() {
+        let path =
+            std::env::temp_dir().join(format!("xenobot_http_client_{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "[http]\noutbound_proxy = \"http://configured:3128\"\n\
+             ca_bundle_path = \"/etc/ssl/configured.pem\"\n",
+        )
+        .expect("write config");
+        let http = load_http_config(&path).expect("load config");
+        std::fs::remove_file(&path).ok();
+        assert_eq!(http.port, HttpConfig::default().port);
+
+        let config = OutboundHttpConfig::from_config(
+            &http,
+            lookup(&[
+                ("HTTPS_PROXY", "http://ignored:1"),
+                ("NO_PROXY", "localhost"),
+            ]),
+        );
+        assert_eq!(
+            config,
+            OutboundHttpConfig {
+                http_proxy: Some("http://configured:3128".to_string()),
+                https_proxy: Some("http://configured:3128".to_string()),
+                no_proxy: Some("localhost".to_string()),
+                ca_bundle_path: Some(PathBuf::from("/etc/ssl/configured.pem")),
+            }
+        );
+
+        let config = OutboundHttpConfig::from_config(
+            &http,
+            lookup(&[
+                ("XENOBOT_HTTP_PROXY", "http://env:8080"),
+                ("XENOBOT_CA_BUNDLE", "/etc/ssl/env.pem"),
+            ]),
+        );
+        assert_eq!(config.http_proxy.as_deref(), Some("http://env:8080"));
+        assert_eq!(config.https_proxy.as_deref(), Some("http://env:8080"));
+        assert_eq!(
+            config.ca_bundle_path,
+            Some(PathBuf::from("/etc/ssl/env.pem"))
+        );
+
+        assert_eq!(
+            load_http_config(&path)
+                .expect("missing file")
+                .outbound_proxy,
+            None
+        );
+    }
+
+    #[test]
+    fn xenobot_proxy_overrides_per_scheme_proxies() {
+        let config = OutboundHttpConfig::from_lookup(lookup(&[
+            ("XENOBOT_HTTP_PROXY", "http://gateway:8080"),
+            ("HTTP_PROXY", "http://ignored:1"),
+            ("XENOBOT_CA_BUNDLE", "  "),
+        ]));
+        assert_eq!(config.http_proxy.as_deref(), Some("http://gateway:8080"));
+        assert_eq!(config.https_proxy.as_deref(), Some("http://gateway:8080"));
+        assert_eq!(config.ca_bundle_path, None);
+    }
+
+    #[test]
+    fn unreadable_ca_bundle_is_a_config_error() {
+        let config = OutboundHttpConfig {
+            ca_bundle_path: Some(PathBuf::from("/nonexistent/xenobot-ca.pem")),
+            ..OutboundHttpConfig::default()
+        };
+        assert!(matches!(config.client_builder(), Err(Error::Config(_))));
+    }
+}
@@ -7,6 +7,7 @@
 pub mod config;
 pub mod constants;
 pub mod error;
+pub mod http_client;
 pub mod monitor;
 pub mod platform_capabilities;
 pub mod platform_sources;
@@ -3,10 +3,14 @@
 //! This module keeps webhook event matching behavior consistent across
 //! CLI and API import pipelines.
 
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
 use std::fs::{self, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 /// Persisted webhook rule.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,7 +23,13 @@ pub struct WebhookRule {
     pub meta_id: Option<i64>,
     pub sender: Option<String>,
     pub keyword: Option<String>,
+    /// Stored message type code (`0` text, `1` image, ...).
+    #[serde(default)]
+    pub msg_type: Option<i64>,
     pub created_at: Option<String>,
+    /// Shared secret for signing deliveries; see [`signed_webhook_request`].
+    #[serde(default)]
+    pub secret: Option<String>,
 }
 
 /// Message-created webhook event payload.
@@ -45,6 +55,13 @@ pub struct WebhookDispatchStats {
     pub delivered: usize,
     pub failed: usize,
     pub filtered: usize,
+    /// Times an endpoint's circuit breaker opened.
+    #[serde(default)]
+    pub breaker_trips: usize,
+    /// Deliveries dead-lettered without a network attempt because the
+    /// endpoint's breaker was open (also counted in `failed`).
+    #[serde(default)]
+    pub short_circuited: usize,
 }
 
 /// Dead-letter entry for failed webhook deliveries.
@@ -162,6 +179,179 @@ pub fn merge_webhook_dispatch_stats(
     target.delivered = target.delivered.saturating_add(delta.delivered);
     target.failed = target.failed.saturating_add(delta.failed);
     target.filtered = target.filtered.saturating_add(delta.filtered);
+    target.breaker_trips = target.breaker_trips.saturating_add(delta.breaker_trips);
+    target.short_circuited = target.short_circuited.saturating_add(delta.short_circuited);
+}
+
+/// Whether a delivery to an endpoint may go out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerAdmission {
+    /// Breaker closed; deliver normally.
+    Closed,
+    /// Cooldown elapsed; this delivery is the single half-open probe.
+    Probe,
+    /// Breaker open; skip the network and dead-letter the event.
+    Open,
+}
+
+#[derive(Debug, Default)]
+struct EndpointBreaker {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probing: bool,
+}
+
+/// Per-endpoint circuit breaker keyed by webhook id.
+///
+/// After `failure_threshold` consecutive failed deliveries the endpoint is
+/// opened for `cooldown`; afterwards one probe is let through, which either
+/// closes the breaker on success or re-opens it on failure.
+#[derive(Debug)]
+pub struct WebhookCircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    endpoints: HashMap<String, EndpointBreaker>,
+}
+
+impl WebhookCircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            endpoints: HashMap::new(),
+        }
+    }
+
+    pub fn admit(&mut self, webhook_id: &str, now: Instant) -> BreakerAdmission {
+        let cooldown = self.cooldown;
+        let Some(endpoint) = self.endpoints.get_mut(webhook_id) else {
+            return BreakerAdmission::Closed;
+        };
+        match endpoint.opened_at {
+            None => BreakerAdmission::Closed,
+            Some(_) if endpoint.probing => BreakerAdmission::Open,
+            Some(opened_at) if now.saturating_duration_since(opened_at) >= cooldown => {
+                endpoint.probing = true;
+                BreakerAdmission::Probe
+            }
+            Some(_) => BreakerAdmission::Open,
+        }
+    }
+
+    pub fn record_success(&mut self, webhook_id: &str) {
+        self.endpoints.remove(webhook_id);
+    }
+
+    /// Records a failed delivery; returns `true` when this failure opened the breaker.
+    pub fn record_failure(&mut self, webhook_id: &str, now: Instant) -> bool {
+        let endpoint = self.endpoints.entry(webhook_id.to_string()).or_default();
+        endpoint.consecutive_failures = endpoint.consecutive_failures.saturating_add(1);
+        if endpoint.probing {
+            endpoint.probing = false;
+            endpoint.opened_at = Some(now);
+            return true;
+        }
+        if endpoint.opened_at.is_none() && endpoint.consecutive_failures >= self.failure_threshold {
+            endpoint.opened_at = Some(now);
+            return true;
+        }
+        false
+    }
+}
+
+/// Header carrying the delivery signature.
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Xenobot-Signature";
+
+/// `sha256=` followed by the lowercase hex HMAC-SHA256 of `body` keyed with
+/// the UTF-8 bytes of `secret`.
+pub fn webhook_signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Build the POST for one event delivery.
+///
+/// The body is the compact JSON encoding of `event`. With a non-empty
+/// `secret`, [`WEBHOOK_SIGNATURE_HEADER`] is set to [`webhook_signature`]
+/// over exactly those body bytes, so receivers must verify the raw body
+/// before re-parsing it.
+pub fn signed_webhook_request(
+    client: &reqwest::Client,
+    url: &str,
+    webhook_id: &str,
+    event: &WebhookMessageCreatedEvent,
+    secret: Option<&str>,
+) -> reqwest::Result<reqwest::Request> {
+    let mut request = client
+        .post(url)
+        .header("X-Xenobot-Event", &event.event_type)
+        .header("X-Xenobot-Webhook-Id", webhook_id)
+        .json(event)
+        .build()?;
+    if let Some(secret) = secret.filter(|secret| !secret.is_empty()) {
+        let body = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .unwrap_or_default();
+        let signature = webhook_signature(secret, body);
+        if let Ok(value) = reqwest::header::HeaderValue::from_str(&signature) {
+            request
+                .headers_mut()
+                .insert(WEBHOOK_SIGNATURE_HEADER, value);
+        }
+    }
+    Ok(request)
+}
+
+/// Send one event delivery built by [`signed_webhook_request`].
+pub async fn send_webhook_event(
+    client: &reqwest::Client,
+    url: &str,
+    webhook_id: &str,
+    event: &WebhookMessageCreatedEvent,
+    secret: Option<&str>,
+) -> reqwest::Result<reqwest::Response> {
+    let request = signed_webhook_request(client, url, webhook_id, event, secret)?;
+    client.execute(request).await
+}
+
+/// Retry schedule for one webhook delivery, shared by the CLI and API
+/// dispatchers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebhookRetryPolicy {
+    pub attempts: u32,
+    pub base_delay_ms: u64,
+    pub jitter: bool,
+}
+
+impl WebhookRetryPolicy {
+    /// Sleep after failed attempt `attempt` (0-based): `base * 2^attempt`,
+    /// with the exponent capped at 10, then scaled by a random factor in
+    /// `[0.5, 1.5)` when jitter is on.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.delay_with_unit(attempt, random_unit_interval())
+    }
+
+    fn delay_with_unit(&self, attempt: u32, unit: f64) -> Duration {
+        let delay_ms = self
+            .base_delay_ms
+            .max(1)
+            .saturating_mul(1_u64 << attempt.min(10));
+        if !self.jitter {
+            return Duration::from_millis(delay_ms);
+        }
+        Duration::from_millis((delay_ms as f64 * (0.5 + unit)) as u64)
+    }
+}
+
+/// Uniform value in `[0, 1)`. `RandomState` draws fresh random keys for each
+/// instance, which is enough to spread retries apart.
+fn random_unit_interval() -> f64 {
+    use std::hash::BuildHasher;
+    let bits = std::collections::hash_map::RandomState::new().hash_one(());
+    (bits >> 11) as f64 / (1_u64 << 53) as f64
 }
 
 /// Returns true when a rule should receive the message-created event.
@@ -208,6 +398,12 @@ pub fn webhook_rule_matches_event(rule: &WebhookRule, event: &WebhookMessageCrea
         }
     }
 
+    if let Some(expected_msg_type) = rule.msg_type {
+        if expected_msg_type != event.msg_type {
+            return false;
+        }
+    }
+
     let sender_rule = rule
         .sender
         .as_deref()
@@ -250,6 +446,32 @@ pub fn webhook_rule_matches_event(rule: &WebhookRule, event: &WebhookMessageCrea
 mod tests {
     use super::*;
 
+    #[test]
+    fn webhook_retry_delay_doubles_and_jitters_within_half() {
+        let fixed = WebhookRetryPolicy {
+            attempts: 3,
+            base_delay_ms: 150,
+            jitter: false,
+        };
+        let ms = |delay: Duration| delay.as_millis() as u64;
+        assert_eq!(ms(fixed.delay(0)), 150);
+        assert_eq!(ms(fixed.delay(2)), 600);
+        assert_eq!(ms(fixed.delay(40)), 150 * 1024);
+
+        let jittered = WebhookRetryPolicy {
+            jitter: true,
+            ..fixed
+        };
+        assert_eq!(ms(jittered.delay_with_unit(1, 0.0)), 150);
+        assert_eq!(ms(jittered.delay_with_unit(1, 0.5)), 300);
+        assert_eq!(ms(jittered.delay_with_unit(1, 0.999_999)), 449);
+        for _ in 0..200 {
+            let unit = random_unit_interval();
+            assert!((0.0..1.0).contains(&unit));
+            assert!((150..450).contains(&ms(jittered.delay(1))));
+        }
+    }
+
     #[test]
     fn webhook_rule_match_by_event_sender_and_keyword() {
         let rule = WebhookRule {
@@ -261,7 +483,9 @@ mod tests {
             meta_id: None,
             sender: Some("alice".to_string()),
             keyword: Some("urgent".to_string()),
+            msg_type: None,
             created_at: Some("2026-02-23T00:00:00Z".to_string()),
+            secret: None,
         };
         let ok = WebhookMessageCreatedEvent {
             event_type: "message.created".to_string(),
@@ -294,7 +518,9 @@ mod tests {
             meta_id: None,
             sender: None,
             keyword: None,
+            msg_type: None,
             created_at: None,
+            secret: None,
         };
         let event = WebhookMessageCreatedEvent {
             event_type: "message.created".to_string(),
@@ -324,18 +550,47 @@ mod tests {
             delivered: 1,
             failed: 0,
             filtered: 2,
+            breaker_trips: 0,
+            short_circuited: 0,
         };
         let delta = WebhookDispatchStats {
             attempted: 3,
             delivered: 1,
             failed: 2,
             filtered: 4,
+            breaker_trips: 1,
+            short_circuited: 5,
         };
         merge_webhook_dispatch_stats(&mut total, &delta);
         assert_eq!(total.attempted, 4);
         assert_eq!(total.delivered, 2);
         assert_eq!(total.failed, 2);
         assert_eq!(total.filtered, 6);
+        assert_eq!(total.breaker_trips, 1);
+        assert_eq!(total.short_circuited, 5);
+    }
+
+    #[test]
+    fn circuit_breaker_opens_after_threshold_and_probes_after_cooldown() {
+        let mut breaker = WebhookCircuitBreaker::new(2, Duration::from_secs(10));
+        let start = Instant::now();
+
+        assert_eq!(breaker.admit("wh", start), BreakerAdmission::Closed);
+        assert!(!breaker.record_failure("wh", start));
+        assert!(breaker.record_failure("wh", start));
+        assert_eq!(breaker.admit("wh", start), BreakerAdmission::Open);
+        assert_eq!(breaker.admit("other", start), BreakerAdmission::Closed);
+
+        let later = start + Duration::from_secs(11);
+        assert_eq!(breaker.admit("wh", later), BreakerAdmission::Probe);
+        assert_eq!(breaker.admit("wh", later), BreakerAdmission::Open);
+        assert!(breaker.record_failure("wh", later));
+        assert_eq!(breaker.admit("wh", later), BreakerAdmission::Open);
+
+        let much_later = later + Duration::from_secs(11);
+        assert_eq!(breaker.admit("wh", much_later), BreakerAdmission::Probe);
+        breaker.record_success("wh");
+        assert_eq!(breaker.admit("wh", much_later), BreakerAdmission::Closed);
     }
 
     #[test]
@@ -349,7 +604,9 @@ mod tests {
             meta_id: Some(42),
             sender: None,
             keyword: None,
+            msg_type: None,
             created_at: None,
+            secret: None,
         };
         let event = WebhookMessageCreatedEvent {
             event_type: "message.created".to_string(),
@@ -386,4 +643,101 @@ mod tests {
             }
         ));
     }
+
+    #[test]
+    fn webhook_rule_match_by_platform_and_msg_type() {
+        let rule = WebhookRule {
+            id: "wh_4".to_string(),
+            url: "http://127.0.0.1:65535/hook".to_string(),
+            event_type: None,
+            platform: Some("wechat".to_string()),
+            chat_name: None,
+            meta_id: None,
+            sender: None,
+            keyword: None,
+            msg_type: Some(1),
+            created_at: None,
+            secret: None,
+        };
+        let image = WebhookMessageCreatedEvent {
+            event_type: "message.created".to_string(),
+            platform: "wechat".to_string(),
+            chat_name: "Family".to_string(),
+            meta_id: 3,
+            message_id: 7,
+            sender_id: 2,
+            sender_name: None,
+            ts: 1_771_800_000,
+            msg_type: 1,
+            content: None,
+        };
+        let text = WebhookMessageCreatedEvent {
+            msg_type: 0,
+            ..image.clone()
+        };
+        let other_platform = WebhookMessageCreatedEvent {
+            platform: "telegram".to_string(),
+            ..image.clone()
+        };
+        assert!(webhook_rule_matches_event(&rule, &image));
+        assert!(!webhook_rule_matches_event(&rule, &text));
+        assert!(!webhook_rule_matches_event(&rule, &other_platform));
+
+        let unfiltered = WebhookRule {
+            platform: None,
+            msg_type: None,
+            ..rule
+        };
+        for event in [&image, &text, &other_platform] {
+            assert!(webhook_rule_matches_event(&unfiltered, event));
+        }
+    }
+
+    #[test]
+    fn signed_requests_carry_an_hmac_of_the_exact_body() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            webhook_signature("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        let event = WebhookMessageCreatedEvent {
+            event_type: "message.created".to_string(),
+            platform: "telegram".to_string(),
+            chat_name: "Ops".to_string(),
+            meta_id: 1,
+            message_id: 2,
+            sender_id: 3,
+            sender_name: Some("Alice".to_string()),
+            ts: 1_771_800_000,
+            msg_type: 0,
+            content: Some("héllo \"there\"".to_string()),
+        };
+        let client = reqwest::Client::new();
+        let request = signed_webhook_request(
+            &client,
+            "http://127.0.0.1:9/hook",
+            "wh_1",
+            &event,
+            Some("s3"),
+        )
+        .expect("request");
+        let body = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .expect("body");
+        assert_eq!(body, serde_json::to_vec(&event).expect("json").as_slice());
+        assert_eq!(
+            request.headers()[WEBHOOK_SIGNATURE_HEADER],
+            webhook_signature("s3", body).as_str()
+        );
+        assert_eq!(request.headers()["X-Xenobot-Webhook-Id"], "wh_1");
+
+        for secret in [None, Some("")] {
+            let unsigned =
+                signed_webhook_request(&client, "http://127.0.0.1:9/hook", "wh_1", &event, secret)
+                    .expect("request");
+            assert!(!unsigned.headers().contains_key(WEBHOOK_SIGNATURE_HEADER));
+        }
+    }
 }
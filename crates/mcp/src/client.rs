@@ -13,9 +13,29 @@ use serde_json::Value;
 use std::collections::VecDeque;
 use std::time::Duration;
 use tokio::net::TcpStream;
+use tokio::sync::watch;
 use tokio_tungstenite::{
     connect_async, tungstenite::protocol::Message, MaybeTlsStream, WebSocketStream,
 };
+use tracing::{info, warn};
+
+/// Transport connection state, observable through [`McpClient::watch_connection_state`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Never connected, or disconnected explicitly.
+    Disconnected,
+    /// Transport is up.
+    Connected,
+    /// The connection dropped and a reconnection attempt is pending.
+    Reconnecting {
+        /// 1-based attempt number.
+        attempt: u32,
+        /// Configured attempt limit.
+        max_attempts: u32,
+    },
+    /// Every reconnection attempt failed; call [`McpClient::connect`] to retry.
+    Failed,
+}
 
 #[derive(Debug, Clone)]
 enum ResponseMatcher {
@@ -79,6 +99,12 @@ pub struct McpClient {
     next_message_id: u64,
     /// Inbound messages buffered while waiting for a different response.
     pending_messages: VecDeque<McpMessage>,
+    /// Outgoing messages held until a dropped WebSocket is re-established.
+    outbound_buffer: VecDeque<McpMessage>,
+    /// Current transport state, shared with state watchers.
+    connection_state: watch::Sender<ConnectionState>,
+    /// Number of successful reconnections since creation.
+    reconnect_count: u32,
 }
 
 impl McpClient {
@@ -93,9 +119,31 @@ impl McpClient {
             server_capabilities: None,
             next_message_id: 1,
             pending_messages: VecDeque::new(),
+            outbound_buffer: VecDeque::new(),
+            connection_state: watch::channel(ConnectionState::Disconnected).0,
+            reconnect_count: 0,
         }
     }
 
+    /// Current transport state.
+    pub fn connection_state(&self) -> ConnectionState {
+        *self.connection_state.borrow()
+    }
+
+    /// Subscribe to transport state changes (connect, drop, reconnect attempts).
+    pub fn watch_connection_state(&self) -> watch::Receiver<ConnectionState> {
+        self.connection_state.subscribe()
+    }
+
+    /// Number of times a dropped connection was re-established.
+    pub fn reconnect_count(&self) -> u32 {
+        self.reconnect_count
+    }
+
+    fn set_connection_state(&self, state: ConnectionState) {
+        self.connection_state.send_replace(state);
+    }
+
     /// Connect to the MCP server.
     pub async fn connect(&mut self) -> Result<()> {
         let url = self.config.server_url.clone();
@@ -120,6 +168,101 @@ impl McpClient {
         self.ws_connection = Some(ws_stream);
         self.http_client = None;
         self.http_base_url = None;
+        self.set_connection_state(ConnectionState::Connected);
+        Ok(())
+    }
+
+    /// Re-establish a dropped WebSocket with exponential backoff, replay the
+    /// `initialize` handshake if it had completed, then flush buffered
+    /// outgoing messages.
+    async fn reconnect_with_backoff(&mut self, reason: &str) -> Result<()> {
+        let url = self.config.server_url.clone();
+        let was_initialized = self.initialized;
+        let max_attempts = self.config.max_reconnection_attempts;
+        self.ws_connection = None;
+        self.initialized = false;
+        warn!("MCP connection to {} dropped: {}", url, reason);
+
+        let mut last_error = reason.to_string();
+        for attempt in 1..=max_attempts {
+            self.set_connection_state(ConnectionState::Reconnecting {
+                attempt,
+                max_attempts,
+            });
+            tokio::time::sleep(self.config.reconnection_backoff(attempt)).await;
+
+            if let Err(err) = self.connect_websocket(&url).await {
+                last_error = err.to_string();
+                continue;
+            }
+            if was_initialized {
+                if let Err(err) = self.initialize_ws().await {
+                    last_error = err.to_string();
+                    self.ws_connection = None;
+                    continue;
+                }
+            }
+            if let Err(err) = self.flush_outbound_buffer().await {
+                last_error = err.to_string();
+                self.ws_connection = None;
+                self.initialized = false;
+                continue;
+            }
+
+            self.reconnect_count = self.reconnect_count.saturating_add(1);
+            info!(
+                "MCP connection to {} restored after {} attempt(s)",
+                url, attempt
+            );
+            return Ok(());
+        }
+
+        self.outbound_buffer.clear();
+        self.set_connection_state(ConnectionState::Failed);
+        Err(McpError::Network(format!(
+            "Reconnection failed after {} attempt(s): {}",
+            max_attempts, last_error
+        )))
+    }
+
+    /// Replay the `initialize` handshake on a fresh WebSocket without
+    /// triggering another reconnect.
+    async fn initialize_ws(&mut self) -> Result<()> {
+        let request = McpMessage::InitializeRequest(self.initialize_request());
+        self.write_ws_message(&request).await?;
+        match self
+            .wait_for_matching_response(ResponseMatcher::Initialize)
+            .await?
+        {
+            McpMessage::InitializeResponse(response) => {
+                self.initialized = true;
+                self.server_capabilities = Some(response.capabilities);
+                Ok(())
+            }
+            _ => Err(McpError::Protocol(
+                "Initialization failed while reconnecting".to_string(),
+            )),
+        }
+    }
+
+    async fn flush_outbound_buffer(&mut self) -> Result<()> {
+        while let Some(message) = self.outbound_buffer.pop_front() {
+            if let Err(err) = self.write_ws_message(&message).await {
+                self.outbound_buffer.push_front(message);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    fn buffer_outgoing(&mut self, message: McpMessage) -> Result<()> {
+        if self.outbound_buffer.len() >= self.config.reconnect_buffer_size {
+            return Err(McpError::Network(format!(
+                "Reconnect buffer is full ({} message(s))",
+                self.config.reconnect_buffer_size
+            )));
+        }
+        self.outbound_buffer.push_back(message);
         Ok(())
     }
 
@@ -139,6 +282,7 @@ impl McpClient {
                 self.http_client = Some(client);
                 self.http_base_url = Some(base_url);
                 self.ws_connection = None;
+                self.set_connection_state(ConnectionState::Connected);
                 return Ok(());
             }
         }
@@ -160,6 +304,7 @@ impl McpClient {
         self.http_client = Some(client);
         self.http_base_url = Some(base_url);
         self.ws_connection = None;
+        self.set_connection_state(ConnectionState::Connected);
         Ok(())
     }
 
@@ -169,15 +314,7 @@ impl McpClient {
             return Ok(());
         }
 
-        let request = InitializeRequest {
-            protocol_version: crate::protocol::MCP_PROTOCOL_VERSION.to_string(),
-            capabilities: ClientCapabilities::default(),
-            client_info: Some(ClientInfo {
-                name: self.config.name.clone(),
-                version: self.config.version.clone(),
-            }),
-        };
-
+        let request = self.initialize_request();
         let response_msg = self
             .send_message(McpMessage::InitializeRequest(request))
             .await?;
@@ -198,7 +335,22 @@ impl McpClient {
         }
     }
 
+    fn initialize_request(&self) -> InitializeRequest {
+        InitializeRequest {
+            protocol_version: crate::protocol::MCP_PROTOCOL_VERSION.to_string(),
+            capabilities: ClientCapabilities::default(),
+            client_info: Some(ClientInfo {
+                name: self.config.name.clone(),
+                version: self.config.version.clone(),
+            }),
+        }
+    }
+
     /// Send a message to the server and wait for response.
+    ///
+    /// Over WebSocket with `auto_reconnect`, a connection that drops before the
+    /// response arrives is re-established and the request is sent again, so
+    /// handlers should tolerate seeing a request twice.
     pub async fn send_message(&mut self, message: McpMessage) -> Result<McpMessage> {
         if self.ws_connection.is_some() {
             self.send_message_ws(message).await
@@ -222,7 +374,15 @@ impl McpClient {
             }));
         }
 
-        self.wait_for_matching_response(matcher).await
+        match self.wait_for_matching_response(matcher.clone()).await {
+            Err(McpError::Network(reason)) if self.config.auto_reconnect => {
+                // The server went away before answering; replay on a fresh connection.
+                self.buffer_outgoing(message)?;
+                self.reconnect_with_backoff(&reason).await?;
+                self.wait_for_matching_response(matcher).await
+            }
+            other => other,
+        }
     }
 
     async fn send_message_http(&mut self, message: McpMessage) -> Result<McpMessage> {
@@ -290,6 +450,8 @@ impl McpClient {
                     subscribe: Some(true),
                 }),
                 roots: None,
+                prompts: None,
+                logging: None,
             },
             server_info: Some(ServerInfo {
                 name: "Xenobot MCP HTTP Gateway".to_string(),
@@ -416,15 +578,18 @@ impl McpClient {
     }
 
     /// Send a raw message without waiting for response.
+    ///
+    /// If the WebSocket has dropped and `auto_reconnect` is enabled, the message
+    /// is buffered and delivered once the connection is re-established.
     pub async fn send_raw_message(&mut self, message: &McpMessage) -> Result<()> {
-        let json = serde_json::to_string(message)
-            .map_err(|e| McpError::Serialization(format!("Failed to serialize message: {}", e)))?;
-
-        if let Some(ws) = &mut self.ws_connection {
-            ws.send(Message::Text(json)).await.map_err(|e| {
-                McpError::Network(format!("Failed to send WebSocket message: {}", e))
-            })?;
-            Ok(())
+        if self.ws_connection.is_some() {
+            match self.write_ws_message(message).await {
+                Err(McpError::Network(reason)) if self.config.auto_reconnect => {
+                    self.buffer_outgoing(message.clone())?;
+                    self.reconnect_with_backoff(&reason).await
+                }
+                other => other,
+            }
         } else if self.http_base_url.is_some() {
             Err(McpError::Unsupported(
                 "Raw message sending is only supported over WebSocket transport".to_string(),
@@ -434,6 +599,18 @@ impl McpClient {
         }
     }
 
+    async fn write_ws_message(&mut self, message: &McpMessage) -> Result<()> {
+        let json = serde_json::to_string(message)
+            .map_err(|e| McpError::Serialization(format!("Failed to serialize message: {}", e)))?;
+        let ws = self
+            .ws_connection
+            .as_mut()
+            .ok_or_else(|| McpError::Client("Not connected".to_string()))?;
+        ws.send(Message::Text(json))
+            .await
+            .map_err(|e| McpError::Network(format!("Failed to send WebSocket message: {}", e)))
+    }
+
     /// Receive a message from the server.
     pub async fn receive_message(&mut self) -> Result<McpMessage> {
         if let Some(message) = self.pending_messages.pop_front() {
@@ -600,8 +777,10 @@ impl McpClient {
         self.http_client = None;
         self.http_base_url = None;
         self.pending_messages.clear();
+        self.outbound_buffer.clear();
         self.initialized = false;
         self.server_capabilities = None;
+        self.set_connection_state(ConnectionState::Disconnected);
         Ok(())
     }
 
@@ -667,5 +846,88 @@ mod tests {
         let client = McpClient::new(config);
         assert!(!client.initialized);
         assert!(client.server_capabilities.is_none());
+        assert_eq!(client.connection_state(), ConnectionState::Disconnected);
+    }
+
+    #[test]
+    fn reconnection_backoff_grows_exponentially_up_to_cap() {
+        let config = McpClientConfig {
+            reconnection_delay: 1,
+            max_reconnection_delay: 10,
+            reconnection_backoff_factor: 2.0,
+            ..McpClientConfig::default()
+        };
+        let delays: Vec<u64> = (1..=6)
+            .map(|attempt| config.reconnection_backoff(attempt).as_secs())
+            .collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 10, 10]);
+    }
+
+    #[tokio::test]
+    async fn dropped_websocket_reconnects_and_completes_pending_request() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test listener");
+        let addr = listener.local_addr().expect("listener addr");
+
+        let server = tokio::spawn(async move {
+            // First connection: read the request, then drop without answering.
+            let (stream, _) = listener.accept().await.expect("accept first");
+            let mut ws = tokio_tungstenite::accept_async(stream)
+                .await
+                .expect("first handshake");
+            let first = ws.next().await.expect("first frame").expect("frame ok");
+            drop(ws);
+
+            // Second connection: answer the replayed request.
+            let (stream, _) = listener.accept().await.expect("accept second");
+            let mut ws = tokio_tungstenite::accept_async(stream)
+                .await
+                .expect("second handshake");
+            let replayed = ws.next().await.expect("replayed frame").expect("frame ok");
+            let request = serde_json::from_str::<McpMessage>(replayed.to_text().expect("text"))
+                .expect("parse replayed request");
+            assert!(matches!(request, McpMessage::InitializeRequest(_)));
+            let reply = McpMessage::InitializeResponse(InitializeResponse {
+                protocol_version: crate::protocol::MCP_PROTOCOL_VERSION.to_string(),
+                capabilities: crate::protocol::ServerCapabilities::default(),
+                server_info: None,
+                instructions: None,
+            });
+            ws.send(Message::Text(
+                serde_json::to_string(&reply).expect("serialize reply"),
+            ))
+            .await
+            .expect("send reply");
+            (first, replayed)
+        });
+
+        let mut client = McpClient::new(McpClientConfig {
+            server_url: format!("ws://{addr}"),
+            request_timeout: 5,
+            reconnection_delay: 0,
+            max_reconnection_attempts: 3,
+            ..McpClientConfig::default()
+        });
+        let mut states = client.watch_connection_state();
+        client.connect().await.expect("connect");
+        assert_eq!(client.connection_state(), ConnectionState::Connected);
+
+        client
+            .initialize()
+            .await
+            .expect("initialize completes after reconnect");
+        assert!(client.initialized);
+        assert_eq!(client.reconnect_count(), 1);
+        assert_eq!(client.connection_state(), ConnectionState::Connected);
+        assert!(states.has_changed().expect("state channel open"));
+        states.mark_unchanged();
+
+        let (first, replayed) = server.await.expect("server task");
+        assert_eq!(first, replayed, "the pending request is replayed unchanged");
+
+        client.disconnect().await.ok();
+        assert!(states.has_changed().expect("state channel open"));
+        assert_eq!(*states.borrow(), ConnectionState::Disconnected);
     }
 }
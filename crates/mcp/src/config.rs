@@ -2,6 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// MCP server configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,13 +82,42 @@ pub struct McpClientConfig {
     #[serde(default = "default_auto_reconnect")]
     pub auto_reconnect: bool,
 
-    /// Reconnection delay in seconds.
+    /// Delay before the first reconnection attempt, in seconds.
     #[serde(default = "default_reconnection_delay")]
     pub reconnection_delay: u64,
 
+    /// Upper bound for the reconnection delay in seconds.
+    #[serde(default = "default_max_reconnection_delay")]
+    pub max_reconnection_delay: u64,
+
+    /// Factor the delay grows by after each failed attempt (values below 1 act as 1).
+    #[serde(default = "default_reconnection_backoff_factor")]
+    pub reconnection_backoff_factor: f64,
+
     /// Maximum reconnection attempts.
     #[serde(default = "default_max_reconnection_attempts")]
     pub max_reconnection_attempts: u32,
+
+    /// Outgoing messages held while a dropped connection is re-established.
+    #[serde(default = "default_reconnect_buffer_size")]
+    pub reconnect_buffer_size: usize,
+}
+
+impl McpClientConfig {
+    /// Delay before reconnection `attempt` (1-based): `reconnection_delay`
+    /// multiplied by `reconnection_backoff_factor` per prior attempt, capped at
+    /// `max_reconnection_delay`.
+    pub fn reconnection_backoff(&self, attempt: u32) -> Duration {
+        let factor = if self.reconnection_backoff_factor.is_finite() {
+            self.reconnection_backoff_factor.max(1.0)
+        } else {
+            1.0
+        };
+        let exponent = attempt.saturating_sub(1).min(64) as i32;
+        let cap = self.max_reconnection_delay.max(self.reconnection_delay) as f64;
+        let secs = (self.reconnection_delay as f64 * factor.powi(exponent)).min(cap);
+        Duration::from_secs_f64(secs)
+    }
 }
 
 /// Tool configuration.
@@ -137,7 +167,10 @@ impl Default for McpClientConfig {
             request_timeout: default_request_timeout(),
             auto_reconnect: default_auto_reconnect(),
             reconnection_delay: default_reconnection_delay(),
+            max_reconnection_delay: default_max_reconnection_delay(),
+            reconnection_backoff_factor: default_reconnection_backoff_factor(),
             max_reconnection_attempts: default_max_reconnection_attempts(),
+            reconnect_buffer_size: default_reconnect_buffer_size(),
         }
     }
 }
@@ -195,10 +228,22 @@ fn default_reconnection_delay() -> u64 {
     5
 }
 
+fn default_max_reconnection_delay() -> u64 {
+    60
+}
+
+fn default_reconnection_backoff_factor() -> f64 {
+    2.0
+}
+
 fn default_max_reconnection_attempts() -> u32 {
     10
 }
 
+fn default_reconnect_buffer_size() -> usize {
+    32
+}
+
 fn default_enabled() -> bool {
     true
 }
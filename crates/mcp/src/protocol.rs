@@ -210,6 +210,12 @@ pub struct ServerCapabilities {
     /// Available roots.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub roots: Option<RootCapabilities>,
+    /// Available prompt templates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompts: Option<PromptCapabilities>,
+    /// Log notifications pushed to clients.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logging: Option<LoggingCapabilities>,
 }
 
 /// Tool capabilities.
@@ -235,6 +241,20 @@ pub struct ResourceCapabilities {
     pub subscribe: Option<bool>,
 }
 
+/// Prompt capabilities.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PromptCapabilities {
+    /// Whether prompts are supported.
+    pub supported: bool,
+    /// Whether prompts can be listed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list_changed: Option<bool>,
+}
+
+/// Logging capabilities.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LoggingCapabilities {}
+
 /// Root capabilities.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RootCapabilities {
@@ -298,6 +318,30 @@ pub struct RootDefinition {
     pub name: String,
 }
 
+/// Prompt template definition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptDefinition {
+    /// Prompt name.
+    pub name: String,
+    /// Prompt description.
+    pub description: String,
+    /// Arguments accepted by the prompt.
+    #[serde(default)]
+    pub arguments: Vec<PromptArgument>,
+}
+
+/// Prompt template argument.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptArgument {
+    /// Argument name.
+    pub name: String,
+    /// Argument description.
+    pub description: String,
+    /// Whether the argument must be supplied.
+    #[serde(default)]
+    pub required: bool,
+}
+
 impl McpMessage {
     /// Create a new initialization request.
     pub fn initialize_request(
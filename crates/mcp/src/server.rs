@@ -3,9 +3,11 @@
 use crate::config::McpServerConfig;
 use crate::error::{McpError, Result};
 use crate::protocol::{
-    ClientCapabilities, ClientInfo, Content, InitializeRequest, InitializeResponse, McpMessage,
-    Notification, NotificationLevel, ResourceSubscribeRequest, ResourceUpdate, ServerCapabilities,
-    ServerInfo, ToolCallRequest, ToolCallResult, ToolResult,
+    ClientCapabilities, ClientInfo, Content, InitializeRequest, InitializeResponse,
+    LoggingCapabilities, McpMessage, Notification, NotificationLevel, PromptArgument,
+    PromptCapabilities, PromptDefinition, ResourceCapabilities, ResourceSubscribeRequest,
+    ResourceUpdate, RootCapabilities, ServerCapabilities, ServerInfo, ToolCallRequest,
+    ToolCallResult, ToolCapabilities, ToolResult,
 };
 use axum::{
     extract::{
@@ -41,6 +43,8 @@ pub struct McpServer {
     tools: Arc<RwLock<HashMap<String, ToolHandler>>>,
     /// Available resources.
     resources: Arc<RwLock<HashMap<String, Resource>>>,
+    /// Available prompt templates.
+    prompts: Arc<RwLock<HashMap<String, PromptTemplate>>>,
 }
 
 /// Client connection state.
@@ -64,6 +68,17 @@ struct ClientState {
 /// Tool handler function.
 type ToolHandler = Box<dyn Fn(Value) -> Result<Value> + Send + Sync>;
 
+/// Prompt handler function: expands `prompts/get` arguments into a prompt.
+type PromptHandler = Box<dyn Fn(Value) -> Result<Value> + Send + Sync>;
+
+/// Registered prompt template.
+struct PromptTemplate {
+    /// Metadata returned by `prompts/list`.
+    definition: PromptDefinition,
+    /// Expands arguments into prompt messages.
+    handler: PromptHandler,
+}
+
 /// Resource representation.
 #[derive(Debug, Clone)]
 struct Resource {
@@ -100,6 +115,54 @@ impl McpServer {
             clients: Arc::new(RwLock::new(HashMap::new())),
             tools: Arc::new(RwLock::new(HashMap::new())),
             resources: Arc::new(RwLock::new(HashMap::new())),
+            prompts: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Register a prompt template.
+    pub async fn register_prompt(
+        &self,
+        definition: PromptDefinition,
+        handler: PromptHandler,
+    ) -> Result<()> {
+        let mut prompts = self.prompts.write().await;
+        prompts.insert(
+            definition.name.clone(),
+            PromptTemplate {
+                definition,
+                handler,
+            },
+        );
+        Ok(())
+    }
+
+    /// Capabilities derived from what is registered, so clients never see a
+    /// method family this server cannot answer.
+    async fn advertised_capabilities(&self) -> ServerCapabilities {
+        let has_tools = !self.tools.read().await.is_empty();
+        let has_resources = !self.resources.read().await.is_empty();
+        let has_prompts = !self.prompts.read().await.is_empty();
+
+        ServerCapabilities {
+            tools: has_tools.then_some(ToolCapabilities {
+                supported: true,
+                list_changed: Some(true),
+            }),
+            resources: has_resources.then_some(ResourceCapabilities {
+                supported: true,
+                list_changed: Some(true),
+                subscribe: Some(true),
+            }),
+            roots: Some(RootCapabilities {
+                supported: false,
+                list_changed: None,
+            }),
+            prompts: has_prompts.then_some(PromptCapabilities {
+                supported: true,
+                list_changed: Some(true),
+            }),
+            // Notifications are pushed over WebSocket and SSE.
+            logging: Some(LoggingCapabilities {}),
         }
     }
 
@@ -272,10 +335,53 @@ impl McpServer {
         Ok(())
     }
 
+    /// Register built-in prompt templates for common chat analyses.
+    async fn register_builtin_prompts(&self) -> Result<()> {
+        self.register_prompt(
+            chat_prompt_definition(
+                "summarize_chat",
+                "Summarize a chat, optionally limited to a date range",
+            ),
+            Box::new(|args| {
+                transcript_prompt(
+                    &args,
+                    "Chat summary",
+                    "Summarize the following chat. Cover the main topics, decisions made, and open questions.",
+                )
+            }),
+        )
+        .await?;
+        self.register_prompt(
+            chat_prompt_definition(
+                "find_action_items",
+                "Extract action items and commitments from a chat",
+            ),
+            Box::new(|args| {
+                transcript_prompt(
+                    &args,
+                    "Action items",
+                    "List every action item in the following chat as `- owner: task (due date if mentioned)`. Only include requests or commitments that appear in the messages.",
+                )
+            }),
+        )
+        .await?;
+        self.register_prompt(
+            chat_prompt_definition(
+                "who_talked_most",
+                "Rank chat participants by how much they wrote",
+            ),
+            Box::new(|args| participation_prompt(&args)),
+        )
+        .await?;
+
+        Ok(())
+    }
+
     /// Start the MCP server.
     pub async fn start(self) -> Result<()> {
         self.register_builtin_tools().await?;
         self.register_builtin_resources().await?;
+        self.register_builtin_prompts().await?;
         let app = self.create_router();
 
         let addr = format!("{}:{}", self.config.bind_address, self.config.port);
@@ -436,7 +542,7 @@ impl McpServer {
 
         let response = InitializeResponse {
             protocol_version: crate::protocol::MCP_PROTOCOL_VERSION.to_string(),
-            capabilities: ServerCapabilities::default(),
+            capabilities: self.advertised_capabilities().await,
             server_info: Some(ServerInfo {
                 name: self.config.name.clone(),
                 version: self.config.version.clone(),
@@ -1037,6 +1143,297 @@ fn session_summary_tool(args: Value) -> Result<Value> {
     }))
 }
 
+/// Upper bound on transcript characters a prompt template injects.
+const PROMPT_CONTEXT_MAX_CHARS: usize = 12_000;
+/// Upper bound on messages fetched for one prompt.
+const PROMPT_CONTEXT_MAX_MESSAGES: i64 = 500;
+/// Upper bound on participants listed by `who_talked_most`.
+const PROMPT_PARTICIPANT_LIMIT: i64 = 50;
+
+fn chat_prompt_definition(name: &str, description: &str) -> PromptDefinition {
+    PromptDefinition {
+        name: name.to_string(),
+        description: description.to_string(),
+        arguments: vec![
+            PromptArgument {
+                name: "meta_id".to_string(),
+                description: "Chat session id".to_string(),
+                required: true,
+            },
+            PromptArgument {
+                name: "start_ts".to_string(),
+                description: "Range start, unix seconds (inclusive)".to_string(),
+                required: false,
+            },
+            PromptArgument {
+                name: "end_ts".to_string(),
+                description: "Range end, unix seconds (inclusive)".to_string(),
+                required: false,
+            },
+        ],
+    }
+}
+
+/// Prompt arguments arrive as strings per the MCP spec; numbers are accepted too.
+fn parse_prompt_i64(args: &Value, key: &str) -> Result<Option<i64>> {
+    match arg_value(args, key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(number)) => number
+            .as_i64()
+            .map(Some)
+            .ok_or_else(|| McpError::Argument(format!("{key} must be an integer"))),
+        Some(Value::String(text)) if text.trim().is_empty() => Ok(None),
+        Some(Value::String(text)) => text
+            .trim()
+            .parse::<i64>()
+            .map(Some)
+            .map_err(|_| McpError::Argument(format!("{key} must be an integer"))),
+        Some(_) => Err(McpError::Argument(format!("{key} must be an integer"))),
+    }
+}
+
+/// Chat and range a prompt was asked about.
+struct PromptScope {
+    meta_id: i64,
+    chat_name: String,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+}
+
+impl PromptScope {
+    fn from_args(conn: &Connection, args: &Value) -> Result<Self> {
+        let meta_id = parse_prompt_i64(args, "meta_id")?
+            .ok_or_else(|| McpError::Argument("missing required argument: meta_id".to_string()))?;
+        let start_ts = parse_prompt_i64(args, "start_ts")?;
+        let end_ts = parse_prompt_i64(args, "end_ts")?;
+        if let (Some(start), Some(end)) = (start_ts, end_ts) {
+            if start > end {
+                return Err(McpError::Argument(
+                    "start_ts must not be after end_ts".to_string(),
+                ));
+            }
+        }
+        let chat_name = conn
+            .query_row(
+                "SELECT name FROM meta WHERE id = ?1",
+                params![meta_id],
+                |row| row.get::<_, String>(0),
+            )
+            .map_err(|_| McpError::Argument(format!("unknown meta_id: {meta_id}")))?;
+        Ok(Self {
+            meta_id,
+            chat_name,
+            start_ts,
+            end_ts,
+        })
+    }
+
+    fn header(&self) -> String {
+        let mut header = format!("Chat: {} (meta_id {})", self.chat_name, self.meta_id);
+        match (self.start_ts, self.end_ts) {
+            (None, None) => {}
+            (start, end) => {
+                let bound = |ts: Option<i64>| {
+                    ts.map(format_prompt_ts)
+                        .unwrap_or_else(|| "(open)".to_string())
+                };
+                header.push_str(&format!(
+                    "\nRange: {} to {} (UTC)",
+                    bound(start),
+                    bound(end)
+                ));
+            }
+        }
+        header
+    }
+
+    fn context_json(&self) -> Value {
+        serde_json::json!({
+            "metaId": self.meta_id,
+            "chatName": self.chat_name,
+            "startTs": self.start_ts,
+            "endTs": self.end_ts,
+        })
+    }
+}
+
+fn format_prompt_ts(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+/// Most recent transcript lines in the scope that fit in `max_chars`,
+/// returned oldest first, plus whether older messages were left out.
+fn fetch_prompt_transcript(
+    conn: &Connection,
+    scope: &PromptScope,
+    max_chars: usize,
+) -> Result<(Vec<String>, bool)> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT
+                msg.ts,
+                COALESCE(msg.content, ''),
+                COALESCE(msg.sender_group_nickname, msg.sender_account_name, m.group_nickname, m.account_name, m.platform_id, '') as sender_name
+             FROM message msg
+             LEFT JOIN member m ON m.id = msg.sender_id
+             WHERE msg.meta_id = ?1
+               AND (?2 IS NULL OR msg.ts >= ?2)
+               AND (?3 IS NULL OR msg.ts <= ?3)
+               AND TRIM(COALESCE(msg.content, '')) <> ''
+             ORDER BY msg.ts DESC, msg.id DESC
+             LIMIT ?4",
+        )
+        .map_err(|e| McpError::Tool(format!("prepare prompt transcript failed: {e}")))?;
+    let rows = stmt
+        .query_map(
+            params![
+                scope.meta_id,
+                scope.start_ts,
+                scope.end_ts,
+                PROMPT_CONTEXT_MAX_MESSAGES + 1
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            },
+        )
+        .map_err(|e| McpError::Tool(format!("query prompt transcript failed: {e}")))?;
+
+    let mut lines = Vec::new();
+    let mut used_chars = 0usize;
+    let mut truncated = false;
+    for row in rows {
+        let (ts, content, sender) =
+            row.map_err(|e| McpError::Tool(format!("read prompt transcript row failed: {e}")))?;
+        let line = format!("[{}] {}: {}", format_prompt_ts(ts), sender, content.trim());
+        let line_chars = line.chars().count() + 1;
+        if lines.len() as i64 >= PROMPT_CONTEXT_MAX_MESSAGES || used_chars + line_chars > max_chars
+        {
+            truncated = true;
+            break;
+        }
+        used_chars += line_chars;
+        lines.push(line);
+    }
+    lines.reverse();
+    Ok((lines, truncated))
+}
+
+fn prompt_result(description: String, text: String, context: Value) -> Value {
+    serde_json::json!({
+        "description": description,
+        "messages": [{
+            "role": "user",
+            "content": {
+                "type": "text",
+                "text": text,
+            }
+        }],
+        "context": context,
+    })
+}
+
+fn transcript_prompt(args: &Value, title: &str, instruction: &str) -> Result<Value> {
+    let conn = open_xenobot_db()?;
+    let scope = PromptScope::from_args(&conn, args)?;
+    let (lines, truncated) = fetch_prompt_transcript(&conn, &scope, PROMPT_CONTEXT_MAX_CHARS)?;
+
+    let mut text = format!("{instruction}\n\n{}\n", scope.header());
+    if lines.is_empty() {
+        text.push_str("\nNo messages in this range.\n");
+    } else {
+        text.push_str(&format!(
+            "Messages: {}{}\n\n{}\n",
+            lines.len(),
+            if truncated {
+                " most recent (older messages omitted to fit the context limit)"
+            } else {
+                ""
+            },
+            lines.join("\n")
+        ));
+    }
+
+    let mut context = scope.context_json();
+    context["messagesIncluded"] = serde_json::json!(lines.len());
+    context["truncated"] = serde_json::json!(truncated);
+    Ok(prompt_result(
+        format!("{title} for {}", scope.chat_name),
+        text,
+        context,
+    ))
+}
+
+fn participation_prompt(args: &Value) -> Result<Value> {
+    let conn = open_xenobot_db()?;
+    let scope = PromptScope::from_args(&conn, args)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT
+                COALESCE(msg.sender_group_nickname, msg.sender_account_name, m.group_nickname, m.account_name, m.platform_id, '') as sender_name,
+                CAST(COUNT(*) AS INTEGER) as message_count,
+                CAST(SUM(LENGTH(COALESCE(msg.content, ''))) AS INTEGER) as char_count
+             FROM message msg
+             LEFT JOIN member m ON m.id = msg.sender_id
+             WHERE msg.meta_id = ?1
+               AND (?2 IS NULL OR msg.ts >= ?2)
+               AND (?3 IS NULL OR msg.ts <= ?3)
+             GROUP BY m.id, sender_name
+             ORDER BY message_count DESC, sender_name ASC
+             LIMIT ?4",
+        )
+        .map_err(|e| McpError::Tool(format!("prepare prompt participation failed: {e}")))?;
+    let rows = stmt
+        .query_map(
+            params![
+                scope.meta_id,
+                scope.start_ts,
+                scope.end_ts,
+                PROMPT_PARTICIPANT_LIMIT
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            },
+        )
+        .map_err(|e| McpError::Tool(format!("query prompt participation failed: {e}")))?;
+
+    let mut lines = Vec::new();
+    for row in rows {
+        let (sender, messages, chars) =
+            row.map_err(|e| McpError::Tool(format!("read prompt participation row failed: {e}")))?;
+        lines.push(format!(
+            "- {sender}: {messages} messages, {chars} characters"
+        ));
+    }
+
+    let mut text = format!(
+        "Using the per-participant counts below, rank who talked most and describe how participation is spread across the chat.\n\n{}\n",
+        scope.header()
+    );
+    if lines.is_empty() {
+        text.push_str("\nNo messages in this range.\n");
+    } else {
+        text.push_str(&format!("\n{}\n", lines.join("\n")));
+    }
+
+    let mut context = scope.context_json();
+    context["participantsIncluded"] = serde_json::json!(lines.len());
+    Ok(prompt_result(
+        format!("Participation ranking for {}", scope.chat_name),
+        text,
+        context,
+    ))
+}
+
 fn parse_limit(args: &Value, default_limit: i64, max_limit: i64) -> Result<i64> {
     let limit = parse_optional_i64(args, "limit").unwrap_or(default_limit);
     Ok(limit.max(1).min(max_limit))
@@ -1385,6 +1782,50 @@ async fn handle_sse(headers: HeaderMap, State(server): State<Arc<McpServer>>) ->
     )
 }
 
+/// JSON-RPC (camelCase) rendering of [`ServerCapabilities`]; absent
+/// capabilities are omitted.
+fn server_capabilities_json(capabilities: &ServerCapabilities) -> serde_json::Value {
+    let mut out = serde_json::Map::new();
+    if let Some(tools) = &capabilities.tools {
+        out.insert(
+            "tools".to_string(),
+            serde_json::json!({
+                "supported": tools.supported,
+                "listChanged": tools.list_changed.unwrap_or(false),
+            }),
+        );
+    }
+    if let Some(resources) = &capabilities.resources {
+        out.insert(
+            "resources".to_string(),
+            serde_json::json!({
+                "supported": resources.supported,
+                "listChanged": resources.list_changed.unwrap_or(false),
+                "subscribe": resources.subscribe.unwrap_or(false),
+            }),
+        );
+    }
+    if let Some(prompts) = &capabilities.prompts {
+        out.insert(
+            "prompts".to_string(),
+            serde_json::json!({
+                "supported": prompts.supported,
+                "listChanged": prompts.list_changed.unwrap_or(false),
+            }),
+        );
+    }
+    if let Some(roots) = &capabilities.roots {
+        out.insert(
+            "roots".to_string(),
+            serde_json::json!({ "supported": roots.supported }),
+        );
+    }
+    if capabilities.logging.is_some() {
+        out.insert("logging".to_string(), serde_json::json!({}));
+    }
+    serde_json::Value::Object(out)
+}
+
 fn json_rpc_ok(id: serde_json::Value, result: serde_json::Value) -> serde_json::Value {
     serde_json::json!({
         "jsonrpc": "2.0",
@@ -1664,26 +2105,14 @@ async fn handle_streamable_http_rpc(
         "initialize" => {
             let server_name = server.config.name.clone();
             let server_version = server.config.version.clone();
+            let capabilities = server.advertised_capabilities().await;
             let result = serde_json::json!({
                 "protocolVersion": crate::protocol::MCP_PROTOCOL_VERSION,
                 "serverInfo": {
                     "name": server_name,
                     "version": server_version,
                 },
-                "capabilities": {
-                    "tools": {
-                        "supported": true,
-                        "listChanged": true
-                    },
-                    "resources": {
-                        "supported": true,
-                        "listChanged": true,
-                        "subscribe": true
-                    },
-                    "roots": {
-                        "supported": false
-                    }
-                },
+                "capabilities": server_capabilities_json(&capabilities),
                 "instructions": "Welcome to Xenobot MCP Server"
             });
             (StatusCode::OK, Json(json_rpc_ok(id, result))).into_response()
@@ -1770,6 +2199,81 @@ async fn handle_streamable_http_rpc(
                 }
             }
         }
+        "prompts/list" | "prompt/list" => {
+            let prompts = server.prompts.read().await;
+            let mut definitions: Vec<&PromptDefinition> =
+                prompts.values().map(|prompt| &prompt.definition).collect();
+            definitions.sort_by(|a, b| a.name.cmp(&b.name));
+            let result = serde_json::json!({
+                "count": definitions.len(),
+                "prompts": definitions,
+            });
+            (StatusCode::OK, Json(json_rpc_ok(id, result))).into_response()
+        }
+        "prompts/get" | "prompt/get" => {
+            let params = payload
+                .get("params")
+                .cloned()
+                .unwrap_or_else(|| serde_json::json!({}));
+            let Some(prompt_name) =
+                extract_non_empty_string_from_keys(&params, &["name", "prompt"])
+            else {
+                return (
+                    StatusCode::OK,
+                    Json(json_rpc_err(
+                        id,
+                        -32602,
+                        "invalid_params",
+                        Some(serde_json::json!({
+                            "reason": "missing prompt name in params.name",
+                        })),
+                    )),
+                )
+                    .into_response();
+            };
+            let args = params
+                .get("arguments")
+                .cloned()
+                .unwrap_or_else(|| serde_json::json!({}));
+            let prompts = server.prompts.read().await;
+            let Some(prompt) = prompts.get(&prompt_name) else {
+                return (
+                    StatusCode::OK,
+                    Json(json_rpc_err(
+                        id,
+                        -32004,
+                        "prompt_not_found",
+                        Some(serde_json::json!({
+                            "prompt": prompt_name,
+                        })),
+                    )),
+                )
+                    .into_response();
+            };
+
+            match (prompt.handler)(args) {
+                Ok(result) => (StatusCode::OK, Json(json_rpc_ok(id, result))).into_response(),
+                Err(e) => {
+                    let (code, message) = match e {
+                        McpError::Argument(_) => (-32602, "invalid_params"),
+                        _ => (-32603, "prompt_error"),
+                    };
+                    (
+                        StatusCode::OK,
+                        Json(json_rpc_err(
+                            id,
+                            code,
+                            message,
+                            Some(serde_json::json!({
+                                "prompt": prompt_name,
+                                "error": e.to_string(),
+                            })),
+                        )),
+                    )
+                        .into_response()
+                }
+            }
+        }
         "resources/list" | "resource/list" => {
             let resources = server.resources.read().await;
             let mut uris: Vec<String> = resources.keys().cloned().collect();
@@ -2495,6 +2999,10 @@ mod tests {
             .register_builtin_tools()
             .await
             .expect("register builtin tools");
+        server
+            .register_builtin_resources()
+            .await
+            .expect("register builtin resources");
         let app = server.create_router();
 
         let (status, init_json) = request_json(
@@ -2704,6 +3212,159 @@ mod tests {
         let _ = std::fs::remove_file(&db_path);
     }
 
+    #[tokio::test]
+    async fn test_initialize_advertises_only_registered_capabilities() {
+        let config = McpServerConfig {
+            name: "Capability Probe".to_string(),
+            version: "9.9.9".to_string(),
+            ..McpServerConfig::default()
+        };
+        let server = McpServer::new(config);
+        server
+            .register_builtin_tools()
+            .await
+            .expect("register builtin tools");
+
+        let capabilities = server.advertised_capabilities().await;
+        assert!(capabilities
+            .tools
+            .as_ref()
+            .is_some_and(|tools| tools.supported));
+        assert!(capabilities.prompts.is_none());
+        assert!(capabilities.resources.is_none());
+
+        let app = server.create_router();
+        let (status, init_json) = request_json(
+            &app,
+            Method::POST,
+            "/mcp",
+            Some(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": "init-caps",
+                "method": "initialize",
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let result = &init_json["result"];
+        assert_eq!(result["serverInfo"]["name"], "Capability Probe");
+        assert_eq!(result["serverInfo"]["version"], "9.9.9");
+        assert_eq!(result["capabilities"]["tools"]["supported"], true);
+        assert!(result["capabilities"].get("prompts").is_none());
+        assert!(result["capabilities"].get("resources").is_none());
+        assert_eq!(result["capabilities"]["roots"]["supported"], false);
+        assert!(result["capabilities"]["logging"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_streamable_http_prompts_list_and_get() {
+        let _guard = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+        let db_path = unique_db_path("prompts");
+        let previous_db = std::env::var("XENOBOT_DB_PATH").ok();
+        std::env::set_var("XENOBOT_DB_PATH", &db_path);
+        seed_chat_records_fixture(&db_path);
+
+        let server = McpServer::new(McpServerConfig::default());
+        server
+            .register_builtin_prompts()
+            .await
+            .expect("register builtin prompts");
+        assert!(server.advertised_capabilities().await.prompts.is_some());
+        let app = server.create_router();
+
+        let (status, list_json) = request_json(
+            &app,
+            Method::POST,
+            "/mcp",
+            Some(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": "prompts-1",
+                "method": "prompts/list"
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let names: Vec<&str> = list_json["result"]["prompts"]
+            .as_array()
+            .expect("prompts array")
+            .iter()
+            .filter_map(|prompt| prompt["name"].as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["find_action_items", "summarize_chat", "who_talked_most"]
+        );
+        assert_eq!(
+            list_json["result"]["prompts"][0]["arguments"][0]["name"],
+            "meta_id"
+        );
+        assert_eq!(
+            list_json["result"]["prompts"][0]["arguments"][0]["required"],
+            true
+        );
+
+        let (status, get_json) = request_json(
+            &app,
+            Method::POST,
+            "/mcp",
+            Some(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": "prompts-2",
+                "method": "prompts/get",
+                "params": {
+                    "name": "summarize_chat",
+                    "arguments": { "meta_id": "1", "start_ts": "1900000005" }
+                }
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let result = &get_json["result"];
+        assert_eq!(result["messages"][0]["role"], "user");
+        let text = result["messages"][0]["content"]["text"]
+            .as_str()
+            .expect("prompt text");
+        assert!(text.starts_with("Summarize the following chat."));
+        assert!(text.contains("Chat: MCP Session (meta_id 1)"));
+        assert!(text.contains("Alice: another line"));
+        assert!(
+            !text.contains("hello world"),
+            "start_ts excludes older messages"
+        );
+        assert_eq!(result["context"]["messagesIncluded"], 1);
+        assert_eq!(result["context"]["truncated"], false);
+
+        let (_, missing_json) = request_json(
+            &app,
+            Method::POST,
+            "/mcp",
+            Some(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": "prompts-3",
+                "method": "prompts/get",
+                "params": { "name": "who_talked_most", "arguments": {} }
+            })),
+        )
+        .await;
+        assert_eq!(missing_json["error"]["code"], -32602);
+
+        let conn = Connection::open(&db_path).expect("open db");
+        let scope = PromptScope::from_args(&conn, &serde_json::json!({ "meta_id": 1 }))
+            .expect("prompt scope");
+        let (lines, truncated) =
+            fetch_prompt_transcript(&conn, &scope, 40).expect("bounded transcript");
+        assert_eq!(lines.len(), 1, "only the newest line fits in 40 chars");
+        assert!(lines[0].ends_with("Alice: another line"));
+        assert!(truncated);
+
+        if let Some(previous) = previous_db {
+            std::env::set_var("XENOBOT_DB_PATH", previous);
+        } else {
+            std::env::remove_var("XENOBOT_DB_PATH");
+        }
+        let _ = std::fs::remove_file(&db_path);
+    }
+
     #[tokio::test]
     async fn test_sse_endpoint_returns_event_stream_content_type() {
         let _guard = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
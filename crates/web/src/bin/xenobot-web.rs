@@ -14,7 +14,13 @@ use xenobot_api::router::build_router;
 use xenobot_api::webhook_replay::spawn_webhook_dead_letter_replayer;
 use xenobot_core::config::XenobotConfig;
 use xenobot_web::assets::static_files_service;
-use xenobot_web::websocket::{ws_handler, WebSocketState};
+use xenobot_web::integration::{
+    spawn_import_progress_forwarder, spawn_message_created_forwarder,
+    spawn_monitor_file_processed_forwarder,
+};
+use xenobot_web::websocket::{
+    events_ws_handler, ws_handler, ws_metrics_handler, WebSocketLimits, WebSocketState,
+};
 
 /// Main entry point.
 #[tokio::main]
@@ -30,7 +36,10 @@ async fn main() -> anyhow::Result<()> {
     let _replay_worker = spawn_webhook_dead_letter_replayer(&api_config);
 
     // Create WebSocket state
-    let ws_state = Arc::new(WebSocketState::new());
+    let ws_state = Arc::new(WebSocketState::with_limits(WebSocketLimits::from_env()));
+    let _import_progress_forwarder = spawn_import_progress_forwarder(ws_state.clone());
+    let _message_created_forwarder = spawn_message_created_forwarder(ws_state.clone());
+    let _monitor_forwarder = spawn_monitor_file_processed_forwarder(ws_state.clone());
 
     // Build API router
     let api_router = build_router(&api_config);
@@ -40,12 +49,16 @@ async fn main() -> anyhow::Result<()> {
 
     // Combine routers: API, WebSocket, static files (in order of precedence)
     let app = Router::new()
-        // Add WebSocket state as extension
-        .layer(Extension(ws_state))
         // Mount API under /api
         .nest("/api", api_router)
         // WebSocket endpoint
         .route("/ws", get(ws_handler))
+        // Subscribable progress/monitor event stream
+        .route("/ws/events", get(events_ws_handler))
+        // Connection count and limits
+        .route("/ws/metrics", get(ws_metrics_handler))
+        // WebSocket state for the routes above (layers only wrap earlier routes)
+        .layer(Extension(ws_state))
         // Health check
         .route("/health", get(|| async { "OK" }))
         // Serve static files under root (catch-all for frontend)
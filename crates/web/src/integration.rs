@@ -5,6 +5,12 @@
 
 use std::sync::Arc;
 
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+use xenobot_api::events::{
+    subscribe_import_progress, subscribe_message_created, subscribe_monitor_file_processed,
+};
+
 use crate::error::WebResult;
 use crate::websocket::{WebSocketMessage, WebSocketState};
 
@@ -79,6 +85,74 @@ impl FrontendIntegration {
     }
 }
 
+/// Relay `import.progress` events from the API import bus to WebSocket clients.
+///
+/// The final event of a run ("complete" or "error") is also sent as
+/// `import.completed`.
+pub fn spawn_import_progress_forwarder(ws_state: Arc<WebSocketState>) -> JoinHandle<()> {
+    let mut rx = subscribe_import_progress();
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    let finished = matches!(event.status.as_str(), "complete" | "error");
+                    let _ = ws_state
+                        .broadcast(WebSocketMessage::ImportProgress(event.clone()))
+                        .await;
+                    if finished {
+                        let _ = ws_state
+                            .broadcast(WebSocketMessage::ImportCompleted(event))
+                            .await;
+                    }
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
+/// Relay `message.created` events from API imports and monitor writes to
+/// WebSocket clients.
+pub fn spawn_message_created_forwarder(ws_state: Arc<WebSocketState>) -> JoinHandle<()> {
+    spawn_forwarder(
+        subscribe_message_created(),
+        ws_state,
+        WebSocketMessage::MessageCreated,
+    )
+}
+
+/// Relay `monitor.file_processed` events from the file monitor to WebSocket
+/// clients.
+pub fn spawn_monitor_file_processed_forwarder(ws_state: Arc<WebSocketState>) -> JoinHandle<()> {
+    spawn_forwarder(
+        subscribe_monitor_file_processed(),
+        ws_state,
+        WebSocketMessage::MonitorFileProcessed,
+    )
+}
+
+fn spawn_forwarder<T>(
+    mut rx: broadcast::Receiver<T>,
+    ws_state: Arc<WebSocketState>,
+    wrap: fn(T) -> WebSocketMessage,
+) -> JoinHandle<()>
+where
+    T: Clone + Send + 'static,
+{
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    let _ = ws_state.broadcast(wrap(event)).await;
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
 /// IPC to HTTP adapter (placeholder).
 /// In the original Electron app, the frontend uses `window.electronAPI` or `ipcRenderer`.
 /// We will replace those calls with HTTP requests to the Rust backend.
@@ -1,29 +1,132 @@
 //! WebSocket server for real-time updates.
+//!
+//! `/ws` carries every broadcast message. `/ws/events` carries only the typed
+//! event frames a client subscribes to:
+//!
+//! ```json
+//! {"type": "subscribe", "data": {"events": ["import.progress", "import.completed"]}}
+//! ```
+//!
+//! Event frames look like `{"type": "import.progress", "data": {...}}`.
 
 use axum::{
     extract::{
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
         Extension,
     },
-    response::IntoResponse,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
 };
 use futures::{
     stream::{SplitSink, SplitStream},
     SinkExt, StreamExt,
 };
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::{broadcast, RwLock};
-use tracing::info;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+pub use xenobot_api::events::MonitorFileProcessedEvent;
+use xenobot_api::events::{ImportProgressEvent, IMPORT_PROGRESS_EVENT};
+use xenobot_core::webhook::WebhookMessageCreatedEvent;
 
 use crate::error::WebResult;
 
+/// Event name for a finished import run.
+pub const IMPORT_COMPLETED_EVENT: &str = "import.completed";
+/// Event name for a watched file the monitor has written.
+pub const MONITOR_FILE_PROCESSED_EVENT: &str = "monitor.file_processed";
+/// Event name for a message written to the database.
+pub const MESSAGE_CREATED_EVENT: &str = "message.created";
+
+/// Event types a `/ws/events` client can subscribe to.
+pub const EVENT_TYPES: &[&str] = &[
+    IMPORT_PROGRESS_EVENT,
+    IMPORT_COMPLETED_EVENT,
+    MONITOR_FILE_PROCESSED_EVENT,
+    MESSAGE_CREATED_EVENT,
+];
+
+/// How often `/ws/events` pings an idle client. A client that sends nothing,
+/// not even a pong, for a full interval after a ping is disconnected.
+pub const EVENTS_PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long a closing connection may take to flush its close frame before
+/// the socket is dropped.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Connection limits for the WebSocket endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebSocketLimits {
+    /// Concurrent connections across `/ws` and `/ws/events`; further
+    /// upgrades are refused with 503.
+    pub max_connections: u32,
+    /// Frames queued for one client before it is disconnected as too slow.
+    pub send_buffer: usize,
+}
+
+impl Default for WebSocketLimits {
+    fn default() -> Self {
+        Self {
+            max_connections: 256,
+            send_buffer: 64,
+        }
+    }
+}
+
+impl WebSocketLimits {
+    /// Read limits from `XENOBOT_WS_MAX_CONNECTIONS` and
+    /// `XENOBOT_WS_SEND_BUFFER`, keeping defaults for unset or invalid values.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Read limits through `lookup`, which returns a variable's value.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        let positive = |name: &str| {
+            lookup(name)
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .filter(|value| *value > 0)
+        };
+        Self {
+            max_connections: positive("XENOBOT_WS_MAX_CONNECTIONS")
+                .and_then(|value| u32::try_from(value).ok())
+                .unwrap_or(defaults.max_connections),
+            send_buffer: positive("XENOBOT_WS_SEND_BUFFER")
+                .and_then(|value| usize::try_from(value).ok())
+                .unwrap_or(defaults.send_buffer),
+        }
+    }
+}
+
 /// Shared state for WebSocket connections.
 #[derive(Debug, Clone)]
 pub struct WebSocketState {
     /// Broadcast sender for sending messages to all connected clients.
     pub tx: broadcast::Sender<WebSocketMessage>,
     /// Connected client count.
-    pub client_count: Arc<RwLock<u32>>,
+    pub client_count: Arc<AtomicU32>,
+    /// Clients disconnected for falling behind their send buffer.
+    pub slow_disconnects: Arc<AtomicU64>,
+    /// Connection and buffer limits.
+    pub limits: WebSocketLimits,
+}
+
+/// Snapshot served by `/ws/metrics`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebSocketMetrics {
+    /// Open connections across both endpoints.
+    pub active_connections: u32,
+    /// Configured connection limit.
+    pub max_connections: u32,
+    /// Configured per-connection send buffer, in frames.
+    pub send_buffer: usize,
+    /// Clients dropped for not keeping up since startup.
+    pub slow_disconnects: u64,
 }
 
 /// Message types for WebSocket communication.
@@ -49,18 +152,77 @@ pub enum WebSocketMessage {
         message: String,
         level: String,
     },
+    /// Throttled import progress from the API import pipeline.
+    #[serde(rename = "import.progress")]
+    ImportProgress(ImportProgressEvent),
+    /// Final progress of an import run, with status "complete" or "error".
+    #[serde(rename = "import.completed")]
+    ImportCompleted(ImportProgressEvent),
+    /// A watched export file was parsed and written by the monitor.
+    #[serde(rename = "monitor.file_processed")]
+    MonitorFileProcessed(MonitorFileProcessedEvent),
+    /// A message was written to the database.
+    #[serde(rename = "message.created")]
+    MessageCreated(WebhookMessageCreatedEvent),
     /// Ping/Pong heartbeat.
     Ping,
     Pong,
 }
 
+impl WebSocketMessage {
+    /// Event type name for messages delivered on `/ws/events`; `None` for
+    /// messages only sent on `/ws`.
+    pub fn event_type(&self) -> Option<&'static str> {
+        match self {
+            WebSocketMessage::ImportProgress(_) => Some(IMPORT_PROGRESS_EVENT),
+            WebSocketMessage::ImportCompleted(_) => Some(IMPORT_COMPLETED_EVENT),
+            WebSocketMessage::MonitorFileProcessed(_) => Some(MONITOR_FILE_PROCESSED_EVENT),
+            WebSocketMessage::MessageCreated(_) => Some(MESSAGE_CREATED_EVENT),
+            _ => None,
+        }
+    }
+}
+
+/// Control frames a client sends on `/ws/events`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "lowercase")]
+pub enum EventClientMessage {
+    /// Start receiving the named event types.
+    Subscribe { events: Vec<String> },
+    /// Stop receiving the named event types.
+    Unsubscribe { events: Vec<String> },
+    /// Application-level keepalive; answered with `pong`.
+    Ping,
+}
+
+/// Control frames the server sends on `/ws/events`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "lowercase")]
+pub enum EventControlReply {
+    /// Event types the connection now receives.
+    Subscribed { events: Vec<String> },
+    /// Reply to a client `ping`.
+    Pong,
+    /// The last control frame was rejected.
+    Error { message: String },
+}
+
 impl WebSocketState {
-    /// Create new WebSocket state.
+    /// Create new WebSocket state with default limits.
     pub fn new() -> Self {
-        let (tx, _) = broadcast::channel(100);
+        Self::with_limits(WebSocketLimits::default())
+    }
+
+    /// Create new WebSocket state with the given limits.
+    pub fn with_limits(limits: WebSocketLimits) -> Self {
+        // Each client also has its own send buffer; the broadcast backlog only
+        // needs to cover the time it takes a connection task to drain it.
+        let (tx, _) = broadcast::channel(limits.send_buffer.max(100));
         Self {
             tx,
-            client_count: Arc::new(RwLock::new(0)),
+            client_count: Arc::new(AtomicU32::new(0)),
+            slow_disconnects: Arc::new(AtomicU64::new(0)),
+            limits,
         }
     }
 
@@ -70,18 +232,48 @@ impl WebSocketState {
         Ok(())
     }
 
-    /// Increment client count.
-    pub async fn increment_client_count(&self) {
-        let mut count = self.client_count.write().await;
-        *count += 1;
-        info!("WebSocket client connected. Total clients: {}", count);
+    /// Reserve a connection slot, or `None` when `max_connections` are open.
+    pub fn try_connect(&self) -> Option<ConnectionSlot> {
+        let max = self.limits.max_connections;
+        let previous = self
+            .client_count
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                (count < max).then_some(count + 1)
+            })
+            .ok()?;
+        info!(
+            "WebSocket client connected. Total clients: {}",
+            previous + 1
+        );
+        Some(ConnectionSlot {
+            client_count: Arc::clone(&self.client_count),
+        })
+    }
+
+    /// Current connection count and limits.
+    pub fn metrics(&self) -> WebSocketMetrics {
+        WebSocketMetrics {
+            active_connections: self.client_count.load(Ordering::SeqCst),
+            max_connections: self.limits.max_connections,
+            send_buffer: self.limits.send_buffer,
+            slow_disconnects: self.slow_disconnects.load(Ordering::SeqCst),
+        }
     }
+}
+
+/// A reserved connection; the slot is released when this is dropped.
+#[derive(Debug)]
+pub struct ConnectionSlot {
+    client_count: Arc<AtomicU32>,
+}
 
-    /// Decrement client count.
-    pub async fn decrement_client_count(&self) {
-        let mut count = self.client_count.write().await;
-        *count = count.saturating_sub(1);
-        info!("WebSocket client disconnected. Total clients: {}", count);
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        let previous = self.client_count.fetch_sub(1, Ordering::SeqCst);
+        info!(
+            "WebSocket client disconnected. Total clients: {}",
+            previous.saturating_sub(1)
+        );
     }
 }
 
@@ -89,70 +281,269 @@ impl WebSocketState {
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
     Extension(state): Extension<Arc<WebSocketState>>,
-) -> impl IntoResponse {
-    ws.on_upgrade(|socket| handle_socket(socket, state))
+) -> Response {
+    match state.try_connect() {
+        Some(slot) => ws
+            .on_upgrade(move |socket| handle_socket(socket, state, slot))
+            .into_response(),
+        None => too_many_connections(&state),
+    }
 }
 
-/// Handle individual WebSocket connection.
-async fn handle_socket(socket: WebSocket, state: Arc<WebSocketState>) {
-    state.increment_client_count().await;
+/// Metrics handler for `/ws/metrics`.
+pub async fn ws_metrics_handler(
+    Extension(state): Extension<Arc<WebSocketState>>,
+) -> Json<WebSocketMetrics> {
+    Json(state.metrics())
+}
+
+fn too_many_connections(state: &WebSocketState) -> Response {
+    warn!(
+        "Refusing WebSocket client: {} connections open",
+        state.limits.max_connections
+    );
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        "too many WebSocket connections",
+    )
+        .into_response()
+}
 
+/// Handle individual WebSocket connection.
+async fn handle_socket(socket: WebSocket, state: Arc<WebSocketState>, _slot: ConnectionSlot) {
     let mut rx = state.tx.subscribe();
-    let (sender, mut receiver): (SplitSink<WebSocket, Message>, SplitStream<WebSocket>) =
+    let (sink, mut stream): (SplitSink<WebSocket, Message>, SplitStream<WebSocket>) =
         socket.split();
+    let sender = ClientSender::spawn(sink, state.limits.send_buffer);
 
-    // Clone sender for the send task
-    let sender = Arc::new(tokio::sync::Mutex::new(sender));
-    let sender_clone = Arc::clone(&sender);
-
-    // Spawn task to receive messages from client.
-    let recv_task = tokio::spawn(async move {
-        let mut sender = sender_clone.lock().await;
-        while let Some(Ok(msg)) = receiver.next().await {
-            match msg {
-                Message::Text(text) => {
-                    // Handle incoming messages from client
-                    if let Ok(message) = serde_json::from_str::<WebSocketMessage>(&text) {
-                        match message {
-                            WebSocketMessage::Ping => {
-                                // Respond with pong
-                                let _ = sender
-                                    .send(Message::Text(
-                                        serde_json::to_string(&WebSocketMessage::Pong).unwrap(),
-                                    ))
-                                    .await;
-                            }
-                            _ => {
-                                // Forward to broadcast? For now, just log
-                                info!("Received WebSocket message: {:?}", message);
+    let outcome = loop {
+        tokio::select! {
+            incoming = stream.next() => {
+                let Some(Ok(frame)) = incoming else {
+                    break Ok(());
+                };
+                match frame {
+                    Message::Text(text) => {
+                        // Handle incoming messages from client
+                        match serde_json::from_str::<WebSocketMessage>(&text) {
+                            Ok(WebSocketMessage::Ping) => {
+                                if let Err(e) = sender.send_json(&WebSocketMessage::Pong) {
+                                    break Err(e);
+                                }
                             }
+                            Ok(message) => info!("Received WebSocket message: {:?}", message),
+                            Err(_) => {}
                         }
                     }
+                    Message::Close(_) => break Ok(()),
+                    _ => {}
                 }
-                Message::Close(_) => {
-                    break;
+            }
+            event = rx.recv() => match event {
+                Ok(message) => {
+                    if let Err(e) = sender.send_json(&message) {
+                        break Err(e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => break Err(QueueError::Full),
+                Err(broadcast::error::RecvError::Closed) => break Ok(()),
+            },
+        }
+    };
+
+    sender.finish(outcome, &state).await;
+}
+
+/// WebSocket upgrade handler for `/ws/events`.
+pub async fn events_ws_handler(
+    ws: WebSocketUpgrade,
+    Extension(state): Extension<Arc<WebSocketState>>,
+) -> Response {
+    match state.try_connect() {
+        Some(slot) => ws
+            .on_upgrade(move |socket| handle_event_socket(socket, state, slot))
+            .into_response(),
+        None => too_many_connections(&state),
+    }
+}
+
+/// Serve one `/ws/events` connection until the client leaves, stops
+/// answering pings or falls behind its send buffer.
+async fn handle_event_socket(socket: WebSocket, state: Arc<WebSocketState>, _slot: ConnectionSlot) {
+    let mut rx = state.tx.subscribe();
+    let (sink, mut stream) = socket.split();
+    let sender = ClientSender::spawn(sink, state.limits.send_buffer);
+    let mut subscribed: BTreeSet<&'static str> = BTreeSet::new();
+    let mut keepalive = tokio::time::interval(EVENTS_PING_INTERVAL);
+    keepalive.reset();
+    let mut awaiting_pong = false;
+
+    let outcome = loop {
+        tokio::select! {
+            incoming = stream.next() => {
+                let Some(Ok(frame)) = incoming else {
+                    break Ok(());
+                };
+                // Any frame, including a pong, shows the client is alive.
+                awaiting_pong = false;
+                match frame {
+                    Message::Text(text) => {
+                        let reply = handle_event_control(&text, &mut subscribed);
+                        if let Err(e) = sender.send_json(&reply) {
+                            break Err(e);
+                        }
+                    }
+                    Message::Close(_) => break Ok(()),
+                    _ => {}
+                }
+            }
+            event = rx.recv() => match event {
+                Ok(message) => {
+                    let wanted = message
+                        .event_type()
+                        .is_some_and(|kind| subscribed.contains(kind));
+                    if wanted {
+                        if let Err(e) = sender.send_json(&message) {
+                            break Err(e);
+                        }
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => break Err(QueueError::Full),
+                Err(broadcast::error::RecvError::Closed) => break Ok(()),
+            },
+            _ = keepalive.tick() => {
+                if awaiting_pong {
+                    info!("WebSocket events client missed a ping; closing");
+                    break Ok(());
+                }
+                awaiting_pong = true;
+                if let Err(e) = sender.send(Message::Ping(Vec::new())) {
+                    break Err(e);
                 }
-                _ => {}
             }
         }
-    });
-
-    // Spawn task to send messages to client.
-    let send_task = tokio::spawn(async move {
-        let mut sender = sender.lock().await;
-        while let Ok(message) = rx.recv().await {
-            let text = serde_json::to_string(&message).unwrap();
-            if sender.send(Message::Text(text)).await.is_err() {
-                break;
+    };
+
+    sender.finish(outcome, &state).await;
+}
+
+/// Why a frame could not be queued for a client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QueueError {
+    /// The client's send buffer is full.
+    Full,
+    /// The writer stopped after a socket error.
+    Closed,
+}
+
+/// Outgoing side of one connection.
+///
+/// Frames are queued for a writer task rather than written inline, so a
+/// client that stops reading cannot stall its connection loop or hold the
+/// broadcast backlog. When the queue is full the client is dropped.
+struct ClientSender {
+    queue: mpsc::Sender<Message>,
+    writer: JoinHandle<()>,
+}
+
+impl ClientSender {
+    fn spawn(mut sink: SplitSink<WebSocket, Message>, send_buffer: usize) -> Self {
+        let (queue, mut frames) = mpsc::channel::<Message>(send_buffer.max(1));
+        let writer = tokio::spawn(async move {
+            while let Some(frame) = frames.recv().await {
+                let closing = matches!(frame, Message::Close(_));
+                if sink.send(frame).await.is_err() || closing {
+                    return;
+                }
+            }
+            let _ = sink.close().await;
+        });
+        Self { queue, writer }
+    }
+
+    fn send(&self, frame: Message) -> Result<(), QueueError> {
+        self.queue.try_send(frame).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(_) => QueueError::Full,
+            mpsc::error::TrySendError::Closed(_) => QueueError::Closed,
+        })
+    }
+
+    fn send_json<T: serde::Serialize>(&self, value: &T) -> Result<(), QueueError> {
+        match serde_json::to_string(value) {
+            Ok(text) => self.send(Message::Text(text)),
+            Err(e) => {
+                warn!("Dropping unserializable WebSocket frame: {}", e);
+                Ok(())
             }
         }
-    });
+    }
 
-    // Wait for either task to complete.
-    tokio::select! {
-        _ = recv_task => {},
-        _ = send_task => {},
+    /// Close the connection. A slow client gets a policy close frame if it
+    /// makes room for one within [`CLOSE_TIMEOUT`]; the writer is aborted if
+    /// it cannot finish in that time either.
+    async fn finish(self, outcome: Result<(), QueueError>, state: &WebSocketState) {
+        let Self { queue, mut writer } = self;
+        if outcome == Err(QueueError::Full) {
+            state.slow_disconnects.fetch_add(1, Ordering::SeqCst);
+            info!(
+                "WebSocket client fell more than {} frames behind; closing",
+                state.limits.send_buffer
+            );
+            let close = Message::Close(Some(CloseFrame {
+                code: close_code::POLICY,
+                reason: "client too slow".into(),
+            }));
+            let _ = tokio::time::timeout(CLOSE_TIMEOUT, queue.send(close)).await;
+        }
+        drop(queue);
+        if tokio::time::timeout(CLOSE_TIMEOUT, &mut writer)
+            .await
+            .is_err()
+        {
+            writer.abort();
+        }
     }
+}
+
+/// Apply one client control frame and build the reply.
+fn handle_event_control(text: &str, subscribed: &mut BTreeSet<&'static str>) -> EventControlReply {
+    let message = match serde_json::from_str::<EventClientMessage>(text) {
+        Ok(message) => message,
+        Err(e) => {
+            return EventControlReply::Error {
+                message: format!("invalid control frame: {}", e),
+            }
+        }
+    };
+    let (events, subscribe) = match message {
+        EventClientMessage::Ping => return EventControlReply::Pong,
+        EventClientMessage::Subscribe { events } => (events, true),
+        EventClientMessage::Unsubscribe { events } => (events, false),
+    };
 
-    state.decrement_client_count().await;
+    let mut kinds = Vec::with_capacity(events.len());
+    for name in &events {
+        match EVENT_TYPES.iter().find(|kind| **kind == name.trim()) {
+            Some(kind) => kinds.push(*kind),
+            None => {
+                return EventControlReply::Error {
+                    message: format!(
+                        "unknown event type '{}' (available: {})",
+                        name,
+                        EVENT_TYPES.join(", ")
+                    ),
+                }
+            }
+        }
+    }
+    for kind in kinds {
+        if subscribe {
+            subscribed.insert(kind);
+        } else {
+            subscribed.remove(kind);
+        }
+    }
+    EventControlReply::Subscribed {
+        events: subscribed.iter().map(|kind| kind.to_string()).collect(),
+    }
 }
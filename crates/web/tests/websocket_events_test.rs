@@ -0,0 +1,242 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::{extract::Extension, routing::get, Router};
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio_tungstenite::tungstenite::Message;
+use tower::util::ServiceExt;
+use xenobot_api::events::ImportProgressEvent;
+use xenobot_core::config::DatabaseConfig;
+use xenobot_core::webhook::WebhookMessageCreatedEvent;
+use xenobot_web::integration::spawn_message_created_forwarder;
+use xenobot_web::websocket::{
+    events_ws_handler, ws_handler, WebSocketLimits, WebSocketMessage, WebSocketState,
+};
+
+async fn next_json<S>(ws: &mut S) -> Value
+where
+    S: StreamExt<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    loop {
+        let frame = tokio::time::timeout(Duration::from_secs(5), ws.next())
+            .await
+            .expect("frame before timeout")
+            .expect("socket open")
+            .expect("valid frame");
+        if let Message::Text(text) = frame {
+            return serde_json::from_str(&text).expect("json frame");
+        }
+    }
+}
+
+#[tokio::test]
+async fn events_socket_delivers_only_subscribed_event_types(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let state = Arc::new(WebSocketState::new());
+    let app = Router::new()
+        .route("/ws/events", get(events_ws_handler))
+        .layer(Extension(state.clone()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws/events", addr)).await?;
+    ws.send(Message::Text(
+        json!({"type": "subscribe", "data": {"events": ["import.progress"]}}).to_string(),
+    ))
+    .await?;
+    let ack = next_json(&mut ws).await;
+    assert_eq!(ack["type"], "subscribed");
+    assert_eq!(ack["data"]["events"], json!(["import.progress"]));
+
+    // Not subscribed: must not reach the client.
+    state
+        .broadcast(WebSocketMessage::MessageCreated(
+            WebhookMessageCreatedEvent {
+                event_type: "message.created".to_string(),
+                platform: "telegram".to_string(),
+                chat_name: "Team".to_string(),
+                meta_id: 1,
+                message_id: 1,
+                sender_id: 1,
+                sender_name: None,
+                ts: 1_700_000_000,
+                msg_type: 0,
+                content: Some("hi".to_string()),
+            },
+        ))
+        .await?;
+    state
+        .broadcast(WebSocketMessage::ImportProgress(ImportProgressEvent {
+            total: 10,
+            processed: 4,
+            inserted: 3,
+            duplicates: 1,
+            current_file: Some("chat.txt".to_string()),
+            status: "importing".to_string(),
+            error: None,
+        }))
+        .await?;
+
+    let event = next_json(&mut ws).await;
+    assert_eq!(event["type"], "import.progress");
+    assert_eq!(event["data"]["processed"], 4);
+    assert_eq!(event["data"]["currentFile"], "chat.txt");
+
+    ws.send(Message::Text(json!({"type": "ping"}).to_string()))
+        .await?;
+    assert_eq!(next_json(&mut ws).await["type"], "pong");
+
+    ws.send(Message::Text(
+        json!({"type": "subscribe", "data": {"events": ["import.nope"]}}).to_string(),
+    ))
+    .await?;
+    assert_eq!(next_json(&mut ws).await["type"], "error");
+
+    ws.close(None).await?;
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while state.metrics().active_connections > 0 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn events_socket_receives_message_created_from_api_import(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let test_root = std::env::temp_dir().join(format!(
+        "xenobot_web_events_{}_{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    ));
+    std::fs::create_dir_all(&test_root)?;
+    let db_config = DatabaseConfig {
+        sqlite_path: test_root.join("events.db"),
+        ..DatabaseConfig::default()
+    };
+    xenobot_api::database::init_database_with_config(&db_config).await?;
+
+    let state = Arc::new(WebSocketState::new());
+    let _forwarder = spawn_message_created_forwarder(state.clone());
+    let app = Router::new()
+        .route("/ws/events", get(events_ws_handler))
+        .layer(Extension(state.clone()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws/events", addr)).await?;
+    ws.send(Message::Text(
+        json!({"type": "subscribe", "data": {"events": ["message.created"]}}).to_string(),
+    ))
+    .await?;
+    assert_eq!(next_json(&mut ws).await["type"], "subscribed");
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/import")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::to_vec(&json!({
+            "content": "[01/02/2025, 10:00:00] Alice: Launch checklist is green.\n",
+            "platform": "whatsapp",
+            "chat_name": "Launch Room"
+        }))?))?;
+    let response = xenobot_api::chat::router().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let event = next_json(&mut ws).await;
+    assert_eq!(event["type"], "message.created");
+    assert_eq!(event["data"]["platform"], "whatsapp");
+    assert_eq!(event["data"]["chat_name"], "Launch Room");
+    assert_eq!(event["data"]["content"], "Launch checklist is green.");
+
+    let _ = std::fs::remove_dir_all(&test_root);
+    Ok(())
+}
+
+#[tokio::test]
+async fn slow_client_is_dropped_without_affecting_fast_clients(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let state = Arc::new(WebSocketState::with_limits(WebSocketLimits {
+        max_connections: 2,
+        send_buffer: 4,
+    }));
+    let app = Router::new()
+        .route("/ws", get(ws_handler))
+        .layer(Extension(state.clone()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    let url = format!("ws://{}/ws", addr);
+
+    let (mut fast, _) = tokio_tungstenite::connect_async(&url).await?;
+    let (mut slow, _) = tokio_tungstenite::connect_async(&url).await?;
+    match tokio_tungstenite::connect_async(&url).await {
+        Err(tokio_tungstenite::tungstenite::Error::Http(response)) => {
+            assert_eq!(response.status(), 503)
+        }
+        other => panic!(
+            "third connection should be refused: {:?}",
+            other.map(|_| ())
+        ),
+    }
+
+    // The slow client never reads, so once the socket buffers fill its
+    // queue backs up; the fast client reads every frame as it arrives.
+    let payload = "x".repeat(64 * 1024);
+    let mut sent = 0;
+    while state.metrics().slow_disconnects == 0 {
+        assert!(sent < 2_000, "slow client was never disconnected");
+        state
+            .broadcast(WebSocketMessage::SystemNotification {
+                title: format!("n{}", sent),
+                message: payload.clone(),
+                level: "info".to_string(),
+            })
+            .await?;
+        let frame = next_json(&mut fast).await;
+        assert_eq!(frame["data"]["title"], format!("n{}", sent));
+        sent += 1;
+    }
+
+    state
+        .broadcast(WebSocketMessage::SystemNotification {
+            title: "after".to_string(),
+            message: "still here".to_string(),
+            level: "info".to_string(),
+        })
+        .await?;
+    assert_eq!(next_json(&mut fast).await["data"]["title"], "after");
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while state.metrics().active_connections > 1 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await?;
+
+    // Draining the slow socket ends in a close frame or a dropped connection.
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(Ok(frame)) = slow.next().await {
+            if let Message::Close(_) = frame {
+                break;
+            }
+        }
+    })
+    .await?;
+    Ok(())
+}
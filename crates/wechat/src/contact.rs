@@ -0,0 +1,283 @@
+//! Contact resolution against a decrypted WeChat contact database.
+//!
+//! Group messages only carry the sender's wxid. The contact tables map a wxid
+//! to the user's remark or nickname, and the chat-room table carries the
+//! per-room nickname members chose for themselves.
+
+use crate::error::WeChatResult;
+use rusqlite::{Connection, OpenFlags};
+use std::collections::HashMap;
+use std::path::Path;
+use xenobot_analysis::parsers::ParsedChat;
+
+/// Separator WeChat uses inside `ChatRoom.UserNameList` / `DisplayNameList`.
+const ROOM_LIST_SEPARATOR: char = '\u{7}';
+
+/// wxid → name mapping loaded once from a decrypted contact database.
+#[derive(Debug, Clone, Default)]
+pub struct ContactDirectory {
+    display_names: HashMap<String, String>,
+    room_nicknames: HashMap<(String, String), String>,
+}
+
+impl ContactDirectory {
+    /// Read contacts and chat-room nicknames from a decrypted contact database
+    /// (`MicroMsg.db` for v3, `contact.db` for v4). Missing tables are skipped.
+    pub fn load(path: &Path) -> WeChatResult<Self> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let mut directory = Self::default();
+
+        if table_exists(&conn, "Contact")? {
+            directory.load_contacts(&conn, "SELECT UserName, Remark, NickName FROM Contact")?;
+        } else if table_exists(&conn, "contact")? {
+            directory.load_contacts(&conn, "SELECT username, remark, nick_name FROM contact")?;
+        }
+        if table_exists(&conn, "ChatRoom")? {
+            directory.load_room_nicknames(&conn)?;
+        }
+
+        Ok(directory)
+    }
+
+    fn load_contacts(&mut self, conn: &Connection, sql: &str) -> WeChatResult<()> {
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (wxid, remark, nickname) = row?;
+            let Some(wxid) = non_empty(wxid) else {
+                continue;
+            };
+            // A remark is the name the account owner chose, so it wins over the nickname.
+            if let Some(name) = non_empty(remark).or_else(|| non_empty(nickname)) {
+                self.display_names.insert(wxid, name);
+            }
+        }
+        Ok(())
+    }
+
+    fn load_room_nicknames(&mut self, conn: &Connection) -> WeChatResult<()> {
+        let mut stmt =
+            conn.prepare("SELECT ChatRoomName, UserNameList, DisplayNameList FROM ChatRoom")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (room, members, nicknames) = row?;
+            let (Some(room), Some(members), Some(nicknames)) = (room, members, nicknames) else {
+                continue;
+            };
+            for (wxid, nickname) in members
+                .split(ROOM_LIST_SEPARATOR)
+                .zip(nicknames.split(ROOM_LIST_SEPARATOR))
+            {
+                let (wxid, nickname) = (wxid.trim(), nickname.trim());
+                if !wxid.is_empty() && !nickname.is_empty() {
+                    self.room_nicknames
+                        .insert((room.clone(), wxid.to_string()), nickname.to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of contacts with a known display name.
+    pub fn len(&self) -> usize {
+        self.display_names.len()
+    }
+
+    /// Whether no contact names were loaded.
+    pub fn is_empty(&self) -> bool {
+        self.display_names.is_empty() && self.room_nicknames.is_empty()
+    }
+
+    /// Display name (remark, else nickname) for a wxid.
+    pub fn display_name(&self, wxid: &str) -> Option<&str> {
+        self.display_names.get(wxid).map(String::as_str)
+    }
+
+    /// Nickname a member set for themselves inside one chat room.
+    pub fn room_nickname(&self, room: &str, wxid: &str) -> Option<&str> {
+        self.room_nicknames
+            .get(&(room.to_string(), wxid.to_string()))
+            .map(String::as_str)
+    }
+
+    /// Best name for a sender: room nickname, then display name, then the raw wxid.
+    pub fn resolve<'a>(&'a self, room: Option<&str>, wxid: &'a str) -> &'a str {
+        room.and_then(|room| self.room_nickname(room, wxid))
+            .or_else(|| self.display_name(wxid))
+            .unwrap_or(wxid)
+    }
+
+    /// Fill sender and member names in a parsed WeChat chat.
+    ///
+    /// Only names that are missing or just repeat the wxid are replaced; they
+    /// get the account display name, falling back to the room nickname. The
+    /// room nickname itself goes to `sender_group_nickname`. When `room` is
+    /// `None`, the chat name is used if it is a `@chatroom` id.
+    pub fn apply_to_chat(&self, chat: &mut ParsedChat, room: Option<&str>) {
+        let room = room.map(str::to_string).or_else(|| {
+            chat.chat_name
+                .ends_with("@chatroom")
+                .then(|| chat.chat_name.clone())
+        });
+        let room = room.as_deref();
+
+        for message in &mut chat.messages {
+            let opaque = message.sender_name.as_deref().map_or(true, |name| {
+                name.trim().is_empty() || name == message.sender
+            });
+            let nickname = room.and_then(|room| self.room_nickname(room, &message.sender));
+            if opaque {
+                let name = self
+                    .display_name(&message.sender)
+                    .or(nickname)
+                    .unwrap_or(&message.sender);
+                message.sender_name = Some(name.to_string());
+            }
+            if message.sender_group_nickname.is_none() {
+                message.sender_group_nickname = nickname
+                    .filter(|nickname| message.sender_name.as_deref() != Some(*nickname))
+                    .map(str::to_string);
+            }
+        }
+        for member in &mut chat.members {
+            if member.display_name.is_none() {
+                if let Some(nickname) = room.and_then(|room| self.room_nickname(room, &member.id)) {
+                    member.display_name = Some(nickname.to_string());
+                }
+            }
+            let opaque = member
+                .name
+                .as_deref()
+                .map_or(true, |name| name.trim().is_empty() || name == member.id);
+            if opaque {
+                if let Some(name) = self.display_name(&member.id) {
+                    member.name = Some(name.to_string());
+                }
+            }
+        }
+    }
+}
+
+fn table_exists(conn: &Connection, name: &str) -> WeChatResult<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        [name],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use xenobot_analysis::parsers::{ChatMember, ChatType, MessageType, ParsedMessage};
+
+    fn fixture_contact_db(dir: &Path) -> std::path::PathBuf {
+        let path = dir.join("MicroMsg.db");
+        let conn = Connection::open(&path).expect("create contact fixture");
+        conn.execute_batch(
+            "CREATE TABLE Contact (UserName TEXT, Alias TEXT, Remark TEXT, NickName TEXT);
+             INSERT INTO Contact VALUES ('wxid_alice', '', '', 'Alice Zhang');
+             INSERT INTO Contact VALUES ('wxid_bob', 'bob88', 'Bob (work)', 'bobby');
+             CREATE TABLE ChatRoom (ChatRoomName TEXT, UserNameList TEXT, DisplayNameList TEXT);",
+        )
+        .expect("seed contacts");
+        conn.execute(
+            "INSERT INTO ChatRoom VALUES ('launch@chatroom', ?1, ?2)",
+            ["wxid_alice\u{7}wxid_bob", "Launch Lead\u{7}"],
+        )
+        .expect("seed chat room");
+        path
+    }
+
+    fn message(sender: &str, sender_name: Option<&str>) -> ParsedMessage {
+        ParsedMessage {
+            sender: sender.to_string(),
+            sender_name: sender_name.map(str::to_string),
+            sender_group_nickname: None,
+            timestamp: 1_735_813_230,
+            content: "hello".to_string(),
+            msg_type: MessageType::Text,
+            edited_at: None,
+            platform_message_id: None,
+        }
+    }
+
+    #[test]
+    fn resolves_wxid_to_room_nickname_then_display_name() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let contacts = ContactDirectory::load(&fixture_contact_db(dir.path())).expect("load");
+
+        assert_eq!(contacts.display_name("wxid_alice"), Some("Alice Zhang"));
+        assert_eq!(contacts.display_name("wxid_bob"), Some("Bob (work)"));
+        assert_eq!(
+            contacts.resolve(Some("launch@chatroom"), "wxid_alice"),
+            "Launch Lead"
+        );
+        assert_eq!(
+            contacts.resolve(Some("launch@chatroom"), "wxid_bob"),
+            "Bob (work)"
+        );
+        assert_eq!(contacts.resolve(None, "wxid_unknown"), "wxid_unknown");
+
+        let mut chat = ParsedChat {
+            platform: "wechat".to_string(),
+            chat_name: "launch@chatroom".to_string(),
+            chat_type: ChatType::Group,
+            messages: vec![
+                message("wxid_alice", None),
+                message("wxid_bob", Some("wxid_bob")),
+                message("wxid_carol", None),
+                message("wxid_alice", Some("Alice (export)")),
+            ],
+            members: vec![ChatMember {
+                id: "wxid_alice".to_string(),
+                name: None,
+                display_name: None,
+            }],
+            encoding: None,
+            salvage: None,
+            chat_type_known: false,
+        };
+        contacts.apply_to_chat(&mut chat, None);
+
+        let names: Vec<_> = chat
+            .messages
+            .iter()
+            .map(|msg| msg.sender_name.as_deref().unwrap_or_default())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["Alice Zhang", "Bob (work)", "wxid_carol", "Alice (export)"]
+        );
+        let nicknames: Vec<_> = chat
+            .messages
+            .iter()
+            .map(|msg| msg.sender_group_nickname.as_deref())
+            .collect();
+        assert_eq!(
+            nicknames,
+            vec![Some("Launch Lead"), None, None, Some("Launch Lead")]
+        );
+        assert_eq!(chat.members[0].name.as_deref(), Some("Alice Zhang"));
+        assert_eq!(chat.members[0].display_name.as_deref(), Some("Launch Lead"));
+    }
+}
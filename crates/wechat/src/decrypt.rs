@@ -7,10 +7,16 @@ use pbkdf2::pbkdf2;
 use sha2::Sha512;
 use std::fs::File;
 use std::io::{Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 type Aes256CbcDec = cbc::Decryptor<aes::Aes256>;
 
+/// Magic header every valid SQLite database file starts with.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Directory (next to the decrypted output) that receives failed outputs.
+pub const QUARANTINE_DIR_NAME: &str = "quarantine";
+
 /// V4 database decryption parameters.
 #[derive(Debug, Clone)]
 pub struct V4DecryptionParams {
@@ -102,6 +108,72 @@ pub fn decrypt_v4_database(
     Ok(())
 }
 
+/// Decrypt a WeChat V4 database file and verify the decrypted output.
+///
+/// Outputs that fail [`verify_decrypted_database`] are moved into a
+/// `quarantine/` folder next to the output so they are never fed to parsers.
+pub fn decrypt_and_verify_v4_database(
+    input_path: &Path,
+    output_path: &Path,
+    params: &V4DecryptionParams,
+) -> WeChatResult<()> {
+    decrypt_v4_database(input_path, output_path, params)?;
+
+    if let Err(reason) = verify_decrypted_database(output_path) {
+        let quarantine_path = quarantine_decrypted_database(output_path).ok();
+        tracing::warn!(
+            "Decrypted database {:?} failed integrity check: {}",
+            output_path,
+            reason
+        );
+        return Err(WeChatError::IntegrityCheck {
+            path: output_path.to_path_buf(),
+            quarantine_path,
+            reason,
+        });
+    }
+
+    Ok(())
+}
+
+/// Verify that a decrypted file is a readable SQLite database.
+///
+/// Checks the SQLite header, opens the file read-only and runs
+/// `PRAGMA integrity_check`. Returns a short failure reason on error.
+pub fn verify_decrypted_database(path: &Path) -> Result<(), String> {
+    let mut header = [0u8; 16];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map_err(|e| format!("unreadable header: {}", e))?;
+    if &header != SQLITE_HEADER {
+        return Err("missing SQLite header".to_string());
+    }
+
+    let conn = rusqlite::Connection::open_with_flags(
+        path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| format!("open failed: {}", e))?;
+    let result: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| format!("integrity_check failed: {}", e))?;
+    if result != "ok" {
+        return Err(format!("integrity_check reported: {}", result));
+    }
+
+    Ok(())
+}
+
+/// Move a decrypted database into the sibling quarantine folder.
+pub fn quarantine_decrypted_database(path: &Path) -> WeChatResult<PathBuf> {
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let quarantine_dir = parent.join(QUARANTINE_DIR_NAME);
+    std::fs::create_dir_all(&quarantine_dir).map_err(WeChatError::Io)?;
+    let target = quarantine_dir.join(path.file_name().unwrap_or_default());
+    std::fs::rename(path, &target).map_err(WeChatError::Io)?;
+    Ok(target)
+}
+
 /// Process a single V4 database page.
 fn process_v4_page(
     page: &[u8],
@@ -288,4 +360,41 @@ mod tests {
         let valid = validate_v4_key(&path, &[0x11; 32], &[0x22; 16]).expect("validation result");
         assert!(!valid);
     }
+
+    #[test]
+    fn verify_decrypted_database_accepts_valid_sqlite_file() {
+        let temp_dir = tempdir().expect("temp dir");
+        let path = temp_dir.path().join("decrypted-ok.db");
+        let conn = rusqlite::Connection::open(&path).expect("create sqlite fixture");
+        conn.execute_batch("CREATE TABLE msg (id INTEGER PRIMARY KEY, content TEXT);")
+            .expect("create table");
+        drop(conn);
+
+        assert!(verify_decrypted_database(&path).is_ok());
+    }
+
+    #[test]
+    fn corrupted_decrypted_database_is_flagged_and_quarantined() {
+        let temp_dir = tempdir().expect("temp dir");
+        let path = temp_dir.path().join("decrypted-bad.db");
+        let mut payload = SQLITE_HEADER.to_vec();
+        payload.extend_from_slice(&[0xa5; 4080]);
+        fs::write(&path, payload).expect("write corrupted fixture");
+
+        let reason = verify_decrypted_database(&path).expect_err("corrupted db should fail");
+        assert!(!reason.is_empty());
+
+        let no_header = temp_dir.path().join("decrypted-no-header.db");
+        fs::write(&no_header, vec![0u8; 4096]).expect("write headerless fixture");
+        let reason = verify_decrypted_database(&no_header).expect_err("headerless db should fail");
+        assert!(reason.contains("SQLite header"));
+
+        let quarantined = quarantine_decrypted_database(&path).expect("quarantine file");
+        assert!(!path.exists());
+        assert!(quarantined.exists());
+        assert_eq!(
+            quarantined.parent().and_then(|p| p.file_name()),
+            Some(std::ffi::OsStr::new(QUARANTINE_DIR_NAME))
+        );
+    }
 }
@@ -54,6 +54,17 @@ pub enum WeChatError {
         actual: String,
     },
 
+    /// Decrypted database failed the post-decrypt integrity check and was quarantined.
+    #[error("decrypted database failed integrity check ({reason}): {path}")]
+    IntegrityCheck {
+        /// Decrypted output that failed verification.
+        path: std::path::PathBuf,
+        /// Where the failed output was moved, if quarantine succeeded.
+        quarantine_path: Option<std::path::PathBuf>,
+        /// Short description of the failed check.
+        reason: String,
+    },
+
     /// SQLite database error
     #[error("Database error: {0}")]
     Database(#[from] rusqlite::Error),
@@ -14,11 +14,13 @@
 pub mod account;
 pub mod audio;
 pub mod config;
+pub mod contact;
 pub mod decrypt;
 pub mod error;
 pub mod media;
 pub mod monitor;
 pub mod service;
 
+pub use contact::ContactDirectory;
 pub use error::{WeChatError, WeChatResult};
 pub use service::{AuthorizedWeChatWorkspace, StagedWeChatExport, WeChatService};
@@ -5,7 +5,8 @@ use crate::audio::{
     has_ffmpeg, transcode_audio_bytes_to_mp3, transcode_audio_to_mp3, AudioTranscodeOptions,
 };
 use crate::config::WeChatConfig;
-use crate::decrypt::{decrypt_v4_database, V4DecryptionParams};
+use crate::contact::ContactDirectory;
+use crate::decrypt::{decrypt_and_verify_v4_database, V4DecryptionParams};
 use crate::error::{WeChatError, WeChatResult};
 use crate::media::{collect_media_assets, WeChatMediaAsset};
 use crate::monitor::{FileMonitor, FileMonitorConfig};
@@ -81,6 +82,7 @@ pub struct WeChatService {
     event_rx: mpsc::Receiver<ServiceEvent>,
     accounts: Arc<DashMap<u32, Account>>,
     keys: Arc<DashMap<u32, WeChatResolvedKeys>>, // (data_key, img_key) per PID
+    contacts: Option<Arc<ContactDirectory>>,
 }
 
 /// Service events.
@@ -139,6 +141,7 @@ impl WeChatService {
             event_rx,
             accounts: Arc::new(DashMap::new()),
             keys: Arc::new(DashMap::new()),
+            contacts: None,
         }
     }
 
@@ -177,14 +180,34 @@ impl WeChatService {
         self.config.add_authorized_root(path);
     }
 
+    /// Load a decrypted contact database used to resolve sender wxids to names.
+    ///
+    /// The mapping is cached for the lifetime of the service and applied to every
+    /// export parsed afterwards. Returns the number of contacts with a name.
+    pub fn load_contact_database(&mut self, path: &Path) -> WeChatResult<usize> {
+        self.ensure_authorized(path)?;
+        let contacts = ContactDirectory::load(path)?;
+        let count = contacts.len();
+        self.contacts = Some(Arc::new(contacts));
+        Ok(count)
+    }
+
+    /// Contact mapping loaded by [`Self::load_contact_database`], if any.
+    pub fn contacts(&self) -> Option<&ContactDirectory> {
+        self.contacts.as_deref()
+    }
+
     /// Parse one explicitly authorized export file.
     pub fn parse_authorized_export(&self, path: &Path) -> Result<ParsedChat, WeChatError> {
         self.ensure_authorized(path)?;
 
         let registry = ParserRegistry::new();
-        let parsed = registry.detect_and_parse(path)?;
+        let mut parsed = registry.detect_and_parse(path)?;
 
         if parsed.platform.eq_ignore_ascii_case(Self::PLATFORM_ID) {
+            if let Some(contacts) = self.contacts.as_deref() {
+                contacts.apply_to_chat(&mut parsed, None);
+            }
             Ok(parsed)
         } else {
             Err(WeChatError::PlatformMismatch {
@@ -522,7 +545,7 @@ impl WeChatService {
         let params = V4DecryptionParams::new(data_key, img_key, salt);
 
         // Decrypt
-        match decrypt_v4_database(&input_path, &output_path, &params) {
+        match decrypt_and_verify_v4_database(&input_path, &output_path, &params) {
             Ok(()) => {
                 let event = ServiceEvent::DecryptionComplete {
                     input_path: input_path.clone(),
@@ -659,7 +682,7 @@ impl WeChatService {
                     let salt =
                         crate::decrypt::extract_v4_salt(&input_path).map_err(|e| e.to_string())?;
                     let params = V4DecryptionParams::new(data_key, img_key, salt);
-                    decrypt_v4_database(&input_path, &output_path_for_job, &params)
+                    decrypt_and_verify_v4_database(&input_path, &output_path_for_job, &params)
                         .map_err(|e| e.to_string())
                 })
                 .await;
@@ -765,6 +788,7 @@ impl Clone for WeChatService {
             event_rx,
             accounts: Arc::new(DashMap::new()),
             keys: Arc::new(DashMap::new()),
+            contacts: self.contacts.clone(),
         }
     }
 }